@@ -1,27 +1,43 @@
+use std::cmp;
 use std::collections::HashMap;
-use std::fs::File;
+use std::fs::{File, OpenOptions, TryLockError};
 use std::io;
+use std::io::{IsTerminal, Read, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output, Stdio};
-use anyhow::anyhow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use bzip2::read::MultiBzDecoder;
 use flate2::read::GzDecoder;
+use indicatif::{ProgressBar, ProgressStyle};
+use reqwest::header::{HeaderMap, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
 use reqwest::redirect::Policy;
-use tar::Archive;
+use reqwest::StatusCode;
+use lzma_rust2::XzReader;
+use sha2::{Digest, Sha256};
+use tar::{Archive, EntryType};
 use zip::ZipArchive;
+use crate::error::{Error, Result};
+use crate::http::{connect_timeout, http_client_builder, http_get, rewrite_url};
 
-pub fn jbang_home() -> PathBuf {
+/// home directory of the current user
+pub fn home_dir() -> Result<PathBuf> {
+    dirs::home_dir().ok_or_else(|| Error::Config("Home directory of the current user not found".to_owned()))
+}
+
+pub fn jbang_home() -> Result<PathBuf> {
     if let Ok(jbang_home) = std::env::var("JBANG_DIR") {
-        PathBuf::from(jbang_home)
+        Ok(PathBuf::from(jbang_home))
     } else {
-        dirs::home_dir().unwrap().join(".jbang")
+        Ok(home_dir()?.join(".jbang"))
     }
 }
 
-pub fn sdkman_home() -> PathBuf {
+pub fn sdkman_home() -> Result<PathBuf> {
     if let Ok(jbang_home) = std::env::var("SDKMAN_DIR") {
-        PathBuf::from(jbang_home)
+        Ok(PathBuf::from(jbang_home))
     } else {
-        dirs::home_dir().unwrap().join(".sdkman")
+        Ok(home_dir()?.join(".sdkman"))
     }
 }
 
@@ -36,107 +52,682 @@ pub fn is_java_home(java_home: &PathBuf) -> bool {
     }
 }
 
-pub fn http_text(http_url: &str) -> String {
-    let resp = reqwest::blocking::get(http_url).unwrap();
-    if resp.status().is_success() {
-        resp.text().unwrap()
-    } else {
-        "".to_owned()
+/// JDK found by `discover_jdks`
+#[derive(Debug, Clone, PartialEq)]
+pub struct JdkLocation {
+    /// JBang, SDKMAN, Gradle, IntelliJ, System, User or Homebrew
+    pub source: &'static str,
+    /// directory name, such as `21.0.3-tem`
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// JDKs from JBang, SDKMAN, Gradle, IntelliJ(`~/.jdks`) and system locations, `current` links are excluded
+pub fn discover_jdks() -> Result<Vec<JdkLocation>> {
+    let mut jdks = list_java_homes("JBang", &jbang_home()?.join("cache").join("jdks"));
+    jdks.extend(list_java_homes("SDKMAN", &sdkman_home()?.join("candidates").join("java")));
+    let home_dir = home_dir()?;
+    jdks.extend(list_java_homes("Gradle", &home_dir.join(".gradle").join("jdks")));
+    jdks.extend(list_java_homes("IntelliJ", &home_dir.join(".jdks")));
+    if cfg!(target_os = "macos") {
+        jdks.extend(list_java_homes("System", Path::new("/Library/Java/JavaVirtualMachines")));
+        jdks.extend(list_java_homes("User", &home_dir.join("Library").join("Java").join("JavaVirtualMachines")));
+        for cellar_dir in ["/opt/homebrew/Cellar", "/usr/local/Cellar"] {
+            let Ok(entries) = Path::new(cellar_dir).read_dir() else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                if entry.file_name().to_string_lossy().starts_with("openjdk") {
+                    jdks.extend(list_java_homes("Homebrew", &entry.path()));
+                }
+            }
+        }
+    } else if cfg!(target_os = "windows") {
+        jdks.extend(list_java_homes("System", Path::new("C:\\Program Files\\Java")));
+    } else if cfg!(target_os = "linux") {
+        jdks.extend(list_java_homes("System", Path::new("/usr/lib/jvm")));
+    }
+    Ok(jdks)
+}
+
+/// properties of `release` file in Java home, such as `JAVA_VERSION` and `IMPLEMENTOR`, quotes of values removed
+pub fn read_release(release_file: &Path) -> Result<HashMap<String, String>> {
+    let text = std::fs::read_to_string(release_file)?;
+    Ok(text.lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().trim_matches(['"', '\'']).to_string()))
+        .collect())
+}
+
+/// segments of version split by `.`, `-`, `_` and `+`, such as `["4", "0", "0", "rc", "1"]` for `4.0.0-rc-1`
+pub fn version_segments(version: &str) -> Vec<&str> {
+    version.split(['.', '-', '_', '+']).filter(|segment| !segment.is_empty()).collect()
+}
+
+/// compare versions segment by segment, numeric segments are compared as numbers,
+/// and a release is newer than its pre-releases: `23.0.1 > 23.ea.5`, `3.9.0 > 3.9.0-rc-1`
+pub fn compare_versions(a: &str, b: &str) -> cmp::Ordering {
+    let segments_a = version_segments(a);
+    let segments_b = version_segments(b);
+    for i in 0..segments_a.len().max(segments_b.len()) {
+        let ordering = match (segments_a.get(i), segments_b.get(i)) {
+            (Some(x), Some(y)) => match (x.parse::<u64>(), y.parse::<u64>()) {
+                (Ok(x), Ok(y)) => x.cmp(&y),
+                (Ok(_), Err(_)) => cmp::Ordering::Greater,
+                (Err(_), Ok(_)) => cmp::Ordering::Less,
+                (Err(_), Err(_)) => x.to_lowercase().cmp(&y.to_lowercase()),
+            },
+            // `3.9.0` < `3.9.0.1`, but `3.9.0` > `3.9.0-rc-1`
+            (None, Some(y)) => if y.parse::<u64>().is_ok() { cmp::Ordering::Less } else { cmp::Ordering::Greater },
+            (Some(x), None) => if x.parse::<u64>().is_ok() { cmp::Ordering::Greater } else { cmp::Ordering::Less },
+            (None, None) => cmp::Ordering::Equal,
+        };
+        if ordering != cmp::Ordering::Equal {
+            return ordering;
+        }
     }
+    cmp::Ordering::Equal
+}
+
+/// Java homes in the directory
+pub fn list_java_homes(source: &'static str, base_path: &Path) -> Vec<JdkLocation> {
+    let Ok(entries) = base_path.read_dir() else {
+        return vec![];
+    };
+    entries.flatten()
+        .filter(|entry| entry.file_name() != "current")
+        .map(|entry| (entry.file_name().to_string_lossy().to_string(), entry.path()))
+        .filter(|(_, child)| child.is_dir() && is_java_home(child))
+        .map(|(name, child)| JdkLocation { source, name, path: child })
+        .collect()
 }
 
-pub fn http_download<P: AsRef<Path>>(http_url: &str, target_file_path: P) {
-    let mut response = reqwest::blocking::get(http_url).unwrap();
-    let status_code = &response.status();
-    if status_code.is_success() {
-        if let Some(prefix) = target_file_path.as_ref().parent() {
-            std::fs::create_dir_all(prefix).unwrap();
+/// `.java-version` file and the version in it, shared by jenv and `sdk direnv`
+#[derive(Debug, Clone, PartialEq)]
+pub struct JavaVersionFile {
+    pub version: String,
+    pub path: PathBuf,
+}
+
+/// `.java-version` in `dir` or parent directories up to the filesystem root, or up to `stop_dir` if `dir` is in it.
+/// Blank files are skipped, and comments are ignored.
+pub fn find_java_version_file(dir: &Path, stop_dir: Option<&Path>) -> Option<JavaVersionFile> {
+    for current_dir in dir.ancestors() {
+        let path = current_dir.join(".java-version");
+        if let Ok(text) = std::fs::read_to_string(&path) {
+            let version = text.lines()
+                .map(|line| line.trim())
+                .find(|line| !line.is_empty() && !line.starts_with('#'));
+            if let Some(version) = version {
+                return Some(JavaVersionFile { version: version.to_string(), path });
+            }
         }
-        let mut dest = File::create(&target_file_path).unwrap();
-        response.copy_to(&mut dest).unwrap();
+        if stop_dir.is_some_and(|stop_dir| current_dir == stop_dir) {
+            break;
+        }
+    }
+    None
+}
+
+/// `.java-version` for `dir`, and the lookup stops at `$HOME` with `WUKONG_JAVA_VERSION_STOP_AT_HOME=true`
+pub fn resolve_java_version_file(dir: &Path) -> Option<JavaVersionFile> {
+    let stop_at_home = std::env::var("WUKONG_JAVA_VERSION_STOP_AT_HOME")
+        .is_ok_and(|value| value == "true" || value == "1");
+    let stop_dir = if stop_at_home { dirs::home_dir() } else { None };
+    find_java_version_file(dir, stop_dir.as_deref())
+}
+
+/// response text, `Error::Http` with the status for unsuccessful status
+pub fn http_text(http_url: &str) -> Result<String> {
+    let resp = http_get(http_url)?;
+    let status = resp.status();
+    if status.is_success() {
+        Ok(resp.text()?)
     } else {
-        eprintln!("Failed to download, status: {} , url: {}", status_code.as_u16(), http_url);
-        std::process::exit(1);
+        Err(Error::Http(status.as_u16(), http_url.to_owned()))
     }
 }
 
-pub fn get_redirect_url(http_url: &str) -> anyhow::Result<String> {
-    let client = reqwest::blocking::Client::builder().redirect(Policy::none()).build()?;
-    let response = client.get(http_url).send()?;
-    // Check if the response status is a redirect
-    if response.status().is_redirection() {
-        if let Some(location) = response.headers().get("Location") {
-            return Ok(location.to_str()?.to_string());
+/// options for `download_file`
+#[derive(Debug, Clone)]
+pub struct DownloadOptions {
+    /// retries after the first failed attempt
+    pub retries: u32,
+    /// delay before the first retry, doubled after each failed attempt
+    pub backoff: Duration,
+    /// display progress bar, only when stderr is a terminal and quiet mode is off
+    pub progress: bool,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        DownloadOptions {
+            retries: 3,
+            backoff: Duration::from_secs(1),
+            progress: true,
         }
     }
-    Err(anyhow!("Failed to get redirect url: {}", http_url))
 }
 
-pub fn extract_zip<P: AsRef<Path>>(archive_file_path: P, target_dir: &PathBuf, root_excluded: bool) {
-    let mut archive = ZipArchive::new(File::open(archive_file_path).unwrap()).unwrap();
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i).unwrap();
-        if file.is_file() {
-            let enclosed_name = file.enclosed_name().unwrap();
-            let mut relative_path = enclosed_name.to_str().unwrap();
-            if root_excluded {  // exclude root path
-                relative_path = &relative_path[(relative_path.find(std::path::MAIN_SEPARATOR).unwrap() + 1)..];
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// quiet mode, `-q`, disables progress and retry messages
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+pub fn http_download<P: AsRef<Path>>(http_url: &str, target_file_path: P) -> Result<()> {
+    download_file(http_url, target_file_path, &DownloadOptions::default())
+}
+
+/// download file with retries. Data is written to `<target>.part` first, and an existing `.part` file
+/// is resumed with a `Range` request, then renamed to the target file after the download completes.
+/// `ETag` or `Last-Modified` of the response is kept in `<target>.part.validator` and sent as `If-Range`
+/// on resume, so a changed file on the server is downloaded again instead of being appended to the old part.
+pub fn download_file<P: AsRef<Path>>(http_url: &str, target_file_path: P, options: &DownloadOptions) -> Result<()> {
+    let target_file_path = target_file_path.as_ref();
+    if let Some(prefix) = target_file_path.parent() {
+        std::fs::create_dir_all(prefix)?;
+    }
+    let part_file_path = get_part_file_path(target_file_path);
+    let mut attempt = 0;
+    loop {
+        match download_to_part_file(http_url, &part_file_path, options) {
+            Ok(_) => {
+                std::fs::rename(&part_file_path, target_file_path)?;
+                let _ = std::fs::remove_file(get_validator_file_path(&part_file_path));
+                return Ok(());
             }
-            let outpath = target_dir.join(relative_path);
-            if let Some(p) = outpath.parent() {
-                if !p.exists() {
-                    std::fs::create_dir_all(p).unwrap();
+            Err(e) => {
+                if !e.is_retryable() || attempt >= options.retries {
+                    return Err(e);
                 }
+                let delay = options.backoff * 2u32.pow(attempt);
+                if !is_quiet() {
+                    eprintln!("{}, retrying in {:.1}s...", e, delay.as_secs_f32());
+                }
+                std::thread::sleep(delay);
+                attempt += 1;
             }
-            let mut outfile = File::create(&outpath).unwrap();
-            std::io::copy(&mut file, &mut outfile).unwrap();
         }
     }
 }
 
-pub fn extract_tgz<P: AsRef<Path>>(archive_file_path: P, target_dir: &PathBuf, root_excluded: bool) {
-    let tgz_file = File::open(archive_file_path).unwrap();
-    let gz_decoder = GzDecoder::new(tgz_file);
-    let mut archive = Archive::new(gz_decoder);
-    archive
-        .entries().unwrap()
-        .filter_map(|e| e.ok())
-        .for_each(|mut entry| {
-            let entry_path = entry.path().unwrap();
-            let mut relative_path = entry_path.to_str().unwrap();
-            if root_excluded { // exclude root path
-                relative_path = &relative_path[(relative_path.find(std::path::MAIN_SEPARATOR).unwrap() + 1)..];
+pub fn get_part_file_path(target_file_path: &Path) -> PathBuf {
+    let mut file_name = target_file_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".part");
+    target_file_path.with_file_name(file_name)
+}
+
+/// `ETag` or `Last-Modified` of the part file, sent as `If-Range` on resume
+fn get_validator_file_path(part_file_path: &Path) -> PathBuf {
+    let mut file_name = part_file_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".validator");
+    part_file_path.with_file_name(file_name)
+}
+
+/// strong `ETag`, then `Last-Modified`, because a weak `ETag` is not allowed in `If-Range`
+fn get_validator(headers: &HeaderMap) -> Option<String> {
+    headers.get(ETAG)
+        .and_then(|value| value.to_str().ok())
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| headers.get(LAST_MODIFIED).and_then(|value| value.to_str().ok()))
+        .map(|validator| validator.to_string())
+}
+
+fn download_to_part_file(http_url: &str, part_file_path: &Path, options: &DownloadOptions) -> Result<()> {
+    let validator_file_path = get_validator_file_path(part_file_path);
+    // a part file without validator can't be checked against the file on the server, and is downloaded again
+    let validator = std::fs::read_to_string(&validator_file_path).ok()
+        .map(|validator| validator.trim().to_string())
+        .filter(|validator| !validator.is_empty());
+    let existing_len = match validator {
+        Some(_) => std::fs::metadata(part_file_path).map(|m| m.len()).unwrap_or(0),
+        None => 0,
+    };
+    // blocking client has a 30s timeout for the whole request by default, too short for JDK archives
+    let client = http_client_builder()?
+        .timeout(None)
+        .connect_timeout(connect_timeout().unwrap_or(Duration::from_secs(30)))
+        .build()?;
+    let mut request = client.get(rewrite_url(http_url));
+    if existing_len > 0 && let Some(validator) = &validator {
+        // the server sends the whole file with 200 if the validator doesn't match
+        request = request.header(RANGE, format!("bytes={}-", existing_len)).header(IF_RANGE, validator);
+    }
+    let mut response = request.send()?;
+    let status = response.status();
+    let (mut dest, downloaded) = if status == StatusCode::PARTIAL_CONTENT && existing_len > 0 {
+        (OpenOptions::new().append(true).open(part_file_path)?, existing_len)
+    } else if status.is_success() {
+        match get_validator(response.headers()) {
+            Some(validator) => std::fs::write(&validator_file_path, validator)?,
+            None => {
+                let _ = std::fs::remove_file(&validator_file_path);
             }
-            let path = target_dir.join(relative_path);
-            entry.unpack(&path).unwrap();
-        });
+        }
+        (File::create(part_file_path)?, 0)
+    } else if status == StatusCode::RANGE_NOT_SATISFIABLE && existing_len > 0 {
+        // `Content-Range: bytes */<total>`, the part file may be complete already
+        let total_len = response.headers().get(CONTENT_RANGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.rsplit('/').next())
+            .and_then(|total| total.parse::<u64>().ok());
+        if total_len == Some(existing_len) {
+            return Ok(());
+        }
+        std::fs::remove_file(part_file_path)?;
+        let _ = std::fs::remove_file(&validator_file_path);
+        return Err(incomplete_download(existing_len, total_len.unwrap_or(0)));
+    } else {
+        return Err(Error::Http(status.as_u16(), http_url.to_string()));
+    };
+    let total_len = response.content_length().map(|len| len + downloaded);
+    let progress_bar = if options.progress && !is_quiet() && io::stderr().is_terminal() {
+        let progress_bar = match total_len {
+            Some(len) => ProgressBar::new(len),
+            None => ProgressBar::no_length(),
+        };
+        progress_bar.set_style(
+            ProgressStyle::with_template("{bar:40.cyan/blue} {bytes}/{total_bytes} {bytes_per_sec} {eta}")
+                .unwrap_or_else(|_| ProgressStyle::default_bar())
+                .progress_chars("=> ")
+        );
+        progress_bar.set_position(downloaded);
+        Some(progress_bar)
+    } else {
+        None
+    };
+    let mut received = downloaded;
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let len = response.read(&mut buffer)?;
+        if len == 0 {
+            break;
+        }
+        dest.write_all(&buffer[..len])?;
+        received += len as u64;
+        if let Some(progress_bar) = &progress_bar {
+            progress_bar.set_position(received);
+        }
+    }
+    dest.flush()?;
+    if let Some(progress_bar) = &progress_bar {
+        progress_bar.finish_and_clear();
+    }
+    if let Some(total_len) = total_len && received != total_len {
+        return Err(incomplete_download(received, total_len));
+    }
+    Ok(())
+}
+
+fn incomplete_download(received: u64, expected: u64) -> Error {
+    Error::Network(format!("Incomplete download: {} of {} bytes", received, expected))
+}
+
+pub fn get_redirect_url(http_url: &str) -> Result<String> {
+    Ok(get_redirect(http_url)?.0)
+}
+
+/// redirect location and response headers, such as `X-Sdkman-Checksum-SHA-256` from SDKMAN broker
+pub fn get_redirect(http_url: &str) -> Result<(String, HeaderMap)> {
+    let client = http_client_builder()?.redirect(Policy::none()).build()?;
+    let response = client.get(rewrite_url(http_url)).send()?;
+    // Check if the response status is a redirect
+    if response.status().is_redirection() && let Some(location) = response.headers().get("Location") {
+        return Ok((location.to_str()?.to_string(), response.headers().clone()));
+    }
+    Err(Error::Http(response.status().as_u16(), http_url.to_string()))
+}
+
+/// verify SHA-256 checksum of file, `expected` is hex encoded
+pub fn verify_sha256(file_path: &Path, expected: &str) -> Result<()> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(file_path)?, &mut hasher)?;
+    let actual = format!("{:x}", hasher.finalize());
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(Error::Checksum {
+            file: file_path.to_path_buf(),
+            expected: expected.trim().to_string(),
+            actual,
+        })
+    }
 }
 
+/// advisory lock of an installation directory, released when dropped.
+/// The lock file `.<name>.lock` next to the directory is kept, removing it would race with other installers.
+pub struct InstallLock {
+    _file: File,
+    /// other installer held the lock, and the directory may be installed by it already
+    pub waited: bool,
+}
+
+/// lock the installation directory, and wait for other installers, processes or threads, of the same directory
+pub fn lock_install_dir(target_dir: &Path) -> Result<InstallLock> {
+    let (Some(parent_dir), Some(dir_name)) = (target_dir.parent(), target_dir.file_name()) else {
+        return Err(Error::Io(io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid install directory: {}", target_dir.display()))));
+    };
+    std::fs::create_dir_all(parent_dir)?;
+    let lock_file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(parent_dir.join(format!(".{}.lock", dir_name.to_string_lossy())))?;
+    match lock_file.try_lock() {
+        Ok(_) => Ok(InstallLock { _file: lock_file, waited: false }),
+        Err(TryLockError::WouldBlock) => {
+            if !is_quiet() {
+                eprintln!("Waiting for other installer of {} to finish...", target_dir.display());
+            }
+            lock_file.lock()?;
+            Ok(InstallLock { _file: lock_file, waited: true })
+        }
+        Err(TryLockError::Error(e)) => Err(e.into()),
+    }
+}
+
+/// download archive and install it into `target_dir` under the lock of `target_dir`, `install` extracts
+/// the archive into the staging directory of `install_atomically`.
+/// Skipped if `target_dir` was installed by other installer while waiting for the lock.
+pub fn download_and_install<F>(http_url: &str, archive_file_path: &Path, target_dir: &Path, install: F) -> Result<()>
+where
+    F: FnOnce(&Path, &Path) -> Result<()>,
+{
+    let lock = lock_install_dir(target_dir)?;
+    if lock.waited && target_dir.exists() {
+        if !is_quiet() {
+            eprintln!("{} installed by other installer", target_dir.display());
+        }
+        return Ok(());
+    }
+    http_download(http_url, archive_file_path)?;
+    let result = install_atomically(target_dir, |staging_dir| install(archive_file_path, staging_dir));
+    // an archive failed to install is corrupted mostly, and should not be reused by next install
+    let _ = std::fs::remove_file(archive_file_path);
+    result
+}
+
+/// install into `target_dir` atomically: `extract` fills a staging directory next to `target_dir`,
+/// then the staging directory is renamed to `target_dir`.
+/// An existing `target_dir` is moved aside first, and restored if the rename fails.
+pub fn install_atomically<F>(target_dir: &Path, extract: F) -> Result<()>
+where
+    F: FnOnce(&Path) -> Result<()>,
+{
+    let (Some(parent_dir), Some(dir_name)) = (target_dir.parent(), target_dir.file_name()) else {
+        return Err(Error::Io(io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid install directory: {}", target_dir.display()))));
+    };
+    let dir_name = dir_name.to_string_lossy();
+    std::fs::create_dir_all(parent_dir)?;
+    let staging_dir = parent_dir.join(format!(".{}.staging-{}", dir_name, std::process::id()));
+    let backup_dir = parent_dir.join(format!(".{}.backup-{}", dir_name, std::process::id()));
+    if staging_dir.exists() {
+        std::fs::remove_dir_all(&staging_dir)?;
+    }
+    std::fs::create_dir_all(&staging_dir)?;
+    if let Err(e) = extract(&staging_dir) {
+        let _ = std::fs::remove_dir_all(&staging_dir);
+        return Err(e);
+    }
+    let has_backup = target_dir.exists() || target_dir.is_symlink();
+    if has_backup && let Err(e) = std::fs::rename(target_dir, &backup_dir) {
+        let _ = std::fs::remove_dir_all(&staging_dir);
+        return Err(e.into());
+    }
+    if let Err(e) = std::fs::rename(&staging_dir, target_dir) {
+        if has_backup {
+            let _ = std::fs::rename(&backup_dir, target_dir);
+        }
+        let _ = std::fs::remove_dir_all(&staging_dir);
+        return Err(e.into());
+    }
+    if has_backup {
+        if backup_dir.is_symlink() {
+            let _ = symlink::remove_symlink_auto(&backup_dir);
+        } else {
+            let _ = std::fs::remove_dir_all(&backup_dir);
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveFormat {
+    Zip,
+    Gzip,
+    Xz,
+    Bzip2,
+    Tar,
+}
+
+/// archive format by magic bytes, then by file extension: `.zip`, `.tar.gz`/`.tgz`, `.tar.xz`/`.txz`,
+/// `.tar.bz2`/`.tbz2` or `.tar`
+fn detect_archive_format(archive_file_path: &Path) -> Result<ArchiveFormat> {
+    let mut header = vec![];
+    File::open(archive_file_path)?.take(262).read_to_end(&mut header)?;
+    if header.starts_with(b"PK\x03\x04") || header.starts_with(b"PK\x05\x06") {
+        return Ok(ArchiveFormat::Zip);
+    } else if header.starts_with(&[0x1f, 0x8b]) {
+        return Ok(ArchiveFormat::Gzip);
+    } else if header.starts_with(b"\xfd7zXZ\x00") {
+        return Ok(ArchiveFormat::Xz);
+    } else if header.starts_with(b"BZh") {
+        return Ok(ArchiveFormat::Bzip2);
+    } else if header.get(257..262) == Some(b"ustar") {
+        return Ok(ArchiveFormat::Tar);
+    }
+    let file_name = archive_file_path.to_string_lossy().to_lowercase();
+    if file_name.ends_with(".zip") {
+        Ok(ArchiveFormat::Zip)
+    } else if file_name.ends_with(".tar.gz") || file_name.ends_with(".tgz") {
+        Ok(ArchiveFormat::Gzip)
+    } else if file_name.ends_with(".tar.xz") || file_name.ends_with(".txz") {
+        Ok(ArchiveFormat::Xz)
+    } else if file_name.ends_with(".tar.bz2") || file_name.ends_with(".tbz2") {
+        Ok(ArchiveFormat::Bzip2)
+    } else if file_name.ends_with(".tar") {
+        Ok(ArchiveFormat::Tar)
+    } else {
+        Err(Error::Archive(format!("Unsupported archive format: {}", archive_file_path.display())))
+    }
+}
+
+/// extract zip or tar archive, and the format is detected by magic bytes, then by file extension
+pub fn extract_archive<P: AsRef<Path>>(archive_file_path: P, target_dir: &Path, root_excluded: bool) -> Result<()> {
+    let archive_file_path = archive_file_path.as_ref();
+    if detect_archive_format(archive_file_path)? == ArchiveFormat::Zip {
+        extract_zip(archive_file_path, target_dir, root_excluded)
+    } else {
+        let mut archive = open_tar_archive(archive_file_path)?;
+        unpack_tar(&mut archive, target_dir, |entry_path| sanitize_entry_path(entry_path, root_excluded))
+    }
+}
+
+/// tar archive with decoder chosen by magic bytes, then by file extension
+pub fn open_tar_archive(archive_file_path: &Path) -> Result<Archive<Box<dyn Read>>> {
+    let format = detect_archive_format(archive_file_path)?;
+    let file = io::BufReader::new(File::open(archive_file_path)?);
+    let reader: Box<dyn Read> = match format {
+        ArchiveFormat::Gzip => Box::new(GzDecoder::new(file)),
+        ArchiveFormat::Xz => Box::new(XzReader::new(file, true)),
+        ArchiveFormat::Bzip2 => Box::new(MultiBzDecoder::new(file)),
+        ArchiveFormat::Tar => Box::new(file),
+        ArchiveFormat::Zip => return Err(Error::Archive(format!("Not a tar archive: {}", archive_file_path.display()))),
+    };
+    Ok(Archive::new(reader))
+}
+
+pub fn extract_zip<P: AsRef<Path>>(archive_file_path: P, target_dir: &Path, root_excluded: bool) -> Result<()> {
+    let mut archive = ZipArchive::new(File::open(archive_file_path)?)?;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let Some(relative_path) = sanitize_entry_path(file.name(), root_excluded)? else {
+            continue;
+        };
+        check_no_symlink_in_path(target_dir, &relative_path)?;
+        let outpath = target_dir.join(&relative_path);
+        if file.is_dir() {
+            std::fs::create_dir_all(&outpath)?;
+            continue;
+        }
+        if let Some(parent) = outpath.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        if file.is_symlink() {
+            let mut link_target = String::new();
+            file.read_to_string(&mut link_target)?;
+            check_link_target(target_dir, &relative_path, Path::new(&link_target))?;
+            symlink::symlink_auto(&link_target, &outpath)?;
+        } else {
+            let mut outfile = File::create(&outpath)?;
+            io::copy(&mut file, &mut outfile)?;
+            #[cfg(unix)]
+            if let Some(mode) = file.unix_mode() {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&outpath, std::fs::Permissions::from_mode(mode & 0o777))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn extract_tgz<P: AsRef<Path>>(archive_file_path: P, target_dir: &Path, root_excluded: bool) -> Result<()> {
+    let mut archive = Archive::new(GzDecoder::new(File::open(archive_file_path)?));
+    unpack_tar(&mut archive, target_dir, |entry_path| sanitize_entry_path(entry_path, root_excluded))
+}
 
 /// extract tgz from sub path, for example `Contents/Home/` from Mac JDK tgz
 /// sub_path should end with `/`
-pub fn extract_tgz_from_sub_path<P: AsRef<Path>>(archive_file_path: P, target_dir: &PathBuf, sub_path: &str) {
-    let sub_path_len = sub_path.len();
-    let tgz_file = File::open(archive_file_path).unwrap();
-    let gz_decoder = GzDecoder::new(tgz_file);
-    let mut archive = Archive::new(gz_decoder);
-    archive
-        .entries().unwrap()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().unwrap().to_str().unwrap().contains(sub_path))
-        .for_each(|mut entry| {
-            let entry_path = entry.path().unwrap();
-            let mut relative_path = entry_path.to_str().unwrap();
-            relative_path = &relative_path[(relative_path.find(sub_path).unwrap() + sub_path_len)..];
-            let path = target_dir.join(relative_path);
-            // create parent directory
-            if let Some(parent) = path.parent() {
-                if !parent.exists() {
-                    std::fs::create_dir_all(parent).unwrap();
+pub fn extract_tgz_from_sub_path<P: AsRef<Path>>(archive_file_path: P, target_dir: &Path, sub_path: &str) -> Result<()> {
+    let mut archive = Archive::new(GzDecoder::new(File::open(archive_file_path)?));
+    extract_tar_from_sub_path(&mut archive, target_dir, sub_path)
+}
+
+pub fn extract_tar_from_sub_path<R: Read>(archive: &mut Archive<R>, target_dir: &Path, sub_path: &str) -> Result<()> {
+    unpack_tar(archive, target_dir, |entry_path| {
+        let entry_path = entry_path.replace('\\', "/");
+        match entry_path.find(sub_path) {
+            Some(pos) => sanitize_entry_path(&entry_path[(pos + sub_path.len())..], false),
+            None => Ok(None),
+        }
+    })
+}
+
+/// unpack tar entries, `map_path` maps entry path to relative path under `target_dir`, `None` to skip the entry
+fn unpack_tar<R, F>(archive: &mut Archive<R>, target_dir: &Path, map_path: F) -> Result<()>
+where
+    R: Read,
+    F: Fn(&str) -> Result<Option<PathBuf>>,
+{
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_path = entry.path()?.to_string_lossy().to_string();
+        let Some(relative_path) = map_path(&entry_path)? else {
+            continue;
+        };
+        check_no_symlink_in_path(target_dir, &relative_path)?;
+        let path = target_dir.join(&relative_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        match entry.header().entry_type() {
+            EntryType::Symlink => {
+                if let Some(link_target) = entry.link_name()? {
+                    check_link_target(target_dir, &relative_path, &link_target)?;
                 }
+                entry.unpack(&path)?;
             }
-            entry.unpack(&path).unwrap();
-        });
+            EntryType::Link => {
+                // hard link target is relative to the archive root, not the current directory
+                let link_name = entry.link_name()?.map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+                let Some(link_target) = map_path(&link_name)? else {
+                    return Err(Error::Archive(format!("Invalid hard link in archive: {} -> {}", entry_path, link_name)));
+                };
+                check_no_symlink_in_path(target_dir, &link_target)?;
+                let link_target = target_dir.join(link_target);
+                if std::fs::hard_link(&link_target, &path).is_err() {
+                    std::fs::copy(&link_target, &path)?;
+                }
+            }
+            _ => {
+                entry.unpack(&path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// normalize archive entry path with `/` and `\` separators, and strip the root directory if `root_excluded`.
+/// Returns `None` for entries to skip, such as the root directory itself or files beside it.
+/// Absolute paths and `..` are rejected to prevent path traversal.
+pub fn sanitize_entry_path(entry_path: &str, root_excluded: bool) -> Result<Option<PathBuf>> {
+    let normalized = entry_path.replace('\\', "/");
+    if normalized.starts_with('/') || normalized.split('/').next().is_some_and(|first| first.ends_with(':')) {
+        return Err(Error::Archive(format!("Absolute path in archive: {}", entry_path)));
+    }
+    let mut components: Vec<&str> = normalized.split('/')
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect();
+    if components.contains(&"..") {
+        return Err(Error::Archive(format!("Path traversal in archive: {}", entry_path)));
+    }
+    if root_excluded {
+        if components.len() <= 1 {
+            return Ok(None);
+        }
+        components.remove(0);
+    }
+    if components.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(components.iter().collect()))
+}
+
+/// parent directories of `relative_path` should not be symbolic links extracted before, otherwise a chain of links,
+/// such as `d/l -> ..` and `d/l/m -> ..`, lets a later entry `d/l/m/evil` escape the extraction directory
+fn check_no_symlink_in_path(target_dir: &Path, relative_path: &Path) -> Result<()> {
+    let mut path = target_dir.to_path_buf();
+    for component in relative_path.parent().into_iter().flat_map(|parent| parent.components()) {
+        path.push(component);
+        if path.is_symlink() {
+            return Err(Error::Archive(format!("Path through symbolic link in archive: {}", relative_path.display())));
+        }
+    }
+    Ok(())
+}
+
+/// symbolic link at `relative_path` should not point outside of the extraction directory,
+/// and should not resolve through symbolic links extracted before
+fn check_link_target(target_dir: &Path, relative_path: &Path, link_target: &Path) -> Result<()> {
+    let link_target_text = link_target.to_string_lossy().replace('\\', "/");
+    if link_target_text.starts_with('/') || link_target.is_absolute() {
+        return Err(Error::Archive(format!("Absolute symbolic link in archive: {} -> {}", relative_path.display(), link_target.display())));
+    }
+    let mut resolved: Vec<String> = relative_path.parent()
+        .map(|parent| parent.components().map(|component| component.as_os_str().to_string_lossy().to_string()).collect())
+        .unwrap_or_default();
+    let components: Vec<&str> = link_target_text.split('/')
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect();
+    for (index, component) in components.iter().enumerate() {
+        if *component == ".." {
+            if resolved.pop().is_none() {
+                return Err(Error::Archive(format!("Symbolic link outside of archive: {} -> {}", relative_path.display(), link_target.display())));
+            }
+            continue;
+        }
+        resolved.push(component.to_string());
+        if index + 1 < components.len() && target_dir.join(resolved.iter().collect::<PathBuf>()).is_symlink() {
+            return Err(Error::Archive(format!("Symbolic link through symbolic link in archive: {} -> {}", relative_path.display(), link_target.display())));
+        }
+    }
+    Ok(())
 }
 
 pub fn run_command(command_name: &str, args: &[&str]) -> io::Result<Output> {
@@ -144,8 +735,10 @@ pub fn run_command(command_name: &str, args: &[&str]) -> io::Result<Output> {
 }
 
 pub fn run_command_line(command_line: &str) -> io::Result<Output> {
-    let command_and_args = shlex::split(command_line).unwrap();
-    let command_name = command_and_args[0].clone();
+    let command_and_args = shlex::split(command_line).unwrap_or_default();
+    let Some(command_name) = command_and_args.first().cloned() else {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid command line: {}", command_line)));
+    };
     let args = &command_and_args[1..].iter().map(AsRef::as_ref).collect::<Vec<&str>>();
     run_command(&command_name, args)
 }
@@ -190,13 +783,201 @@ pub fn run_command_with_env_vars(command_name: &str, args: &[&str],
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::BufRead;
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    const TEST_ETAG: &str = "\"wukong-test\"";
+
+    /// local HTTP server with `Range` and `If-Range` support, the first `broken_responses` responses are cut
+    /// in the middle of body. Returns url and `Range` headers of received requests.
+    fn start_http_server(content: Vec<u8>, status: u16, broken_responses: usize) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/file.tar.gz", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(vec![]));
+        let received = requests.clone();
+        std::thread::spawn(move || {
+            for (index, stream) in listener.incoming().enumerate() {
+                let mut stream = stream.unwrap();
+                let mut reader = io::BufReader::new(stream.try_clone().unwrap());
+                let mut range_start = 0usize;
+                let mut range_header = String::new();
+                let mut if_range_matched = true;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(value) = line.to_lowercase().strip_prefix("range: bytes=") {
+                        range_header = value.trim().to_string();
+                        range_start = range_header.trim_end_matches('-').parse().unwrap();
+                    }
+                    if let Some(value) = line.strip_prefix("if-range: ").or_else(|| line.strip_prefix("If-Range: ")) {
+                        if_range_matched = value.trim() == TEST_ETAG;
+                    }
+                }
+                received.lock().unwrap().push(range_header);
+                if status != 200 {
+                    write!(stream, "HTTP/1.1 {} Error\r\nContent-Length: 0\r\n\r\n", status).unwrap();
+                    continue;
+                }
+                if !if_range_matched {
+                    range_start = 0;
+                }
+                let body = &content[range_start..];
+                if range_start > 0 {
+                    write!(stream, "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes {}-{}/{}\r\n", range_start, content.len() - 1, content.len()).unwrap();
+                } else {
+                    write!(stream, "HTTP/1.1 200 OK\r\n").unwrap();
+                }
+                write!(stream, "ETag: {}\r\n", TEST_ETAG).unwrap();
+                write!(stream, "Content-Length: {}\r\nConnection: close\r\n\r\n", body.len()).unwrap();
+                if index < broken_responses {
+                    stream.write_all(&body[..body.len() / 2]).unwrap();
+                } else {
+                    stream.write_all(body).unwrap();
+                }
+            }
+        });
+        (url, requests)
+    }
+
+    fn download_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join("wukong-download-tests").join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn test_options() -> DownloadOptions {
+        DownloadOptions {
+            retries: 2,
+            backoff: Duration::from_millis(10),
+            progress: false,
+        }
+    }
+
+    #[test]
+    fn test_download_file() {
+        let content: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        let (url, _) = start_http_server(content.clone(), 200, 0);
+        let target_file_path = download_test_dir("full").join("file.tar.gz");
+        download_file(&url, &target_file_path, &test_options()).unwrap();
+        assert_eq!(std::fs::read(&target_file_path).unwrap(), content);
+        assert!(!get_part_file_path(&target_file_path).exists());
+        assert!(!get_validator_file_path(&get_part_file_path(&target_file_path)).exists());
+    }
+
+    #[test]
+    fn test_download_resume_part_file() {
+        let content: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        let (url, requests) = start_http_server(content.clone(), 200, 0);
+        let target_file_path = download_test_dir("resume").join("file.tar.gz");
+        let part_file_path = get_part_file_path(&target_file_path);
+        std::fs::write(&part_file_path, &content[..40_000]).unwrap();
+        std::fs::write(get_validator_file_path(&part_file_path), TEST_ETAG).unwrap();
+        download_file(&url, &target_file_path, &test_options()).unwrap();
+        assert_eq!(std::fs::read(&target_file_path).unwrap(), content);
+        assert_eq!(requests.lock().unwrap().as_slice(), ["40000-"]);
+    }
+
+    #[test]
+    fn test_download_restart_changed_part_file() {
+        let content: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        let (url, requests) = start_http_server(content.clone(), 200, 0);
+        let target_file_path = download_test_dir("changed").join("file.tar.gz");
+        let part_file_path = get_part_file_path(&target_file_path);
+        // part of an older file on the server is replaced by the whole new file
+        std::fs::write(&part_file_path, vec![0u8; 40_000]).unwrap();
+        std::fs::write(get_validator_file_path(&part_file_path), "\"old\"").unwrap();
+        download_file(&url, &target_file_path, &test_options()).unwrap();
+        assert_eq!(std::fs::read(&target_file_path).unwrap(), content);
+        assert_eq!(requests.lock().unwrap().as_slice(), ["40000-"]);
+        // part file without validator is not resumed
+        std::fs::write(&part_file_path, vec![0u8; 40_000]).unwrap();
+        download_file(&url, &target_file_path, &test_options()).unwrap();
+        assert_eq!(std::fs::read(&target_file_path).unwrap(), content);
+        assert_eq!(requests.lock().unwrap().as_slice(), ["40000-", ""]);
+    }
+
+    #[test]
+    fn test_download_retry_after_broken_response() {
+        let content: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        let (url, requests) = start_http_server(content.clone(), 200, 1);
+        let target_file_path = download_test_dir("retry").join("file.tar.gz");
+        download_file(&url, &target_file_path, &test_options()).unwrap();
+        assert_eq!(std::fs::read(&target_file_path).unwrap(), content);
+        assert_eq!(requests.lock().unwrap().as_slice(), ["", "50000-"]);
+    }
+
+    #[test]
+    fn test_download_not_found() {
+        let (url, requests) = start_http_server(vec![], 404, 0);
+        let target_file_path = download_test_dir("not-found").join("file.tar.gz");
+        let result = download_file(&url, &target_file_path, &test_options());
+        assert!(result.is_err());
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        assert!(!target_file_path.exists());
+    }
+
+    #[test]
+    fn test_find_java_version_file() {
+        let dir = download_test_dir("java-version");
+        let project_dir = dir.join("home").join("project");
+        std::fs::create_dir_all(project_dir.join("module").join("src")).unwrap();
+        std::fs::write(dir.join(".java-version"), "11\n").unwrap();
+        std::fs::write(project_dir.join(".java-version"), "# project JDK\n21.0.2\n").unwrap();
+        std::fs::write(project_dir.join("module").join(".java-version"), "\n").unwrap();
+        let found = find_java_version_file(&project_dir.join("module").join("src"), None).unwrap();
+        assert_eq!(found, JavaVersionFile { version: "21.0.2".to_owned(), path: project_dir.join(".java-version") });
+        assert_eq!(find_java_version_file(&dir.join("home"), None).unwrap().version, "11");
+        assert_eq!(find_java_version_file(&dir.join("home"), Some(&dir.join("home"))), None);
+        // stop directory does not apply outside of it
+        assert_eq!(find_java_version_file(&dir, Some(&project_dir)).unwrap().version, "11");
+    }
+
+    #[test]
+    fn test_concurrent_installs() {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(vec![], flate2::Compression::default()));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(5);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder.append_data(&mut header, "jdk-21/bin/java", "java\n".as_bytes()).unwrap();
+        let content = builder.into_inner().unwrap().finish().unwrap();
+        let (url, requests) = start_http_server(content, 200, 0);
+        let dir = download_test_dir("concurrent");
+        let target_dir = dir.join("jdks").join("21");
+        let installers: Vec<_> = (0..4).map(|index| {
+            let (url, dir, target_dir) = (url.clone(), dir.clone(), target_dir.clone());
+            std::thread::spawn(move || {
+                download_and_install(&url, &dir.join(format!("jdk-21-{}.tar.gz", index)), &target_dir, |archive_file_path, staging_dir| {
+                    // hold the lock long enough for other installers to wait
+                    std::thread::sleep(Duration::from_millis(200));
+                    extract_tgz(archive_file_path, staging_dir, true)
+                })
+            })
+        }).collect();
+        for installer in installers {
+            installer.join().unwrap().unwrap();
+        }
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        assert_eq!(std::fs::read_to_string(target_dir.join("bin").join("java")).unwrap(), "java\n");
+        let mut names: Vec<String> = std::fs::read_dir(dir.join("jdks")).unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, [".21.lock", "21"]);
+        assert!(std::fs::read_dir(&dir).unwrap().all(|entry| !entry.unwrap().file_name().to_string_lossy().ends_with(".tar.gz")));
+    }
 
     #[test]
     fn test_download() {
         let download_dir = dirs::download_dir().unwrap();
         let target_file_path = download_dir.join("apache-maven-3.9.9-bin.tar.gz");
         let http_url = "https://dlcdn.apache.org/maven/maven-3/3.9.9/binaries/apache-maven-3.9.9-bin.tar.gz";
-        http_download(http_url, target_file_path.to_str().unwrap());
+        http_download(http_url, target_file_path.to_str().unwrap()).unwrap();
         assert!(target_file_path.exists());
     }
 
@@ -205,14 +986,14 @@ mod tests {
     fn test_extract_maven_zip() {
         let archive_file_path = "/Users/linux_china/temp/jdks/apache-maven-3.9.9-bin.zip";
         let target_dir = PathBuf::from("/Users/linux_china/temp/jdks/maven-3.9.9");
-        extract_zip(archive_file_path, &target_dir, true);
+        extract_zip(archive_file_path, &target_dir, true).unwrap();
     }
 
     #[test]
     fn test_extract_jdk_mac() {
         let archive_file_path = "/Users/linux_china/temp/jdks/jdk-21-mac.tgz";
         let target_dir = PathBuf::from("/Users/linux_china/temp/jdks/21");
-        extract_tgz_from_sub_path(archive_file_path, &target_dir, "Contents/Home/")
+        extract_tgz_from_sub_path(archive_file_path, &target_dir, "Contents/Home/").unwrap();
     }
 
     #[test]
@@ -222,4 +1003,253 @@ mod tests {
         let http_url = url::Url::parse(&redirect_url).unwrap();
         println!("{:?}", http_url)
     }
+
+    #[test]
+    fn test_read_release() {
+        let dir = download_test_dir("release");
+        std::fs::write(dir.join("release"), "IMPLEMENTOR=\"Eclipse Adoptium\"\nJAVA_VERSION=\"21.0.2\"\nOS_NAME='Linux'\n").unwrap();
+        let release = read_release(&dir.join("release")).unwrap();
+        assert_eq!(release.get("IMPLEMENTOR").map(String::as_str), Some("Eclipse Adoptium"));
+        assert_eq!(release.get("JAVA_VERSION").map(String::as_str), Some("21.0.2"));
+        assert_eq!(release.get("OS_NAME").map(String::as_str), Some("Linux"));
+        assert!(read_release(&dir.join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(version_segments("4.0.0-rc-1"), ["4", "0", "0", "rc", "1"]);
+        assert_eq!(compare_versions("21.0.10", "21.0.9"), cmp::Ordering::Greater);
+        assert_eq!(compare_versions("23.0.1", "23.ea.5"), cmp::Ordering::Greater);
+        assert_eq!(compare_versions("3.9.0", "3.9.0-rc-1"), cmp::Ordering::Greater);
+        assert_eq!(compare_versions("3.9.0", "3.9.0.1"), cmp::Ordering::Less);
+        assert_eq!(compare_versions("17.0.4-TEM", "17.0.4-tem"), cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn test_verify_sha256() {
+        let file_path = download_test_dir("checksum").join("hello.txt");
+        std::fs::write(&file_path, "hello").unwrap();
+        verify_sha256(&file_path, "2CF24DBA5FB0A30E26E83B2AC5B9E29E1B161E5C1FA7425E73043362938B9824").unwrap();
+        let result = verify_sha256(&file_path, "0000");
+        assert!(matches!(result, Err(Error::Checksum { .. })));
+        assert_eq!(result.unwrap_err().exit_code(), 4);
+    }
+
+    fn extract_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("wukong-extract-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// tar with `demo-1.0/` root, an executable script, a relative symlink and a hard link
+    fn build_tar<W: Write>(writer: W, link_target: &str) -> W {
+        let mut builder = tar::Builder::new(writer);
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(EntryType::Directory);
+        header.set_mode(0o755);
+        header.set_size(0);
+        builder.append_data(&mut header, "demo-1.0/bin/", io::empty()).unwrap();
+        let script = b"#!/bin/sh\necho demo\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_mode(0o755);
+        header.set_size(script.len() as u64);
+        builder.append_data(&mut header, "demo-1.0/bin/demo", &script[..]).unwrap();
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(EntryType::Symlink);
+        header.set_size(0);
+        builder.append_link(&mut header, "demo-1.0/current", link_target).unwrap();
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(EntryType::Link);
+        header.set_size(0);
+        builder.append_link(&mut header, "demo-1.0/bin/demo2", "demo-1.0/bin/demo").unwrap();
+        builder.into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_sanitize_entry_path() {
+        assert_eq!(sanitize_entry_path("jdk-21/bin/java", true).unwrap(), Some(PathBuf::from("bin/java")));
+        assert_eq!(sanitize_entry_path("./jdk-21/./bin/java", false).unwrap(), Some(PathBuf::from("jdk-21/bin/java")));
+        assert_eq!(sanitize_entry_path("jdk-21\\bin\\java.exe", true).unwrap(), Some(PathBuf::from("bin/java.exe")));
+        assert_eq!(sanitize_entry_path("jdk-21/", true).unwrap(), None);
+        assert_eq!(sanitize_entry_path("README", true).unwrap(), None);
+        assert!(sanitize_entry_path("jdk-21/../../etc/passwd", true).is_err());
+        assert!(sanitize_entry_path("/etc/passwd", false).is_err());
+        assert!(sanitize_entry_path("C:\\Windows\\win.ini", false).is_err());
+        let target_dir = Path::new("/nonexistent/wukong");
+        assert!(check_link_target(target_dir, Path::new("lib/current"), Path::new("../bin/demo")).is_ok());
+        assert!(check_link_target(target_dir, Path::new("current"), Path::new("../etc/passwd")).is_err());
+        assert!(check_link_target(target_dir, Path::new("current"), Path::new("/etc/passwd")).is_err());
+    }
+
+    #[test]
+    fn test_extract_tar_formats() {
+        let dir = extract_test_dir("formats");
+        let archives = [
+            ("demo.tar.gz", build_tar(flate2::write::GzEncoder::new(vec![], flate2::Compression::default()), "bin/demo").finish().unwrap()),
+            ("demo.tar.xz", {
+                let mut writer = build_tar(lzma_rust2::XzWriter::new(vec![], lzma_rust2::XzOptions::with_preset(6)).unwrap(), "bin/demo");
+                writer.flush().unwrap();
+                writer.finish().unwrap()
+            }),
+            ("demo.tar.bz2", build_tar(bzip2::write::BzEncoder::new(vec![], bzip2::Compression::default()), "bin/demo").finish().unwrap()),
+            ("demo.tar", build_tar(vec![], "bin/demo")),
+        ];
+        for (file_name, content) in archives {
+            let archive_file_path = dir.join(file_name);
+            std::fs::write(&archive_file_path, content).unwrap();
+            let target_dir = dir.join(file_name.replace('.', "-"));
+            extract_archive(&archive_file_path, &target_dir, true).unwrap();
+            assert_eq!(std::fs::read_to_string(target_dir.join("bin").join("demo")).unwrap(), "#!/bin/sh\necho demo\n");
+            assert_eq!(std::fs::read_to_string(target_dir.join("bin").join("demo2")).unwrap(), "#!/bin/sh\necho demo\n");
+            assert!(target_dir.join("current").is_symlink());
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let mode = std::fs::metadata(target_dir.join("bin").join("demo")).unwrap().permissions().mode();
+                assert_eq!(mode & 0o111, 0o111);
+            }
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_detect_archive_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let archives = [
+            ("download", build_tar(flate2::write::GzEncoder::new(vec![], flate2::Compression::default()), "bin/demo").finish().unwrap(), ArchiveFormat::Gzip),
+            ("demo.bin", build_tar(bzip2::write::BzEncoder::new(vec![], bzip2::Compression::default()), "bin/demo").finish().unwrap(), ArchiveFormat::Bzip2),
+            ("demo.zip", build_tar(vec![], "bin/demo"), ArchiveFormat::Tar),
+        ];
+        for (file_name, content, format) in archives {
+            let archive_file_path = dir.path().join(file_name);
+            std::fs::write(&archive_file_path, content).unwrap();
+            assert_eq!(detect_archive_format(&archive_file_path).unwrap(), format);
+            let target_dir = dir.path().join(format!("{}-extracted", file_name));
+            extract_archive(&archive_file_path, &target_dir, true).unwrap();
+            assert!(target_dir.join("bin").join("demo").is_file());
+        }
+        // unknown content falls back to the file extension
+        let archive_file_path = dir.path().join("demo.tar.xz");
+        std::fs::write(&archive_file_path, "demo").unwrap();
+        assert_eq!(detect_archive_format(&archive_file_path).unwrap(), ArchiveFormat::Xz);
+        let archive_file_path = dir.path().join("demo.txt");
+        std::fs::write(&archive_file_path, "demo").unwrap();
+        assert!(detect_archive_format(&archive_file_path).is_err());
+    }
+
+    #[test]
+    fn test_extract_tar_symlink_outside() {
+        let dir = extract_test_dir("tar-symlink");
+        let archive_file_path = dir.join("demo.tar");
+        std::fs::write(&archive_file_path, build_tar(vec![], "../../etc/passwd")).unwrap();
+        let target_dir = dir.join("demo");
+        assert!(extract_archive(&archive_file_path, &target_dir, true).is_err());
+        assert!(!target_dir.join("current").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// `d/l -> ..` and `d/l/m -> ..` are inside the archive as text, but `d/l/m/evil` resolves outside of it
+    #[test]
+    fn test_extract_tar_symlink_chain() {
+        let dir = extract_test_dir("tar-symlink-chain");
+        let mut builder = tar::Builder::new(vec![]);
+        for (path, link_target) in [("d/l", ".."), ("d/l/m", "..")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(EntryType::Symlink);
+            header.set_size(0);
+            builder.append_link(&mut header, path, link_target).unwrap();
+        }
+        let mut header = tar::Header::new_gnu();
+        header.set_mode(0o644);
+        header.set_size(4);
+        builder.append_data(&mut header, "d/l/m/evil", &b"evil"[..]).unwrap();
+        let archive_file_path = dir.join("evil.tar");
+        std::fs::write(&archive_file_path, builder.into_inner().unwrap()).unwrap();
+        let target_dir = dir.join("staging").join("demo");
+        let result = extract_archive(&archive_file_path, &target_dir, false);
+        assert!(matches!(result, Err(Error::Archive(_))));
+        assert!(!dir.join("staging").join("evil").exists());
+        assert!(!dir.join("evil").exists());
+        // link target through a link extracted before
+        let mut builder = tar::Builder::new(vec![]);
+        for (path, link_target) in [("d/l", ".."), ("x", "d/l/../../evil")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(EntryType::Symlink);
+            header.set_size(0);
+            builder.append_link(&mut header, path, link_target).unwrap();
+        }
+        std::fs::write(&archive_file_path, builder.into_inner().unwrap()).unwrap();
+        let target_dir = dir.join("demo2");
+        assert!(extract_archive(&archive_file_path, &target_dir, false).is_err());
+        assert!(!target_dir.join("x").is_symlink());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_extract_zip_safely() {
+        use zip::write::SimpleFileOptions;
+        let dir = extract_test_dir("zip");
+        let archive_file_path = dir.join("demo.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&archive_file_path).unwrap());
+        writer.add_directory("demo-1.0/bin/", SimpleFileOptions::default()).unwrap();
+        writer.start_file("demo-1.0/bin/demo", SimpleFileOptions::default().unix_permissions(0o755)).unwrap();
+        writer.write_all(b"#!/bin/sh\necho demo\n").unwrap();
+        writer.add_symlink("demo-1.0/current", "bin/demo", SimpleFileOptions::default()).unwrap();
+        writer.finish().unwrap();
+        let target_dir = dir.join("demo");
+        extract_archive(&archive_file_path, &target_dir, true).unwrap();
+        assert!(target_dir.join("bin").join("demo").exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(target_dir.join("bin").join("demo")).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o755);
+            assert_eq!(std::fs::read_link(target_dir.join("current")).unwrap(), PathBuf::from("bin/demo"));
+        }
+        // path traversal
+        let evil_archive_path = dir.join("evil.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&evil_archive_path).unwrap());
+        writer.start_file("demo-1.0/../../evil.txt", SimpleFileOptions::default()).unwrap();
+        writer.write_all(b"evil").unwrap();
+        writer.finish().unwrap();
+        assert!(extract_archive(&evil_archive_path, &dir.join("evil"), true).is_err());
+        assert!(!dir.join("evil.txt").exists());
+        // chain of symbolic links
+        let evil_archive_path = dir.join("chain.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&evil_archive_path).unwrap());
+        writer.add_symlink("d/l", "..", SimpleFileOptions::default()).unwrap();
+        writer.add_symlink("d/l/m", "..", SimpleFileOptions::default()).unwrap();
+        writer.start_file("d/l/m/evil.txt", SimpleFileOptions::default()).unwrap();
+        writer.write_all(b"evil").unwrap();
+        writer.finish().unwrap();
+        assert!(extract_archive(&evil_archive_path, &dir.join("chain").join("demo"), false).is_err());
+        assert!(!dir.join("evil.txt").exists());
+        assert!(!dir.join("chain").join("evil.txt").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_install_atomically_rollback() {
+        let dir = extract_test_dir("atomic");
+        let target_dir = dir.join("demo");
+        std::fs::create_dir_all(&target_dir).unwrap();
+        std::fs::write(target_dir.join("version.txt"), "1.0").unwrap();
+        // failed extraction keeps the old installation
+        let result = install_atomically(&target_dir, |staging_dir| {
+            std::fs::write(staging_dir.join("version.txt"), "2.0")?;
+            Err(Error::Archive("corrupted archive".to_string()))
+        });
+        assert!(result.is_err());
+        assert_eq!(std::fs::read_to_string(target_dir.join("version.txt")).unwrap(), "1.0");
+        // successful extraction replaces the old installation
+        install_atomically(&target_dir, |staging_dir| {
+            std::fs::write(staging_dir.join("version.txt"), "2.0")?;
+            Ok(())
+        }).unwrap();
+        assert_eq!(std::fs::read_to_string(target_dir.join("version.txt")).unwrap(), "2.0");
+        let leftovers: Vec<_> = std::fs::read_dir(&dir).unwrap().flatten().map(|entry| entry.file_name()).collect();
+        assert_eq!(leftovers, vec![std::ffi::OsString::from("demo")]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,166 @@
+//! Error type for the `wukong` library.
+//!
+//! Binaries render errors with `report_error()` or `exit_with_error()`, which print `[<program>] <message>` to stderr
+//! and exit with `Error::exit_code()`:
+//!
+//! - 1: I/O error
+//! - 3: network error or unexpected HTTP status
+//! - 4: checksum mismatch
+//! - 5: invalid or unsafe archive
+//! - 6: not found
+//! - 7: unsupported platform
+//! - 8: configuration error
+//! - 9: invalid content, such as a malformed POM or JSON response
+
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use crate::platform::PlatformError;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// connection failure, timeout or incomplete response
+    Network(String),
+    /// unexpected HTTP status with url
+    Http(u16, String),
+    Checksum {
+        file: PathBuf,
+        expected: String,
+        actual: String,
+    },
+    Archive(String),
+    NotFound(String),
+    UnsupportedPlatform(String),
+    Config(String),
+    /// malformed content of a response or file
+    Parse(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Network(message) => write!(f, "Network error: {}", message),
+            Error::Http(404, url) => write!(f, "Not found: {}", url),
+            Error::Http(status, url) => write!(f, "HTTP error: status {}, url: {}", status, url),
+            Error::Checksum { file, expected, actual } => write!(f, "Checksum mismatch for {}: expected {}, actual {}", file.display(), expected, actual),
+            Error::Archive(message) => write!(f, "Archive error: {}", message),
+            Error::NotFound(message) => write!(f, "Not found: {}", message),
+            Error::UnsupportedPlatform(message) => write!(f, "{}", message),
+            Error::Config(message) => write!(f, "Config error: {}", message),
+            Error::Parse(message) => write!(f, "Parse error: {}", message),
+            Error::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Error {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Io(_) => 1,
+            Error::Network(_) => 3,
+            Error::Http(404, _) => 6,
+            Error::Http(_, _) => 3,
+            Error::Checksum { .. } => 4,
+            Error::Archive(_) => 5,
+            Error::NotFound(_) => 6,
+            Error::UnsupportedPlatform(_) => 7,
+            Error::Config(_) => 8,
+            Error::Parse(_) => 9,
+        }
+    }
+
+    /// retry may succeed for network errors, server errors, timeout and rate limit
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Network(_) | Error::Io(_) => true,
+            Error::Http(status, _) => !(400..500).contains(status) || *status == 408 || *status == 429,
+            _ => false,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        if let Some(status) = e.status() {
+            let url = e.url().map(|url| url.to_string()).unwrap_or_default();
+            return Error::Http(status.as_u16(), url);
+        }
+        // reqwest hides the root cause, such as DNS or TLS failure, in the source chain
+        let mut message = e.to_string();
+        let mut source = std::error::Error::source(&e);
+        while let Some(cause) = source {
+            message.push_str(": ");
+            message.push_str(&cause.to_string());
+            source = cause.source();
+        }
+        Error::Network(message)
+    }
+}
+
+impl From<reqwest::header::ToStrError> for Error {
+    fn from(e: reqwest::header::ToStrError) -> Self {
+        Error::Network(e.to_string())
+    }
+}
+
+impl From<zip::result::ZipError> for Error {
+    fn from(e: zip::result::ZipError) -> Self {
+        match e {
+            zip::result::ZipError::Io(e) => Error::Io(e),
+            e => Error::Archive(e.to_string()),
+        }
+    }
+}
+
+impl From<PlatformError> for Error {
+    fn from(e: PlatformError) -> Self {
+        Error::UnsupportedPlatform(e.to_string())
+    }
+}
+
+/// print error as `[<program>] <message>` to stderr
+pub fn report_error(program: &str, error: &Error) {
+    eprintln!("[{}] {}", program, error);
+}
+
+/// print error with `report_error()` and exit with `Error::exit_code()`
+pub fn exit_with_error(program: &str, error: &Error) -> ! {
+    report_error(program, error);
+    std::process::exit(error.exit_code())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code() {
+        assert_eq!(Error::Http(404, "https://example.com/jdk.tar.gz".to_string()).exit_code(), 6);
+        assert_eq!(Error::Http(503, "https://example.com".to_string()).exit_code(), 3);
+        assert_eq!(Error::from(PlatformError::UnsupportedArch("mips".to_string())).exit_code(), 7);
+        assert_eq!(Error::from(io::Error::from(io::ErrorKind::PermissionDenied)).exit_code(), 1);
+        assert!(Error::Http(429, "".to_string()).is_retryable());
+        assert!(!Error::Http(403, "".to_string()).is_retryable());
+        assert!(!Error::Archive("bad".to_string()).is_retryable());
+        assert_eq!(Error::Parse("Invalid POM".to_string()).exit_code(), 9);
+        assert!(!Error::Parse("Invalid POM".to_string()).is_retryable());
+    }
+}
@@ -1,33 +1,43 @@
 use std::collections::HashMap;
-use std::path::{PathBuf};
+use std::io;
+use std::path::Path;
 use itertools::Itertools;
 use serde::{Deserialize};
-use crate::common::{extract_tgz, extract_tgz_from_sub_path, extract_zip, http_download};
+use crate::common::{download_and_install, extract_tgz, extract_tgz_from_sub_path, extract_zip, http_text, verify_sha256};
+use crate::error::{Error, Result};
+use crate::http::http_get;
+use crate::platform::{detect_libc, Arch, Libc, PlatformError};
 
-pub fn get_jdk_download_url(java_version: &str) -> String {
+pub fn get_jdk_download_url(java_version: &str) -> Result<String> {
     let distro = "temurin";
-    let platform_params = get_platform_params(distro);
+    let platform_params = get_platform_params(distro)?;
     let extra_query = platform_params.iter().map(|(k, v)| {
         format!("{}={}", k, v)
     }).join("&");
-    format!("https://api.foojay.io/disco/v3.0/directuris?javafx_bundled=false&package_type=jdk&latest=available&version={}&{}", java_version, extra_query)
+    Ok(format!("https://api.foojay.io/disco/v3.0/directuris?javafx_bundled=false&package_type=jdk&latest=available&version={}&{}", java_version, extra_query))
 }
 
-fn get_platform_params(distro: &str) -> HashMap<String, String> {
+/// JDK packages matching the version, the same query as `get_jdk_download_url`
+fn get_jdk_packages_url(java_version: &str) -> Result<String> {
+    let platform_params = get_platform_params("temurin")?;
+    let extra_query = platform_params.iter().map(|(k, v)| {
+        format!("{}={}", k, v)
+    }).join("&");
+    Ok(format!("https://api.foojay.io/disco/v3.0/packages?javafx_bundled=false&package_type=jdk&latest=available&version={}&{}", java_version, extra_query))
+}
+
+fn get_platform_params(distro: &str) -> Result<HashMap<String, String>> {
     let mut params = HashMap::new();
     params.insert("distro".to_string(), distro.to_string());
-    match std::env::consts::ARCH {
-        "x86_64" => {
-            params.insert("architecture".to_owned(), "x64".to_owned());
-        }
-        "aarch64" => {
-            params.insert("architecture".to_owned(), "aarch64".to_owned());
-        }
-        _ => panic!("Unsupported architecture"),
-    };
+    let arch = Arch::current()?;
+    params.insert("architecture".to_owned(), arch.foojay_name().to_owned());
     if cfg!(target_os = "linux") {
+        let libc_type = match detect_libc() {
+            Libc::Musl => "musl",
+            Libc::Glibc => "glibc",
+        };
         params.insert("operating_system".to_owned(), "linux".to_owned());
-        params.insert("libc_type".to_owned(), "glibc".to_owned());
+        params.insert("libc_type".to_owned(), libc_type.to_owned());
         params.insert("archive_type".to_owned(), "tar.gz".to_owned());
     } else if cfg!(target_os = "macos") {
         params.insert("operating_system".to_owned(), "mac".to_owned());
@@ -38,36 +48,92 @@ fn get_platform_params(distro: &str) -> HashMap<String, String> {
         params.insert("libc_type".to_owned(), "c_std_lib".to_owned());
         params.insert("archive_type".to_owned(), "zip".to_owned());
     } else {
-        panic!("Unsupported OS");
+        return Err(PlatformError::UnsupportedOs(std::env::consts::OS.to_string()).into());
     };
-    params
+    Ok(params)
 }
 
-pub fn install_jdk(java_version: &str, target_dir: &PathBuf) {
-    let download_url = get_jdk_download_url(java_version);
-    let temp_dir = std::env::temp_dir();
-    let mut archive_file_name = format!("jdk-{}.tar.gz", java_version);
-    if cfg!(target_os = "windows") {
-        archive_file_name = format!("jdk-{}.zip", java_version);
-    }
-    let archive_file_path = temp_dir.join(archive_file_name);
-    if archive_file_path.exists() { // remove broken downloaded file
-        std::fs::remove_file(&archive_file_path).unwrap();
-    }
-    http_download(&download_url, archive_file_path.to_str().unwrap());
-    if target_dir.exists() { // remove old jdk version
-        std::fs::remove_dir_all(&target_dir).unwrap();
-    }
-    if cfg!(target_family = "windows") {
-        extract_zip(&archive_file_path, target_dir, true);
-    } else {
-        if cfg!(target_os = "macos") {
-            extract_tgz_from_sub_path(&archive_file_path, target_dir, "Contents/Home/");
+pub fn install_jdk(java_version: &str, target_dir: &Path) -> Result<()> {
+    let package = get_jdk_package(java_version)?;
+    let checksum = package.sha256()?
+        .ok_or_else(|| Error::NotFound(format!("SHA-256 checksum of JDK {}", java_version)))?;
+    let (Some(parent_dir), Some(dir_name)) = (target_dir.parent(), target_dir.file_name()) else {
+        return Err(Error::Io(io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid install directory: {}", target_dir.display()))));
+    };
+    let archive_type = if cfg!(target_os = "windows") { "zip" } else { "tar.gz" };
+    // the archive is next to the install lock of `target_dir`, and interrupted download will be resumed
+    // from `<archive>.part` by next install of the same directory
+    let archive_file_path = parent_dir.join(format!(".{}.jdk.{}", dir_name.to_string_lossy(), archive_type));
+    // extract into a staging directory, and replace old jdk version only after extraction succeeded
+    download_and_install(&package.direct_download_uri, &archive_file_path, target_dir, |archive_file_path, staging_dir| {
+        verify_sha256(archive_file_path, &checksum)?;
+        if cfg!(target_family = "windows") {
+            extract_zip(archive_file_path, staging_dir, true)
+        } else if cfg!(target_os = "macos") {
+            extract_tgz_from_sub_path(archive_file_path, staging_dir, "Contents/Home/")
         } else {
-            extract_tgz(&archive_file_path, target_dir, true);
+            extract_tgz(archive_file_path, staging_dir, true)
         }
+    })
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PackageIdsResponse {
+    pub result: Vec<PackageId>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PackageId {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PackageInfoResponse {
+    pub result: Vec<PackageInfo>,
+}
+
+/// download uri and checksum of a package, from `/disco/v3.0/ids/<id>`
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackageInfo {
+    pub direct_download_uri: String,
+    #[serde(default)]
+    pub checksum: String,
+    #[serde(default)]
+    pub checksum_type: String,
+    #[serde(default)]
+    pub checksum_uri: String,
+}
+
+impl PackageInfo {
+    /// SHA-256 checksum of the package, fetched from `checksum_uri` if it's not in the package info
+    pub fn sha256(&self) -> Result<Option<String>> {
+        if self.checksum_type.eq_ignore_ascii_case("sha256") && !self.checksum.is_empty() {
+            return Ok(parse_sha256(&self.checksum));
+        }
+        if self.checksum_uri.is_empty() {
+            return Ok(None);
+        }
+        Ok(parse_sha256(&http_text(&self.checksum_uri)?))
     }
-    std::fs::remove_file(&archive_file_path).unwrap();
+}
+
+/// hex SHA-256 from checksum text, such as `<sha256>  OpenJDK21U-jdk_x64_linux_hotspot_21.0.2_13.tar.gz`
+fn parse_sha256(text: &str) -> Option<String> {
+    text.split_whitespace()
+        .next()
+        .filter(|checksum| checksum.len() == 64 && checksum.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(|checksum| checksum.to_lowercase())
+}
+
+/// latest JDK package of the version for the current platform
+pub fn get_jdk_package(java_version: &str) -> Result<PackageInfo> {
+    let packages = http_get(&get_jdk_packages_url(java_version)?)?.json::<PackageIdsResponse>()?.result;
+    let Some(package) = packages.first() else {
+        return Err(Error::NotFound(format!("JDK {}", java_version)));
+    };
+    let info_url = format!("https://api.foojay.io/disco/v3.0/ids/{}", package.id);
+    http_get(&info_url)?.json::<PackageInfoResponse>()?.result.into_iter().next()
+        .ok_or_else(|| Error::NotFound(format!("JDK package {}", package.id)))
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -84,15 +150,15 @@ pub struct FoojayJDK {
     pub distribution: String,
 }
 
-pub fn list_jdk(distro: &str, release_status: &str) -> Vec<FoojayJDK> {
-    let platform_params = get_platform_params(distro);
+pub fn list_jdk(distro: &str, release_status: &str) -> Result<Vec<FoojayJDK>> {
+    let platform_params = get_platform_params(distro)?;
     let extra_query = platform_params.iter().map(|(k, v)| {
         format!("{}={}", k, v)
     }).join("&");
     let url = format!("https://api.foojay.io/disco/v3.0/packages?release_status={}&package_type=jdk&latest=available&{}", release_status, extra_query);
-    let mut jdks = reqwest::blocking::get(&url).unwrap().json::<PackagesResponse>().unwrap().result;
+    let mut jdks = http_get(&url)?.json::<PackagesResponse>()?.result;
     jdks.dedup_by(|a, b| a.major_version == b.major_version);
-    jdks
+    Ok(jdks)
 }
 
 #[cfg(test)]
@@ -102,12 +168,25 @@ mod tests {
     #[test]
     fn test_get_download_url() {
         let java_version = "21";
-        println!("{}", get_jdk_download_url(java_version));
+        println!("{}", get_jdk_download_url(java_version).unwrap());
+    }
+
+    #[test]
+    fn test_parse_package_info() {
+        let text = r#"{"result":[{"filename":"OpenJDK21U-jdk_x64_linux_hotspot_21.0.2_13.tar.gz",
+            "direct_download_uri":"https://github.com/adoptium/temurin21-binaries/releases/download/jdk-21.0.2%2B13/OpenJDK21U-jdk_x64_linux_hotspot_21.0.2_13.tar.gz",
+            "checksum":"454BEBB2C9FE48D981341461FFB6BF1017C7B7C6E15C6B0C29B959194BA3AAA5","checksum_type":"sha256","checksum_uri":""}]}"#;
+        let package = serde_json::from_str::<PackageInfoResponse>(text).unwrap().result.remove(0);
+        assert_eq!(package.sha256().unwrap().as_deref(), Some("454bebb2c9fe48d981341461ffb6bf1017c7b7c6e15c6b0c29b959194ba3aaa5"));
+        let checksum_text = "454bebb2c9fe48d981341461ffb6bf1017c7b7c6e15c6b0c29b959194ba3aaa5  OpenJDK21U-jdk_x64_linux_hotspot_21.0.2_13.tar.gz\n";
+        assert!(parse_sha256(checksum_text).is_some());
+        assert!(parse_sha256("").is_none());
+        assert!(parse_sha256("d41d8cd98f00b204e9800998ecf8427e").is_none());
     }
 
     #[test]
     fn test_list_jdk() {
-        let jdks = list_jdk("temurin", "ga");
+        let jdks = list_jdk("temurin", "ga").unwrap();
         for jdk in &jdks {
             println!("{}:{}", jdk.major_version, jdk.java_version);
         }
@@ -0,0 +1,311 @@
+//! Shared HTTP client factory.
+//!
+//! All HTTP traffic should go through `http_client()` or `http_client_builder()` to honor:
+//!
+//! - proxies: `HTTPS_PROXY`/`HTTP_PROXY`/`NO_PROXY` environment variables, then active proxy in `~/.m2/settings.xml`
+//! - custom CA bundle: `WUKONG_CA_BUNDLE` or `SSL_CERT_FILE`, PEM file merged with system roots
+//! - insecure mode: `set_insecure(true)`, `jbang --insecure` or `sdkman_insecure_ssl=true`
+//! - timeouts: `set_timeouts(connect, max_time)`, `sdkman_curl_connect_timeout` and `sdkman_curl_max_time`
+//! - mirrors: `<mirrors>` for Maven Central in `~/.m2/settings.xml` and `WUKONG_MIRRORS`,
+//!   such as `WUKONG_MIRRORS=https://repo1.maven.org/maven2=https://nexus.example.com/repository/central`
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+use reqwest::blocking::{Client, ClientBuilder, Response};
+use reqwest::{Certificate, NoProxy, Proxy};
+use serde::Deserialize;
+use crate::error::{Error, Result};
+
+const MAVEN_CENTRAL_URLS: [&str; 2] = [
+    "https://repo1.maven.org/maven2",
+    "https://repo.maven.apache.org/maven2",
+];
+
+static INSECURE: AtomicBool = AtomicBool::new(false);
+static CONNECT_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(0);
+static MAX_TIME_SECS: AtomicU64 = AtomicU64::new(0);
+static HTTP_CONFIG: OnceLock<HttpConfig> = OnceLock::new();
+static HTTP_CLIENT: OnceLock<Client> = OnceLock::new();
+
+/// accept invalid certificates, should be called before the first request
+pub fn set_insecure(insecure: bool) {
+    INSECURE.store(insecure, Ordering::Relaxed);
+}
+
+pub fn is_insecure() -> bool {
+    INSECURE.load(Ordering::Relaxed)
+}
+
+/// timeouts in seconds for the shared client, 0 for default, should be called before the first request.
+/// Downloads are not limited by max time.
+pub fn set_timeouts(connect_secs: u64, max_time_secs: u64) {
+    CONNECT_TIMEOUT_SECS.store(connect_secs, Ordering::Relaxed);
+    MAX_TIME_SECS.store(max_time_secs, Ordering::Relaxed);
+}
+
+pub fn connect_timeout() -> Option<Duration> {
+    Some(CONNECT_TIMEOUT_SECS.load(Ordering::Relaxed)).filter(|secs| *secs > 0).map(Duration::from_secs)
+}
+
+fn max_time() -> Option<Duration> {
+    Some(MAX_TIME_SECS.load(Ordering::Relaxed)).filter(|secs| *secs > 0).map(Duration::from_secs)
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HttpConfig {
+    pub ca_bundle: Option<PathBuf>,
+    pub proxy: Option<ProxyConfig>,
+    /// url prefix rewrites, `(from, to)`
+    pub mirrors: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProxyConfig {
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// comma separated hosts, `NO_PROXY` format
+    pub non_proxy_hosts: Option<String>,
+}
+
+impl HttpConfig {
+    /// load config from environment variables and `~/.m2/settings.xml`
+    pub fn load() -> Self {
+        let mut config = dirs::home_dir()
+            .map(|home| home.join(".m2").join("settings.xml"))
+            .filter(|path| path.exists())
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|xml| HttpConfig::from_maven_settings(&xml))
+            .unwrap_or_default();
+        if let Ok(ca_bundle) = std::env::var("WUKONG_CA_BUNDLE").or_else(|_| std::env::var("SSL_CERT_FILE")) {
+            config.ca_bundle = Some(PathBuf::from(ca_bundle));
+        }
+        if let Ok(mirrors) = std::env::var("WUKONG_MIRRORS") {
+            // mirrors from environment take precedence over settings.xml
+            let mut env_mirrors = parse_mirrors(&mirrors);
+            env_mirrors.extend(config.mirrors);
+            config.mirrors = env_mirrors;
+        }
+        config
+    }
+
+    /// parse active proxy and Maven Central mirrors from Maven `settings.xml`
+    pub fn from_maven_settings(xml: &str) -> Self {
+        let mut config = HttpConfig::default();
+        let settings: MavenSettings = match quick_xml::de::from_str(xml) {
+            Ok(settings) => settings,
+            Err(_) => return config,
+        };
+        if let Some(proxies) = settings.proxies {
+            config.proxy = proxies.proxy.iter()
+                .filter(|proxy| proxy.active.as_deref().unwrap_or("true") == "true")
+                .find(|proxy| proxy.host.is_some())
+                .map(|proxy| ProxyConfig {
+                    url: format!("{}://{}:{}",
+                                 proxy.protocol.as_deref().unwrap_or("http"),
+                                 proxy.host.as_deref().unwrap_or_default(),
+                                 proxy.port.unwrap_or(80)),
+                    username: proxy.username.clone(),
+                    password: proxy.password.clone(),
+                    // Maven uses `|` as separator and `*.example.com` for domains
+                    non_proxy_hosts: proxy.non_proxy_hosts.as_ref()
+                        .map(|hosts| hosts.split('|').map(|host| host.trim().trim_start_matches('*')).collect::<Vec<_>>().join(",")),
+                });
+        }
+        if let Some(mirrors) = settings.mirrors {
+            for mirror in &mirrors.mirror {
+                let mirror_of = mirror.mirror_of.split(',').map(|item| item.trim()).collect::<Vec<_>>();
+                if !mirror_of.contains(&"!central") && (mirror_of.contains(&"central") || mirror_of.contains(&"*")) {
+                    for central_url in MAVEN_CENTRAL_URLS {
+                        config.mirrors.push((central_url.to_string(), mirror.url.trim_end_matches('/').to_string()));
+                    }
+                    break;
+                }
+            }
+        }
+        config
+    }
+
+    /// rewrite url with the first matched mirror
+    pub fn rewrite_url(&self, url: &str) -> String {
+        for (from, to) in &self.mirrors {
+            if let Some(rest) = url.strip_prefix(from.as_str()) && (rest.is_empty() || rest.starts_with('/') || rest.starts_with('?')) {
+                return format!("{}{}", to, rest);
+            }
+        }
+        url.to_string()
+    }
+}
+
+/// parse `from=to` pairs separated by `,`
+fn parse_mirrors(text: &str) -> Vec<(String, String)> {
+    text.split(',')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(from, to)| (from.trim().trim_end_matches('/').to_string(), to.trim().trim_end_matches('/').to_string()))
+        .filter(|(from, to)| !from.is_empty() && !to.is_empty())
+        .collect()
+}
+
+pub fn http_config() -> &'static HttpConfig {
+    HTTP_CONFIG.get_or_init(HttpConfig::load)
+}
+
+/// rewrite url with mirrors from `http_config()`
+pub fn rewrite_url(url: &str) -> String {
+    http_config().rewrite_url(url)
+}
+
+/// client builder with proxy, CA bundle and insecure mode applied
+pub fn http_client_builder() -> Result<ClientBuilder> {
+    let config = http_config();
+    let mut builder = Client::builder().user_agent(concat!("wukong/", env!("CARGO_PKG_VERSION")));
+    if is_insecure() {
+        builder = builder.tls_danger_accept_invalid_certs(true);
+    }
+    if let Some(connect_timeout) = connect_timeout() {
+        builder = builder.connect_timeout(connect_timeout);
+    }
+    if let Some(max_time) = max_time() {
+        builder = builder.timeout(max_time);
+    }
+    if let Some(ca_bundle) = &config.ca_bundle {
+        builder = builder.tls_certs_merge(load_ca_bundle(ca_bundle)?);
+    }
+    if let Some(proxy) = &config.proxy && !has_env_proxy() {
+        let mut http_proxy = Proxy::all(&proxy.url)
+            .map_err(|e| Error::Config(format!("Invalid proxy {}: {}", proxy.url, e)))?;
+        if let Some(username) = &proxy.username {
+            http_proxy = http_proxy.basic_auth(username, proxy.password.as_deref().unwrap_or_default());
+        }
+        if let Some(non_proxy_hosts) = &proxy.non_proxy_hosts {
+            http_proxy = http_proxy.no_proxy(NoProxy::from_string(non_proxy_hosts));
+        }
+        builder = builder.proxy(http_proxy);
+    }
+    Ok(builder)
+}
+
+/// shared client for simple requests
+pub fn http_client() -> Result<&'static Client> {
+    if let Some(client) = HTTP_CLIENT.get() {
+        return Ok(client);
+    }
+    let client = http_client_builder()?.build()?;
+    Ok(HTTP_CLIENT.get_or_init(|| client))
+}
+
+/// GET request with the shared client, url rewritten by mirrors
+pub fn http_get(url: &str) -> Result<Response> {
+    Ok(http_client()?.get(rewrite_url(url)).send()?)
+}
+
+fn has_env_proxy() -> bool {
+    ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"]
+        .iter()
+        .any(|name| std::env::var(name).is_ok_and(|value| !value.is_empty()))
+}
+
+fn load_ca_bundle(ca_bundle: &Path) -> Result<Vec<Certificate>> {
+    let pem = std::fs::read(ca_bundle)
+        .map_err(|e| Error::Config(format!("Failed to read CA bundle {}: {}", ca_bundle.display(), e)))?;
+    Certificate::from_pem_bundle(&pem)
+        .map_err(|e| Error::Config(format!("Invalid CA bundle {}: {}", ca_bundle.display(), e)))
+}
+
+#[derive(Debug, Deserialize)]
+struct MavenSettings {
+    proxies: Option<MavenProxies>,
+    mirrors: Option<MavenMirrors>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MavenProxies {
+    #[serde(default)]
+    proxy: Vec<MavenProxy>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MavenProxy {
+    active: Option<String>,
+    protocol: Option<String>,
+    host: Option<String>,
+    port: Option<u16>,
+    username: Option<String>,
+    password: Option<String>,
+    #[serde(rename = "nonProxyHosts")]
+    non_proxy_hosts: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MavenMirrors {
+    #[serde(default)]
+    mirror: Vec<MavenMirror>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MavenMirror {
+    #[serde(rename = "mirrorOf")]
+    mirror_of: String,
+    url: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTINGS_XML: &str = r#"<settings>
+  <proxies>
+    <proxy>
+      <id>inactive</id>
+      <active>false</active>
+      <host>inactive.example.com</host>
+    </proxy>
+    <proxy>
+      <id>corp</id>
+      <protocol>http</protocol>
+      <host>proxy.example.com</host>
+      <port>3128</port>
+      <username>jdoe</username>
+      <password>secret</password>
+      <nonProxyHosts>localhost|*.example.com</nonProxyHosts>
+    </proxy>
+  </proxies>
+  <mirrors>
+    <mirror>
+      <id>nexus</id>
+      <mirrorOf>*</mirrorOf>
+      <url>https://nexus.example.com/repository/central/</url>
+    </mirror>
+  </mirrors>
+</settings>"#;
+
+    #[test]
+    fn test_maven_settings() {
+        let config = HttpConfig::from_maven_settings(SETTINGS_XML);
+        let proxy = config.proxy.clone().unwrap();
+        assert_eq!(proxy.url, "http://proxy.example.com:3128");
+        assert_eq!(proxy.username.as_deref(), Some("jdoe"));
+        assert_eq!(proxy.non_proxy_hosts.as_deref(), Some("localhost,.example.com"));
+        assert_eq!(
+            config.rewrite_url("https://repo1.maven.org/maven2/dev/jbang/jbang.bin/0.138.0/jbang.bin-0.138.0-all.jar"),
+            "https://nexus.example.com/repository/central/dev/jbang/jbang.bin/0.138.0/jbang.bin-0.138.0-all.jar"
+        );
+        assert_eq!(
+            config.rewrite_url("https://search.maven.org/solrsearch/select?q=guava"),
+            "https://search.maven.org/solrsearch/select?q=guava"
+        );
+    }
+
+    #[test]
+    fn test_parse_mirrors() {
+        let config = HttpConfig {
+            mirrors: parse_mirrors("https://api.foojay.io=http://foojay.local/, https://repo1.maven.org/maven2 = http://nexus.local/central"),
+            ..HttpConfig::default()
+        };
+        assert_eq!(config.rewrite_url("https://api.foojay.io/disco/v3.0/packages"), "http://foojay.local/disco/v3.0/packages");
+        assert_eq!(config.rewrite_url("https://repo1.maven.org/maven2/a/b"), "http://nexus.local/central/a/b");
+        assert_eq!(config.rewrite_url("https://repo1.maven.org/maven22/a"), "https://repo1.maven.org/maven22/a");
+    }
+}
@@ -10,6 +10,8 @@ use std::io::Read;
 use std::path::Path;
 use std::{env, io};
 use walkdir::WalkDir;
+use wukong::error::exit_with_error;
+use wukong::http::http_get;
 use zip::ZipArchive;
 
 pub mod bytecode;
@@ -62,12 +64,13 @@ fn build_archive_from_local<P: AsRef<Path>>(path: P) -> ZipArchive<File> {
     ZipArchive::new(archive).unwrap()
 }
 
+/// download jar from url, exit with error message if failed
 fn build_archive_from_url(url: &str) -> ZipArchive<io::Cursor<Vec<u8>>> {
-    let mut res = reqwest::blocking::get(url).unwrap();
-    let mut buf: Vec<u8> = Vec::new();
-    let _ = res.read_to_end(&mut buf);
-    let reader = io::Cursor::new(buf);
-    ZipArchive::new(reader).unwrap()
+    let result = http_get(url).and_then(|res| {
+        let buf = res.error_for_status()?.bytes()?.to_vec();
+        Ok(ZipArchive::new(io::Cursor::new(buf))?)
+    });
+    result.unwrap_or_else(|e| exit_with_error("jarviz", &e))
 }
 
 fn scan_remote_archive(
@@ -101,11 +104,7 @@ fn scan_remote_archive(
 }
 
 fn archive_manifest_url(url: &str) -> Option<String> {
-    let mut res = reqwest::blocking::get(url).unwrap();
-    let mut buf: Vec<u8> = Vec::new();
-    let _ = res.read_to_end(&mut buf);
-    let reader = io::Cursor::new(buf);
-    let mut archive = ZipArchive::new(reader).unwrap();
+    let mut archive = build_archive_from_url(url);
     archive
         .by_name("META-INF/MANIFEST.MF")
         .ok()
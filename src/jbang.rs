@@ -23,6 +23,7 @@ use crate::jbang_cli::wrapper::manage_wrapper;
 use crate::jbang_cli::{jbang_home, print_command_help, JBANG_DEFAULT_JAVA_VERSION};
 use clap::ArgMatches;
 use itertools::Itertools;
+use wukong::error::exit_with_error;
 use wukong::foojay::install_jdk;
 
 pub const JBANG_SUB_COMMANDS: [&str; 17] = [
@@ -48,14 +49,18 @@ pub const JBANG_SUB_COMMANDS: [&str; 17] = [
 fn main() {
     let jbang_home = jbang_home();
     if !jbang_home.exists() {
-        install_jbang();
+        if let Err(e) = install_jbang() {
+            wukong::error::report_error("jbang", &e);
+        }
         // install default JDK
         let default_jdk_home = jbang_home
             .join("cache")
             .join("jdks")
             .join(JBANG_DEFAULT_JAVA_VERSION);
         if !default_jdk_home.exists() {
-            install_jdk(JBANG_DEFAULT_JAVA_VERSION, &default_jdk_home);
+            if let Err(e) = install_jdk(JBANG_DEFAULT_JAVA_VERSION, &default_jdk_home) {
+                wukong::error::report_error("jbang", &e);
+            }
         }
     }
     let args = std::env::args().collect::<Vec<String>>();
@@ -64,13 +69,13 @@ fn main() {
         // jbang run script_file
         let script_path = &args[2];
         if script_path == "-h" || script_path == "--help" {
-            print_command_help("run");
+            print_command_help("run").unwrap_or_else(|e| exit_with_error("jbang", &e));
             return;
         } else if !script_path.starts_with("-") {
             jbang_run(
                 &args[2],
                 &args[2..].iter().map(|s| s.as_str()).collect_vec(),
-            );
+            ).unwrap_or_else(|e| exit_with_error("jbang", &e));
             return;
         }
     } else if args.len() >= 2 {
@@ -85,7 +90,7 @@ fn main() {
             jbang_run(
                 &args[1],
                 &args[2..].iter().map(|s| s.as_str()).collect_vec(),
-            );
+            ).unwrap_or_else(|e| exit_with_error("jbang", &e));
             return;
         }
     }
@@ -93,6 +98,7 @@ fn main() {
     let matches = app.get_matches();
     // inject insecure
     inject_insecure(&matches);
+    wukong::common::set_quiet(matches.get_flag("quiet"));
     if let Some((command, command_matches)) = matches.subcommand() {
         match command {
             "run" => manage_run(command_matches),
@@ -125,13 +131,13 @@ fn main() {
         jbang_run(
             script_or_file,
             &params.iter().map(|s| s.as_str()).collect_vec(),
-        );
+        ).unwrap_or_else(|e| exit_with_error("jbang", &e));
     }
 }
 
 fn inject_insecure(matches: &ArgMatches) {
     if matches.get_flag("insecure") {
-        unsafe { std::env::set_var("ONEIO_ACCEPT_INVALID_CERTS", "true") }
+        wukong::http::set_insecure(true);
     }
 }
 
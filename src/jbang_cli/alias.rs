@@ -3,10 +3,11 @@ use crate::jbang_cli::{find_jbang_catalog_from_path, jbang_catalog};
 use clap::{Arg, Command};
 use colored::Colorize;
 use std::path::PathBuf;
+use wukong::error::exit_with_error;
 
 pub fn manage_alias(alias_matches: &clap::ArgMatches) {
     if let Some((sub_command, matches)) = alias_matches.subcommand() {
-        match sub_command {
+        let result = match sub_command {
             "add" => {
                 let name = matches.get_one::<String>("name").unwrap();
                 let script_ref = matches.get_one::<String>("scriptOrFile").unwrap().clone();
@@ -20,27 +21,31 @@ pub fn manage_alias(alias_matches: &clap::ArgMatches) {
                     description,
                     script_ref,
                 };
-                add_alias(name, alias);
+                add_alias(name, alias)
             }
             "remove" => {
                 let alias_name = matches.get_one::<String>("name").unwrap();
-                remove_alias(alias_name);
+                remove_alias(alias_name)
             }
             "list" => {
-                list_aliases();
+                list_aliases()
             }
-            _ => {}
+            _ => Ok(()),
+        };
+        if let Err(e) = result {
+            exit_with_error("jbang", &e);
         }
     }
 }
 
-pub fn list_aliases() {
-    let jbang_catalog = jbang_catalog();
+pub fn list_aliases() -> wukong::Result<()> {
+    let jbang_catalog = jbang_catalog()?;
     print_catalog_alias(&jbang_catalog);
     // list catalog from the current directory
     if let Some(project_catalog) = find_jbang_catalog_from_path(&PathBuf::from(".")) {
         print_catalog_alias(&project_catalog);
     }
+    Ok(())
 }
 
 fn print_catalog_alias(catalog: &JBangCatalog) {
@@ -60,16 +65,16 @@ fn print_catalog_alias(catalog: &JBangCatalog) {
     }
 }
 
-pub fn remove_alias(alias_name: &str) {
-    let mut catalog = jbang_catalog();
+pub fn remove_alias(alias_name: &str) -> wukong::Result<()> {
+    let mut catalog = jbang_catalog()?;
     catalog.remove_alias(alias_name);
-    catalog.write_default();
+    catalog.write_default()
 }
 
-pub fn add_alias(name: &str, alias: Alias) {
-    let mut catalog = jbang_catalog();
+pub fn add_alias(name: &str, alias: Alias) -> wukong::Result<()> {
+    let mut catalog = jbang_catalog()?;
     catalog.add_alias(name, alias);
-    catalog.write_default();
+    catalog.write_default()
 }
 
 pub fn get_description_value(script_ref: &str) -> Option<String> {
@@ -177,7 +182,7 @@ mod tests {
 
     #[test]
     fn test_list() {
-        list_aliases();
+        list_aliases().unwrap();
     }
 
     #[test]
@@ -189,13 +194,13 @@ mod tests {
                 "https://github.com/jbangdev/jbang-examples/blob/HEAD/examples/helloworld.java"
                     .to_string(),
         };
-        add_alias(name, alias);
+        add_alias(name, alias).unwrap();
     }
 
     #[test]
     fn test_remove() {
         let name = "hello";
-        remove_alias(name);
+        remove_alias(name).unwrap();
     }
 
     #[test]
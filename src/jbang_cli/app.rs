@@ -1,10 +1,12 @@
 use std::path::PathBuf;
 use clap::{Arg, Command};
 use crate::jbang_cli::{jbang_home, set_executable};
+use wukong::error::exit_with_error;
+use wukong::Error;
 
 pub fn manage_app(app_matches: &clap::ArgMatches) {
     if let Some((sub_command, matches)) = app_matches.subcommand() {
-        match sub_command {
+        let result = match sub_command {
             "install" => {
                 let script_or_file = matches.get_one::<String>("scriptOrFile").unwrap();
                 let command_name = if let Some(name) = matches.get_one::<String>("name") {
@@ -14,19 +16,20 @@ pub fn manage_app(app_matches: &clap::ArgMatches) {
                 } else {
                     script_or_file
                 };
-                install_app(command_name, script_or_file);
+                install_app(command_name, script_or_file)
             }
             "uninstall" => {
                 let name = matches.get_one::<String>("name").unwrap();
                 let command_path = jbang_home().join("bin").join(name);
                 if command_path.exists() {
-                    std::fs::remove_file(&command_path).unwrap();
+                    std::fs::remove_file(&command_path).map_err(Error::from)
                 } else {
-                    eprintln!("Command not found: {}", command_path.to_str().unwrap());
+                    eprintln!("Command not found: {}", command_path.display());
+                    Ok(())
                 }
             }
             "list" => {
-                list_apps();
+                list_apps()
             }
             "setup" => {
                 if which::which("jbang").is_ok() {
@@ -36,21 +39,28 @@ pub fn manage_app(app_matches: &clap::ArgMatches) {
                     let bin_path = bin_path.to_str().unwrap();
                     println!("Please add {} to PATH environment variable: export PATH=$PATH:{}", bin_path, bin_path);
                 }
+                Ok(())
             }
-            _ => println!("Unknown command"),
+            _ => {
+                println!("Unknown command");
+                Ok(())
+            }
+        };
+        if let Err(e) = result {
+            exit_with_error("jbang", &e);
         }
     }
 }
 
-pub fn list_apps() {
+pub fn list_apps() -> wukong::Result<()> {
     let bin_dir = jbang_home().join("bin");
     if bin_dir.exists() {
-        for entry in std::fs::read_dir(bin_dir).unwrap() {
-            let entry = entry.unwrap();
+        for entry in std::fs::read_dir(bin_dir)? {
+            let entry = entry?;
             let path = entry.path();
             if path.is_file() {
                 let file_name = entry.file_name();
-                let file_name = file_name.to_str().unwrap();
+                let file_name = file_name.to_string_lossy();
                 if !file_name.starts_with(".") &&
                     file_name != "jbang" && !file_name.starts_with("jbang.") {
                     println!("{}", file_name);
@@ -58,19 +68,21 @@ pub fn list_apps() {
             }
         }
     }
+    Ok(())
 }
-pub fn install_app(command_name: &str, script_or_file: &str) {
+pub fn install_app(command_name: &str, script_or_file: &str) -> wukong::Result<()> {
     let file_path = PathBuf::from(script_or_file);
     let script_path = if file_path.exists() {
-        let absolute_path = std::path::absolute(file_path).unwrap();
-        absolute_path.to_str().unwrap().to_string()
+        let absolute_path = std::path::absolute(file_path)?;
+        absolute_path.to_string_lossy().to_string()
     } else {
         script_or_file.to_string()
     };
     let command_path = jbang_home().join("bin").join(command_name);
     let code = format!("#!/bin/sh\nexec jbang run {} \"$@\"", script_path);
-    std::fs::write(&command_path, code).unwrap();
-    set_executable(&command_path);
+    std::fs::write(&command_path, code)?;
+    set_executable(&command_path)?;
+    Ok(())
 }
 
 pub fn build_app_command() -> Command {
@@ -148,11 +160,11 @@ mod tests {
 
     #[test]
     fn test_install_app() {
-        install_app("hello", "scripts/hello.java");
+        install_app("hello", "scripts/hello.java").unwrap();
     }
 
     #[test]
     fn test_list_apps() {
-        list_apps();
+        list_apps().unwrap();
     }
 }
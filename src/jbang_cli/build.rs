@@ -1,4 +1,5 @@
 use crate::jbang_cli::call_jbang_sub_command;
+use wukong::error::exit_with_error;
 use clap::{Arg, Command};
 use itertools::Itertools;
 
@@ -6,7 +7,7 @@ pub fn manage_build(build_matches: &clap::ArgMatches) {
     //let script_or_file = build_matches.get_one::<String>("scriptOrFile").unwrap();
     let args = std::env::args().collect::<Vec<String>>();
     let app_args = &args[2..].iter().map(|s| s.as_str()).collect_vec();
-    call_jbang_sub_command(&app_args);
+    call_jbang_sub_command(app_args).unwrap_or_else(|e| exit_with_error("jbang", &e));
 }
 pub fn build_build_command() -> Command {
     Command::new("build")
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use clap::{Arg, Command};
-use wukong::common::jbang_home;
+use crate::jbang_cli::jbang_home;
 
 pub fn manage_cache(cache_matches: &clap::ArgMatches) {
     if let Some((sub_command, matches)) = cache_matches.subcommand() {
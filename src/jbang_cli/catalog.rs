@@ -1,12 +1,13 @@
 use std::path::PathBuf;
 use clap::{Arg, Command};
 use colored::Colorize;
+use wukong::error::exit_with_error;
 use crate::jbang_cli::{call_jbang_sub_command, find_jbang_catalog_from_path, jbang_catalog};
 use crate::jbang_cli::models::{CatalogRef, JBangCatalog};
 
 pub fn manage_catalog(catalog_matches: &clap::ArgMatches) {
     if let Some((sub_command, matches)) = catalog_matches.subcommand() {
-        match sub_command {
+        let result = match sub_command {
             "add" => {
                 let name = matches.get_one::<String>("name").unwrap();
                 let file = matches.get_one::<String>("file").unwrap();
@@ -16,31 +17,35 @@ pub fn manage_catalog(catalog_matches: &clap::ArgMatches) {
                     description,
                     import_items: true,
                 };
-                add_catalog(name, catalog_ref);
+                add_catalog(name, catalog_ref)
             }
             "remove" => {
                 let name = matches.get_one::<String>("name").unwrap();
-                remove_catalog(name);
+                remove_catalog(name)
             }
             "list" => {
-                list_catalogs();
+                list_catalogs()
             }
             "update" => {
-                update_catalog();
+                update_catalog()
             }
-            _ => {}
+            _ => Ok(()),
+        };
+        if let Err(e) = result {
+            exit_with_error("jbang", &e);
         }
     }
 }
 
-pub fn list_catalogs() {
+pub fn list_catalogs() -> wukong::Result<()> {
     // list jbang level catalogs
-    let jbang_catalog = jbang_catalog();
+    let jbang_catalog = jbang_catalog()?;
     print_catalog(&jbang_catalog);
     // list catalog from current directory
     if let Some(project_catalog) = find_jbang_catalog_from_path(&PathBuf::from(".")) {
         print_catalog(&project_catalog);
     }
+    Ok(())
 }
 
 fn print_catalog(catalog: &JBangCatalog) {
@@ -55,20 +60,20 @@ fn print_catalog(catalog: &JBangCatalog) {
     }
 }
 
-pub fn add_catalog(name: &str, catalog_ref: CatalogRef) {
-    let mut jbang_catalog = jbang_catalog();
+pub fn add_catalog(name: &str, catalog_ref: CatalogRef) -> wukong::Result<()> {
+    let mut jbang_catalog = jbang_catalog()?;
     jbang_catalog.add_catalog(name, catalog_ref);
-    jbang_catalog.write_default();
+    jbang_catalog.write_default()
 }
 
-pub fn remove_catalog(name: &str) {
-    let mut jbang_catalog = jbang_catalog();
+pub fn remove_catalog(name: &str) -> wukong::Result<()> {
+    let mut jbang_catalog = jbang_catalog()?;
     jbang_catalog.remove_catalog(name);
-    jbang_catalog.write_default();
+    jbang_catalog.write_default()
 }
 
-pub fn update_catalog() {
-    call_jbang_sub_command(&["catalog", "update"]);
+pub fn update_catalog() -> wukong::Result<()> {
+    call_jbang_sub_command(&["catalog", "update"])
 }
 
 pub fn build_catalog_command() -> Command {
@@ -186,13 +191,13 @@ mod tests {
 
     #[test]
     fn test_list_catalogs() {
-        list_catalogs();
+        list_catalogs().unwrap();
     }
 
     #[test]
     fn test_remove_catalog() {
         let name = "demo";
-        remove_catalog(name);
+        remove_catalog(name).unwrap();
     }
 
     #[test]
@@ -203,6 +208,6 @@ mod tests {
             description: Some("Demo catalog".to_string()),
             import_items: true,
         };
-        add_catalog(name, catalog_ref);
+        add_catalog(name, catalog_ref).unwrap();
     }
 }
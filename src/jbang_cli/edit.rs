@@ -1,9 +1,10 @@
 use clap::{Arg, Command};
 use crate::jbang_cli::call_jbang_sub_command;
+use wukong::error::exit_with_error;
 
 pub fn manage_edit(edit_matches: &clap::ArgMatches) {
     let script_file = edit_matches.get_one::<String>("scriptOrFile").unwrap();
-    call_jbang_sub_command(&["edit", script_file.as_str()]);
+    call_jbang_sub_command(&["edit", script_file.as_str()]).unwrap_or_else(|e| exit_with_error("jbang", &e));
 }
 
 pub fn build_edit_command() -> Command {
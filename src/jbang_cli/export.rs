@@ -1,32 +1,33 @@
 use clap::{Arg, Command};
 use crate::jbang_cli::call_jbang_sub_command;
+use wukong::error::exit_with_error;
 
 pub fn manage_export(export_matches: &clap::ArgMatches) {
     if let Some((sub_command, matches)) = export_matches.subcommand() {
         match sub_command {
             "portable" => {
                 let script_or_file = matches.get_one::<String>("scriptOrFile").unwrap();
-                call_jbang_sub_command(&["export", "portable", script_or_file]);
+                call_jbang_sub_command(&["export", "portable", script_or_file]).unwrap_or_else(|e| exit_with_error("jbang", &e));
             }
             "local" => {
                 let script_or_file = matches.get_one::<String>("scriptOrFile").unwrap();
-                call_jbang_sub_command(&["export", "local", script_or_file]);
+                call_jbang_sub_command(&["export", "local", script_or_file]).unwrap_or_else(|e| exit_with_error("jbang", &e));
             }
             "mavenrepo" => {
                 let script_or_file = matches.get_one::<String>("scriptOrFile").unwrap();
-                call_jbang_sub_command(&["export", "mavenrepo", script_or_file]);
+                call_jbang_sub_command(&["export", "mavenrepo", script_or_file]).unwrap_or_else(|e| exit_with_error("jbang", &e));
             }
             "native" => {
                 let script_or_file = matches.get_one::<String>("scriptOrFile").unwrap();
-                call_jbang_sub_command(&["export", "native", script_or_file]);
+                call_jbang_sub_command(&["export", "native", script_or_file]).unwrap_or_else(|e| exit_with_error("jbang", &e));
             }
             "fatjar" => {
                 let script_or_file = matches.get_one::<String>("scriptOrFile").unwrap();
-                call_jbang_sub_command(&["export", "fatjar", script_or_file]);
+                call_jbang_sub_command(&["export", "fatjar", script_or_file]).unwrap_or_else(|e| exit_with_error("jbang", &e));
             }
             "jlink" => {
                 let script_or_file = matches.get_one::<String>("scriptOrFile").unwrap();
-                call_jbang_sub_command(&["export", "jlink", script_or_file]);
+                call_jbang_sub_command(&["export", "jlink", script_or_file]).unwrap_or_else(|e| exit_with_error("jbang", &e));
             }
             _ => {}
         }
@@ -1,20 +1,21 @@
 use clap::{Arg, Command};
 use crate::jbang_cli::call_jbang_sub_command;
+use wukong::error::exit_with_error;
 
 pub fn manage_info(info_matches: &clap::ArgMatches) {
     if let Some((sub_command, matches)) = info_matches.subcommand() {
         match sub_command {
             "tools" => {
                 let script_or_file = matches.get_one::<String>("scriptOrFile").unwrap();
-                call_jbang_sub_command(&["info", "tools", script_or_file]);
+                call_jbang_sub_command(&["info", "tools", script_or_file]).unwrap_or_else(|e| exit_with_error("jbang", &e));
             }
             "classpath" => {
                 let script_or_file = matches.get_one::<String>("scriptOrFile").unwrap();
-                call_jbang_sub_command(&["info", "classpath", script_or_file]);
+                call_jbang_sub_command(&["info", "classpath", script_or_file]).unwrap_or_else(|e| exit_with_error("jbang", &e));
             }
             "jar" => {
                 let script_or_file = matches.get_one::<String>("scriptOrFile").unwrap();
-                call_jbang_sub_command(&["info", "jar", script_or_file]);
+                call_jbang_sub_command(&["info", "jar", script_or_file]).unwrap_or_else(|e| exit_with_error("jbang", &e));
             }
             _ => {}
         }
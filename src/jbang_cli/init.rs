@@ -6,6 +6,7 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
+use wukong::error::exit_with_error;
 
 fn handlebars() -> Handlebars<'static> {
     let mut hbs = Handlebars::new();
@@ -50,14 +51,14 @@ pub fn manage_init(init_matches: &clap::ArgMatches) {
                 &api_key,
                 "gpt-5",
                 params.get(0).unwrap(),
-            );
+            ).unwrap_or_else(|e| exit_with_error("jbang", &e));
         } else if let Ok(api_key) = std::env::var("DEEPSEEK_API_KEY") {
             code = extract_code_from_openai(
                 "https://api.deepseek.com",
                 &api_key,
                 "deepseek-chat",
                 params.get(0).unwrap(),
-            );
+            ).unwrap_or_else(|e| exit_with_error("jbang", &e));
         }
         else {
             println!(
@@ -77,12 +78,13 @@ pub fn manage_init(init_matches: &clap::ArgMatches) {
             context.insert("fileName".to_string(), file_name);
             code = handlebars().render(template_name, &context).ok()
         } else {
-            call_jbang_sub_command(&["init", "-t", template_name, file_name.as_str()]);
+            call_jbang_sub_command(&["init", "-t", template_name, file_name.as_str()]).unwrap_or_else(|e| exit_with_error("jbang", &e));
         }
     }
     if let Some(code) = code {
-        std::fs::write(&script_file, code).unwrap();
-        set_executable(&script_file);
+        if let Err(e) = std::fs::write(&script_file, code).and_then(|_| set_executable(&script_file)) {
+            exit_with_error("jbang", &e.into());
+        }
         println!("Script file: {}", script_file);
     } else {
         println!("Please specify OPENAI_API_KEY environment variable to generate code from AI.");
@@ -116,7 +118,7 @@ pub struct AIMessage {
     pub content: String,
 }
 
-fn generate_code_from_openai(base_url: &str, api_key: &str, model: &str, question: &str) -> String {
+fn generate_code_from_openai(base_url: &str, api_key: &str, model: &str, question: &str) -> wukong::Result<String> {
     let request = json!({
       "model": model,
       "messages": [
@@ -130,18 +132,17 @@ fn generate_code_from_openai(base_url: &str, api_key: &str, model: &str, questio
         }
       ]
     });
-    let client = reqwest::blocking::Client::new();
+    let client = wukong::http::http_client()?;
     let chat_url = format!("{}/v1/chat/completions", base_url);
     let response = client
         .post(&chat_url)
         .json(&request)
         .header("Content-Type", "application/json")
         .header("Authorization", format!("Bearer {}", api_key))
-        .send()
-        .unwrap()
-        .json::<OpenAIResponse>()
-        .unwrap();
-    response.get_answer()
+        .send()?
+        .error_for_status()?
+        .json::<OpenAIResponse>()?;
+    Ok(response.get_answer())
 }
 
 fn extract_code_from_openai(
@@ -149,8 +150,8 @@ fn extract_code_from_openai(
     api_key: &str,
     model: &str,
     question: &str,
-) -> Option<String> {
-    let answer = generate_code_from_openai(base_url, api_key, model, question);
+) -> wukong::Result<Option<String>> {
+    let answer = generate_code_from_openai(base_url, api_key, model, question)?;
     let mut code_found = false;
     let mut code_lines: Vec<&str> = vec![];
     for line in answer.lines() {
@@ -168,9 +169,9 @@ fn extract_code_from_openai(
         if !code_lines.get(0).unwrap().starts_with("#!") {
             code_lines.insert(0, "///usr/bin/env jbang \"$0\" \"$@\" ; exit $?");
         }
-        return Some(code_lines.join("\n"));
+        return Ok(Some(code_lines.join("\n")));
     }
-    None
+    Ok(None)
 }
 
 pub fn build_init_command() -> Command {
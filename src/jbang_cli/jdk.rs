@@ -1,13 +1,10 @@
-use std::collections::HashMap;
 use std::fs;
-use std::fs::File;
-use std::io::BufReader;
-use std::path::{Path};
 use clap::{Arg, Command};
-use java_properties::PropertiesError;
 use serde::Serialize;
+use wukong::common::read_release;
 use wukong::foojay;
 use crate::build_jbang_app;
+use wukong::error::{exit_with_error, report_error};
 use wukong::foojay::install_jdk;
 use crate::jbang_cli::jbang_home;
 
@@ -126,7 +123,9 @@ pub fn manage_jdk(jdk_matches: &clap::ArgMatches) {
                 let versions: Vec<&str> = versions.split(',').collect();
                 for version in versions {
                     if !version.trim().is_empty() {
-                        install_jdk(version, &jbang_home_path.join("cache").join("jdks").join(version));
+                        if let Err(e) = install_jdk(version, &jbang_home_path.join("cache").join("jdks").join(version)) {
+                            report_error("jbang", &e);
+                        }
                     }
                 }
             }
@@ -135,7 +134,9 @@ pub fn manage_jdk(jdk_matches: &clap::ArgMatches) {
                 let jbang_home = jbang_home_path.to_str().unwrap();
                 let jdk_path = jbang_home_path.join("cache").join("jdks").join(version);
                 if !jdk_path.exists() {
-                    install_jdk(version, &jdk_path);
+                    if let Err(e) = install_jdk(version, &jdk_path) {
+                        exit_with_error("jbang", &e);
+                    }
                 }
                 println!("export PATH=\"{}/cache/jdks/{}/bin:$PATH\"", jbang_home, version);
                 println!("export JAVA_HOME=\"{}/cache/jdks/{}\"", jbang_home, version);
@@ -207,8 +208,14 @@ pub fn manage_jdk(jdk_matches: &clap::ArgMatches) {
 }
 
 fn list_available(show_details: bool, format: &str) {
-    let mut foojay_jdks = foojay::list_jdk("temurin", "ga");
-    foojay_jdks.extend(foojay::list_jdk("temurin", "ea"));
+    let mut foojay_jdks = match foojay::list_jdk("temurin", "ga") {
+        Ok(jdks) => jdks,
+        Err(e) => {
+            println!("Failed to list available JDKs: {}", e);
+            return;
+        }
+    };
+    foojay_jdks.extend(foojay::list_jdk("temurin", "ea").unwrap_or_default());
     foojay_jdks.sort_by(|a, b| {
         b.major_version.cmp(&a.major_version)
     });
@@ -239,18 +246,6 @@ fn list_available(show_details: bool, format: &str) {
     }
 }
 
-fn read_release(release_file: &Path) -> Result<HashMap<String, String>, PropertiesError> {
-    // Reading
-    let f = File::open(&release_file)?;
-    java_properties::read(BufReader::new(f)).map(|props| {
-        let mut map = HashMap::new();
-        for (key, value) in props {
-            map.insert(key, value.trim_matches(&['"', '\'']).to_string());
-        }
-        map
-    })
-}
-
 pub fn build_jdk_command() -> Command {
     Command::new("jdk")
         .about("Manage Java Development Kits installed by jbang.")
@@ -23,51 +23,54 @@ use std::fs::{File, Permissions};
 use std::io::Read;
 use std::path::{Path, PathBuf};
 use wukong::common::{http_download, run_command};
+use wukong::error::exit_with_error;
+use wukong::Error;
 use zip::ZipArchive;
 
 pub const JBANG_DEFAULT_JAVA_VERSION: &str = "17";
 
 pub fn jbang_home() -> PathBuf {
-    wukong::common::jbang_home()
+    wukong::common::jbang_home().unwrap_or_else(|e| exit_with_error("jbang", &e))
 }
 
-pub fn jbang_jar_path() -> PathBuf {
+pub fn jbang_jar_path() -> wukong::Result<PathBuf> {
     let path = jbang_home().join("bin").join("jbang.jar");
     if !path.exists() {
         http_download(
             "https://repo1.maven.org/maven2/dev/jbang/jbang.bin/0.138.0/jbang.bin-0.138.0-all.jar",
             &path,
-        );
+        )?;
         let version_file_path = jbang_home().join("version.txt");
-        std::fs::write(&version_file_path, "0.138.0").unwrap();
+        std::fs::write(&version_file_path, "0.138.0")?;
         // install_jbang();
     }
-    path
+    Ok(path)
 }
 
 pub fn jdk_home(jdk_version: &str) -> PathBuf {
     jbang_home().join("cache").join("jdks").join(jdk_version)
 }
 
-pub fn jbang_catalog() -> JBangCatalog {
+pub fn jbang_catalog() -> wukong::Result<JBangCatalog> {
     let jbang_catalog_json = jbang_home().join("jbang-catalog.json");
     if !jbang_catalog_json.exists() {
-        JBangCatalog {
+        Ok(JBangCatalog {
             catalogs: None,
             aliases: None,
             templates: None,
-        }
+        })
     } else {
-        serde_json::from_reader(File::open(jbang_catalog_json).unwrap()).unwrap()
+        serde_json::from_reader(File::open(&jbang_catalog_json)?)
+            .map_err(|e| Error::Parse(format!("Invalid {}: {}", jbang_catalog_json.display(), e)))
     }
 }
 
-pub fn builtin_jbang_catalog() -> JBangCatalog {
-    let jbang_jar_file_path = jbang_jar_path();
-    let archive = File::open(jbang_jar_file_path).unwrap();
-    let mut archive = ZipArchive::new(archive).unwrap();
-    let zip_file = archive.by_name("jbang-catalog.json").unwrap();
-    serde_json::from_reader(zip_file).unwrap()
+pub fn builtin_jbang_catalog() -> wukong::Result<JBangCatalog> {
+    let jbang_jar_file_path = jbang_jar_path()?;
+    let archive = File::open(jbang_jar_file_path)?;
+    let mut archive = ZipArchive::new(archive)?;
+    let zip_file = archive.by_name("jbang-catalog.json")?;
+    serde_json::from_reader(zip_file).map_err(|e| Error::Parse(format!("Invalid builtin jbang-catalog.json: {}", e)))
 }
 
 pub fn find_jbang_catalog_from_path(path: &PathBuf) -> Option<JBangCatalog> {
@@ -107,28 +110,29 @@ pub fn jbang_exec() -> PathBuf {
     }
 }
 
-pub fn ensure_jdk_available(jdk_version: &str) -> PathBuf {
+pub fn ensure_jdk_available(jdk_version: &str) -> wukong::Result<PathBuf> {
     let jdk_home = jbang_home().join("cache").join("jdks").join(jdk_version);
     if !jdk_home.exists() {
-        wukong::foojay::install_jdk(jdk_version, &jdk_home);
+        wukong::foojay::install_jdk(jdk_version, &jdk_home)?;
     }
-    jdk_home
+    Ok(jdk_home)
 }
 
-pub fn call_jbang_sub_command(commands: &[&str]) {
-    let java_home = ensure_jdk_available(JBANG_DEFAULT_JAVA_VERSION);
+pub fn call_jbang_sub_command(commands: &[&str]) -> wukong::Result<()> {
+    let java_home = ensure_jdk_available(JBANG_DEFAULT_JAVA_VERSION)?;
     unsafe {
         std::env::set_var("CLICOLOR_FORCE", "1");
     }
-    let jbang_jar_path = jbang_jar_path();
+    let jbang_jar_path = jbang_jar_path()?;
     let jbang_jar = jbang_jar_path.to_str().unwrap();
     let mut jbang_params = vec!["-classpath", jbang_jar, "dev.jbang.Main"];
     jbang_params.extend(commands);
-    run_command(&java_exec(&java_home), &jbang_params).unwrap();
+    run_command(&java_exec(&java_home), &jbang_params)?;
+    Ok(())
 }
 
-pub fn print_command_help(sub_command: &str) {
-    let java_home = ensure_jdk_available(JBANG_DEFAULT_JAVA_VERSION);
+pub fn print_command_help(sub_command: &str) -> wukong::Result<()> {
+    let java_home = ensure_jdk_available(JBANG_DEFAULT_JAVA_VERSION)?;
     unsafe {
         std::env::set_var("CLICOLOR_FORCE", "1");
     }
@@ -142,17 +146,20 @@ pub fn print_command_help(sub_command: &str) {
         sub_command,
         "--help",
     ];
-    run_command(&java_exec(&java_home), &jbang_params).unwrap();
+    run_command(&java_exec(&java_home), &jbang_params)?;
+    Ok(())
 }
 
 #[cfg(unix)]
-pub fn set_executable<P: AsRef<Path>>(path: P) {
+pub fn set_executable<P: AsRef<Path>>(path: P) -> std::io::Result<()> {
     use std::os::unix::fs::PermissionsExt;
-    std::fs::set_permissions(path, Permissions::from_mode(0o755)).unwrap();
+    std::fs::set_permissions(path, Permissions::from_mode(0o755))
 }
 
 #[cfg(not(unix))]
-pub fn set_executable<P: AsRef<Path>>(path: P) {}
+pub fn set_executable<P: AsRef<Path>>(path: P) -> std::io::Result<()> {
+    Ok(())
+}
 
 #[cfg(test)]
 mod tests {
@@ -160,7 +167,7 @@ mod tests {
 
     #[test]
     fn test_print_command_help() {
-        print_command_help("run");
+        print_command_help("run").unwrap();
     }
 
     #[test]
@@ -170,7 +177,7 @@ mod tests {
 
     #[test]
     fn test_builtin_jbang_catalog() {
-        let catalog = builtin_jbang_catalog();
+        let catalog = builtin_jbang_catalog().unwrap();
         println!("{:?}", catalog);
     }
 }
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::path::{Path};
 use serde::{Deserialize, Serialize};
+use wukong::Error;
 use crate::jbang_cli::jbang_home;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -59,12 +60,12 @@ impl JBangCatalog {
         }
     }
 
-    pub fn write<P: AsRef<Path>>(&self, catalog_file: P) {
-        serde_json::to_writer_pretty(std::fs::File::create(catalog_file).unwrap(), self).unwrap();
+    pub fn write<P: AsRef<Path>>(&self, catalog_file: P) -> wukong::Result<()> {
+        serde_json::to_writer_pretty(std::fs::File::create(catalog_file)?, self).map_err(|e| Error::Io(e.into()))
     }
 
-    pub fn write_default(&self) {
-        self.write(jbang_home().join("jbang-catalog.json"));
+    pub fn write_default(&self) -> wukong::Result<()> {
+        self.write(jbang_home().join("jbang-catalog.json"))
     }
 }
 
@@ -2,6 +2,7 @@ use crate::jbang_cli::{ensure_jdk_available, java_exec, jbang_home, jbang_jar_pa
 use clap::{Arg, Command};
 use itertools::Itertools;
 use wukong::common::{capture_command, run_command_line};
+use wukong::error::exit_with_error;
 
 pub fn manage_run(run_matches: &clap::ArgMatches) {
     let script_or_file = run_matches.get_one::<String>("scriptOrFile").unwrap();
@@ -11,12 +12,12 @@ pub fn manage_run(run_matches: &clap::ArgMatches) {
         .filter(|s| *s != "--")
         .map(|s| s.as_str())
         .collect_vec();
-    jbang_run(script_or_file, app_args);
+    jbang_run(script_or_file, app_args).unwrap_or_else(|e| exit_with_error("jbang", &e));
 }
-pub fn jbang_run(_script_or_file: &str, script_and_params: &[&str]) {
-    let jdk_home = ensure_jdk_available(JBANG_DEFAULT_JAVA_VERSION);
+pub fn jbang_run(_script_or_file: &str, script_and_params: &[&str]) -> wukong::Result<()> {
+    let jdk_home = ensure_jdk_available(JBANG_DEFAULT_JAVA_VERSION)?;
     let java_exec = java_exec(&jdk_home);
-    let jbang_jar = jbang_jar_path();
+    let jbang_jar = jbang_jar_path()?;
     // java -classpath $HOME/.jbang/bin/jbang.jar dev.jbang.Main run hello.java param1 param2
     let mut args = vec![
         "-classpath",
@@ -25,12 +26,13 @@ pub fn jbang_run(_script_or_file: &str, script_and_params: &[&str]) {
         "run",
     ];
     args.extend(script_and_params);
-    let output = capture_command(&java_exec, &args).unwrap();
+    let output = capture_command(&java_exec, &args)?;
     let exit_code = output.status.code().unwrap();
     if exit_code == 255 {
         // jbang code
         let app_command_line = String::from_utf8_lossy(&output.stdout);
-        run_command_line(app_command_line.trim()).unwrap();
+        run_command_line(app_command_line.trim())?;
+        Ok(())
     } else {
         eprintln!("{}", String::from_utf8_lossy(&output.stderr));
         std::process::exit(exit_code);
@@ -103,6 +105,6 @@ mod tests {
         jbang_run(
             "scripts/hello.java",
             &["scripts/hello.java", "first", "second"],
-        );
+        ).unwrap();
     }
 }
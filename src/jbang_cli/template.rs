@@ -4,6 +4,7 @@ use clap::{Arg, Command};
 use colored::Colorize;
 use lazy_static::lazy_static;
 use url::Url;
+use wukong::error::exit_with_error;
 use crate::jbang_cli::{builtin_jbang_catalog, jbang_catalog};
 use crate::jbang_cli::models::Template;
 
@@ -19,22 +20,25 @@ lazy_static! {
 }
 pub fn manage_template(template_matches: &clap::ArgMatches) {
     if let Some((sub_command, matches)) = template_matches.subcommand() {
-        match sub_command {
+        let result = match sub_command {
             "list" => {
-                list_templates();
+                list_templates()
             }
             "add" => {
-                add_template(matches);
+                add_template(matches)
             }
             "remove" => {
-                remove_template(matches);
+                remove_template(matches)
             }
-            _ => {}
+            _ => Ok(()),
+        };
+        if let Err(e) = result {
+            exit_with_error("jbang", &e);
         }
     }
 }
 
-pub fn add_template(matches: &clap::ArgMatches) {
+pub fn add_template(matches: &clap::ArgMatches) -> wukong::Result<()> {
     let name = matches.get_one::<String>("name").unwrap();
     let description = matches.get_one::<String>("description").map(|item| item.to_string());
     let file = matches.get_one::<String>("file").unwrap();
@@ -53,7 +57,7 @@ pub fn add_template(matches: &clap::ArgMatches) {
         }
     } else {
         let file_path = PathBuf::from(file);
-        let absolute_path = std::fs::canonicalize(&file_path).unwrap();
+        let absolute_path = std::fs::canonicalize(&file_path)?;
         let absolute_path = absolute_path.as_path();
         let ext_name = absolute_path.extension().unwrap().to_str().unwrap();
         file_refs.insert(format!("{{basename}}.{}", ext_name), absolute_path.to_str().unwrap().to_string());
@@ -63,25 +67,26 @@ pub fn add_template(matches: &clap::ArgMatches) {
         description: description,
         properties: None,
     };
-    let mut jbang_catalog = jbang_catalog();
+    let mut jbang_catalog = jbang_catalog()?;
     jbang_catalog.add_template(name, template);
-    jbang_catalog.write_default();
+    jbang_catalog.write_default()
 }
 
-pub fn remove_template(matches: &clap::ArgMatches) {
+pub fn remove_template(matches: &clap::ArgMatches) -> wukong::Result<()> {
     let name = matches.get_one::<String>("name").unwrap();
-    let mut jbang_catalog = jbang_catalog();
+    let mut jbang_catalog = jbang_catalog()?;
     if let Some(templates) = &jbang_catalog.templates {
         if templates.contains_key(name) {
             jbang_catalog.remove_template(name);
-            jbang_catalog.write_default();
+            jbang_catalog.write_default()?;
         }
     }
+    Ok(())
 }
 
-pub fn list_templates() {
+pub fn list_templates() -> wukong::Result<()> {
     // builtin templates
-    let builtin_jbang_catalog = builtin_jbang_catalog();
+    let builtin_jbang_catalog = builtin_jbang_catalog()?;
     if let Some(templates) = &builtin_jbang_catalog.templates {
         for (key, value) in templates.iter() {
             println!("{}", key.bold());
@@ -89,13 +94,14 @@ pub fn list_templates() {
         }
     }
     // user defined templates
-    let jbang_catalog = jbang_catalog();
+    let jbang_catalog = jbang_catalog()?;
     if let Some(templates) = &jbang_catalog.templates {
         for (key, value) in templates.iter() {
             println!("{}", key.bold());
             println!("  {}", value.description.as_ref().unwrap_or(&"No description".to_string()));
         }
     }
+    Ok(())
 }
 pub fn build_template_command() -> Command {
     Command::new("template")
@@ -2,17 +2,23 @@ use std::fs::File;
 use clap::{Arg, Command};
 use tar::Archive;
 use wukong::common::http_download;
+use wukong::error::exit_with_error;
+use wukong::http::http_get;
 use crate::jbang_cli::clap_app::VERSION;
 use crate::jbang_cli::jbang_home;
 
 pub fn manage_version(version_matches: &clap::ArgMatches) {
-    if version_matches.get_flag("check") {
-        check_jbang_version();
+    let result = if version_matches.get_flag("check") {
+        check_jbang_version()
     } else if version_matches.get_flag("update") {
         println!("[jbang] Downloading and updating jbang...");
-        install_jbang();
+        install_jbang()
     } else {
         display_version();
+        Ok(())
+    };
+    if let Err(e) = result {
+        exit_with_error("jbang", &e);
     }
 }
 
@@ -25,14 +31,14 @@ pub fn display_version() {
     println!("JBang-rs: {}", VERSION);
 }
 
-pub fn update_jbang() {
+pub fn update_jbang() -> wukong::Result<()> {
     println!("[jbang] Downloading and updating jbang...");
-    install_jbang();
+    install_jbang()
 }
 
-pub fn check_jbang_version() {
+pub fn check_jbang_version() -> wukong::Result<()> {
     let url = "https://github.com/jbangdev/jbang/releases/latest/download/version.txt";
-    let last_version = reqwest::blocking::get(url).unwrap().text().unwrap();
+    let last_version = http_get(url)?.error_for_status()?.text()?;
     let jbang_version = get_jbang_version();
     println!("{}", jbang_version);
     if jbang_version == last_version {
@@ -42,30 +48,29 @@ pub fn check_jbang_version() {
         println!("You have version {} and {} is the latest.", jbang_version, last_version);
         println!("Run 'jbang version --update' to update to the latest version.");
     }
+    Ok(())
 }
 
-pub fn install_jbang() {
+pub fn install_jbang() -> wukong::Result<()> {
     let download_url = "https://github.com/jbangdev/jbang/releases/latest/download/jbang.tar";
     let temp_dir = std::env::temp_dir();
     let target_file_path = temp_dir.join("jbang.tar");
-    http_download(&download_url, target_file_path.to_str().unwrap());
+    http_download(download_url, &target_file_path)?;
     let target_dir = jbang_home();
-    let tar_file = File::open(&target_file_path).unwrap();
+    let tar_file = File::open(&target_file_path)?;
     let mut archive = Archive::new(tar_file);
-    archive
-        .entries().unwrap()
-        .filter_map(|e| e.ok())
-        .for_each(|mut entry| {
-            let entry_path = entry.path().unwrap();
-            let mut relative_path = entry_path.to_str().unwrap();
-            if relative_path.starts_with("jbang/") || relative_path.starts_with("jbang\\") {
-                relative_path = &relative_path[(relative_path.find(std::path::MAIN_SEPARATOR).unwrap() + 1)..];
-            }
-            let path = target_dir.join(relative_path);
-            entry.set_preserve_mtime(true);
-            entry.unpack(&path).unwrap();
-        });
-    std::fs::remove_file(&target_file_path).unwrap();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_path = entry.path()?.to_string_lossy().to_string();
+        let relative_path = entry_path.strip_prefix("jbang/")
+            .or_else(|| entry_path.strip_prefix("jbang\\"))
+            .unwrap_or(&entry_path);
+        let path = target_dir.join(relative_path);
+        entry.set_preserve_mtime(true);
+        entry.unpack(&path)?;
+    }
+    std::fs::remove_file(&target_file_path)?;
+    Ok(())
 }
 
 pub fn build_version_command() -> Command {
@@ -93,11 +98,11 @@ mod tests {
 
     #[test]
     fn test_upgrade_jbang() {
-        update_jbang();
+        update_jbang().unwrap();
     }
 
     #[test]
     fn test_check_jbang_version() {
-        check_jbang_version();
+        check_jbang_version().unwrap();
     }
 }
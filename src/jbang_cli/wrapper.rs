@@ -1,5 +1,6 @@
 use crate::jbang_cli::alias::{add_alias, get_description_value, list_aliases, remove_alias};
 use crate::jbang_cli::call_jbang_sub_command;
+use wukong::error::exit_with_error;
 use crate::jbang_cli::models::Alias;
 use clap::{Arg, Command};
 use itertools::Itertools;
@@ -14,7 +15,7 @@ pub fn manage_wrapper(wrapper_matches: &clap::ArgMatches) {
                     let app_args = &args[3..].iter().map(|s| s.as_str()).collect_vec();
                     install_args.extend(app_args);
                 }
-                call_jbang_sub_command(install_args.as_slice());
+                call_jbang_sub_command(install_args.as_slice()).unwrap_or_else(|e| exit_with_error("jbang", &e));
             }
             _ => {}
         }
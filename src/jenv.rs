@@ -1,12 +1,21 @@
 use crate::jenv_cli::{add_command, commands_command, completion_command, global_command, local_command, remove_command, shell_command, version_command, versions_command, which_command};
 use crate::jenv_cli::clap_app::build_jenv_app;
+use crate::jenv_cli::options::options_command;
+use crate::jenv_cli::plugins::{disable_plugin_command, enable_plugin_command, plugins_command};
+use crate::jenv_cli::shims::{exec_command, exec_command_line, javahome_command, rehash_command, shim_name, shims_dir};
+use wukong::error::exit_with_error;
 
 mod jenv_cli;
 fn main() {
     let args = std::env::args().collect::<Vec<String>>();
+    // jenv invoked by a shim, such as `~/.jenv/shims/java`
+    if let Some(command) = args.first().and_then(|arg0| shim_name(arg0, &shims_dir())) {
+        exec_command(&command, &args[1..]);
+    }
     // jenv init scripts
     if args.len() >= 2 && args[1] == "init" {
-        jenv_cli::init();
+        let shell_name = args.iter().skip(2).map(|arg| arg.as_str()).find(|arg| *arg != "-");
+        jenv_cli::init(shell_name);
         return;
     }
     let app = build_jenv_app();
@@ -14,16 +23,23 @@ fn main() {
     if let Some((command, command_matches)) = matches.subcommand() {
         match command {
             "commands" => commands_command(),
-            "local" => local_command(command_matches),
-            "global" => global_command(command_matches),
+            "local" => local_command(command_matches).unwrap_or_else(|e| exit_with_error("jenv", &e)),
+            "global" => global_command(command_matches).unwrap_or_else(|e| exit_with_error("jenv", &e)),
             "shell" => shell_command(),
             "version" => version_command(),
-            "versions" => versions_command(),
+            "versions" => versions_command(command_matches),
             "which" => which_command(command_matches),
             "whence" => which_command(command_matches),
             "add" => add_command(command_matches),
             "remove" => remove_command(command_matches),
             "completion" => completion_command(command_matches),
+            "rehash" => rehash_command().unwrap_or_else(|e| exit_with_error("jenv", &e)),
+            "exec" => exec_command_line(command_matches),
+            "javahome" => javahome_command(),
+            "plugins" => plugins_command(),
+            "enable-plugin" => enable_plugin_command(command_matches),
+            "disable-plugin" => disable_plugin_command(command_matches),
+            "options" => options_command(command_matches).unwrap_or_else(|e| exit_with_error("jenv", &e)),
             &_ => println!("Unknown command"),
         }
     }
@@ -0,0 +1,319 @@
+//! jenv version names and aliases, as upstream jenv does.
+//!
+//! Every JDK in `~/.jenv/versions` has a canonical name, such as `temurin64-21.0.2`, and aliases linked to it:
+//! `21`, `21.0`, `21.0.2` and `openjdk64-21.0.2`. Aliases are relative symbolic links in `~/.jenv/versions`,
+//! and shorter aliases, such as `21`, link to the highest version.
+//! JDKs of other architectures, such as x86_64 JDKs on Apple Silicon, are named as `temurin64-x86_64-21.0.2`
+//! without aliases.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::{Path, PathBuf};
+use wukong::common::compare_versions;
+
+/// vendor in `java -version` output and jenv vendor name
+const VENDORS: [(&str, &str); 11] = [
+    ("Temurin", "temurin"),
+    ("Zulu", "zulu"),
+    ("Corretto", "corretto"),
+    ("GraalVM", "graalvm"),
+    ("BellSoft", "liberica"),
+    ("Microsoft", "microsoft"),
+    ("SapMachine", "sapmachine"),
+    ("Semeru", "semeru"),
+    ("Dragonwell", "dragonwell"),
+    ("JetBrains", "jetbrains"),
+    ("Oracle", "oracle"),
+];
+
+/// `IMPLEMENTOR` in `release` file and jenv vendor name
+const IMPLEMENTORS: [(&str, &str); 13] = [
+    ("Adoptium", "temurin"),
+    ("AdoptOpenJDK", "adoptopenjdk"),
+    ("Azul", "zulu"),
+    ("Amazon", "corretto"),
+    ("GraalVM", "graalvm"),
+    ("BellSoft", "liberica"),
+    ("Microsoft", "microsoft"),
+    ("SAP", "sapmachine"),
+    ("IBM", "semeru"),
+    ("Alibaba", "dragonwell"),
+    ("JetBrains", "jetbrains"),
+    ("Tencent", "kona"),
+    ("Oracle", "oracle"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JdkName {
+    /// vendor, such as `temurin`, `None` for plain version names such as `21`
+    pub vendor: Option<String>,
+    /// architecture of JDKs not for the current machine, such as `x86_64` on Apple Silicon
+    pub arch: Option<String>,
+    /// version with dots, such as `21.0.2` or `1.8.0.332`
+    pub version: String,
+}
+
+impl JdkName {
+    /// parse `temurin64-21.0.2`, `temurin64-x86_64-21.0.2`, `temurin-21`, `21.0.2` or `1.8.0_332`
+    pub fn parse(name: &str) -> Option<JdkName> {
+        let name = name.trim().to_lowercase();
+        let (vendor, version) = match name.split_once('-') {
+            Some((vendor, version)) if vendor.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                (Some(vendor.trim_end_matches("64").to_string()), version.to_string())
+            }
+            _ => (None, name.clone()),
+        };
+        let (arch, version) = match version.split_once('-') {
+            Some((arch, version)) if vendor.is_some() && arch.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                (Some(arch.to_string()), version.to_string())
+            }
+            _ => (None, version),
+        };
+        let version = version.replace('_', ".");
+        if version.is_empty() || !version.split('.').all(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())) {
+            return None;
+        }
+        Some(JdkName { vendor, arch, version })
+    }
+
+    /// name from `release` file of Java home: vendor from `IMPLEMENTOR`, version from `JAVA_RUNTIME_VERSION`,
+    /// and architecture from `OS_ARCH` if it's not the architecture of the current machine
+    pub fn from_release(release: &HashMap<String, String>) -> Option<JdkName> {
+        let runtime_version = release.get("JAVA_RUNTIME_VERSION").or_else(|| release.get("JAVA_VERSION"))?;
+        // `21.0.2+13-LTS` or `1.8.0_332-b09`
+        let version = runtime_version.split(['+', '-']).next()?;
+        let jdk_name = JdkName::parse(version)?;
+        let vendor = if release.contains_key("GRAALVM_VERSION") {
+            "graalvm".to_owned()
+        } else {
+            release.get("IMPLEMENTOR")
+                .and_then(|implementor| IMPLEMENTORS.iter().find(|(keyword, _)| implementor.contains(keyword)))
+                .map(|(_, vendor)| vendor.to_string())
+                .unwrap_or_else(|| "openjdk".to_owned())
+        };
+        let arch = release.get("OS_ARCH")
+            .map(|os_arch| normalize_arch(os_arch))
+            .filter(|arch| *arch != normalize_arch(std::env::consts::ARCH));
+        Some(JdkName { vendor: Some(vendor), arch, ..jdk_name })
+    }
+
+    /// canonical name, such as `temurin64-21.0.2` or `temurin64-x86_64-21.0.2`
+    pub fn canonical_name(&self) -> String {
+        let vendor = self.vendor.as_deref().unwrap_or("openjdk");
+        match &self.arch {
+            Some(arch) => format!("{}64-{}-{}", vendor, arch, self.version),
+            None => format!("{}64-{}", vendor, self.version),
+        }
+    }
+
+    /// aliases from the least to the most specific: `21`, `21.0`, `21.0.2`, and `openjdk64-21.0.2` for other vendors.
+    /// Java 8 and earlier have `8`, `1.8`, `1.8.0` and `1.8.0.332`, and JDKs of other architectures have no aliases.
+    pub fn aliases(&self) -> Vec<String> {
+        if self.arch.is_some() {
+            return vec![];
+        }
+        let numbers: Vec<&str> = self.version.split('.').collect();
+        let mut aliases = vec![];
+        let start = if numbers[0] == "1" && numbers.len() > 1 {
+            aliases.push(numbers[1].to_string());
+            2
+        } else {
+            1
+        };
+        for end in start..=numbers.len() {
+            aliases.push(numbers[..end].join("."));
+        }
+        if self.vendor.as_deref().is_some_and(|vendor| vendor != "openjdk") {
+            aliases.push(format!("openjdk64-{}", self.version));
+        }
+        aliases
+    }
+
+    /// query matches the version: same version or a version with the query as prefix, and same vendor if given.
+    /// `openjdk` matches all vendors, and plain version names, such as `21`, match all vendors too.
+    fn matches(&self, query: &JdkName) -> bool {
+        let version_matched = self.version == query.version || self.version.starts_with(&format!("{}.", query.version))
+            || (query.version.parse::<u32>().is_ok_and(|major| major < 9) && self.version.starts_with(&format!("1.{}.", query.version)));
+        let vendor_matched = match (query.vendor.as_deref(), self.vendor.as_deref()) {
+            (None | Some("openjdk"), _) | (_, None) => true,
+            (query_vendor, vendor) => vendor == query_vendor,
+        };
+        version_matched && vendor_matched && self.arch == query.arch
+    }
+}
+
+/// same name for aliases of architectures, such as `amd64` and `x86_64`
+fn normalize_arch(arch: &str) -> String {
+    match arch.to_lowercase().as_str() {
+        "amd64" | "x64" | "x86_64" => "x86_64".to_owned(),
+        "arm64" | "aarch64" => "aarch64".to_owned(),
+        "i386" | "i586" | "i686" | "x86" => "x86".to_owned(),
+        arch => arch.to_owned(),
+    }
+}
+
+/// vendor from `java -version` output, `openjdk` for OpenJDK builds without vendor
+pub fn detect_vendor(version_output: &str) -> String {
+    VENDORS.iter()
+        .find(|(keyword, _)| version_output.contains(keyword))
+        .map(|(_, vendor)| vendor.to_string())
+        .unwrap_or_else(|| if version_output.starts_with("java version") { "oracle".to_owned() } else { "openjdk".to_owned() })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionEntry {
+    pub name: String,
+    /// canonical name for aliases
+    pub alias_of: Option<String>,
+    pub path: PathBuf,
+}
+
+/// alias link: relative link to other entry in versions directory
+fn read_alias(path: &Path) -> Option<String> {
+    let link_target = path.read_link().ok()?;
+    if link_target.is_relative() && link_target.components().count() == 1 {
+        Some(link_target.to_string_lossy().to_string())
+    } else {
+        None
+    }
+}
+
+/// versions and aliases sorted by name, lock files and staging directories of installers are skipped
+pub fn list_versions(versions_dir: &Path) -> Vec<VersionEntry> {
+    let Ok(entries) = versions_dir.read_dir() else {
+        return vec![];
+    };
+    let mut versions: Vec<VersionEntry> = entries.flatten()
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .map(|entry| VersionEntry {
+            name: entry.file_name().to_string_lossy().to_string(),
+            alias_of: read_alias(&entry.path()),
+            path: entry.path(),
+        })
+        .collect();
+    versions.sort_by(|a, b| a.name.cmp(&b.name));
+    versions
+}
+
+/// canonical version for the query: the version or alias with the same name, otherwise the highest version matched
+/// by the query, such as `17`, `temurin-21` or `1.8`
+pub fn match_version(versions_dir: &Path, query: &str) -> Option<String> {
+    let versions = list_versions(versions_dir);
+    if let Some(entry) = versions.iter().find(|entry| entry.name == query) {
+        return Some(entry.alias_of.clone().unwrap_or_else(|| entry.name.clone()));
+    }
+    let query = JdkName::parse(query)?;
+    versions.iter()
+        .filter(|entry| entry.alias_of.is_none())
+        .filter_map(|entry| JdkName::parse(&entry.name).map(|jdk_name| (entry, jdk_name)))
+        .filter(|(_, jdk_name)| jdk_name.matches(&query))
+        .max_by(|(_, jdk_name1), (_, jdk_name2)| compare_versions(&jdk_name1.version, &jdk_name2.version))
+        .map(|(entry, _)| entry.name.clone())
+}
+
+/// aliases of all canonical versions, an alias links to the highest version with the alias
+pub fn expected_aliases(versions: &[VersionEntry]) -> BTreeMap<String, String> {
+    let mut canonical_versions: Vec<(&VersionEntry, JdkName)> = versions.iter()
+        .filter(|entry| entry.alias_of.is_none())
+        .filter_map(|entry| JdkName::parse(&entry.name).map(|jdk_name| (entry, jdk_name)))
+        .collect();
+    canonical_versions.sort_by(|(_, jdk_name1), (_, jdk_name2)| compare_versions(&jdk_name1.version, &jdk_name2.version));
+    let mut aliases = BTreeMap::new();
+    for (entry, jdk_name) in canonical_versions {
+        for alias in jdk_name.aliases() {
+            let is_canonical = versions.iter().any(|other| other.name == alias && other.alias_of.is_none());
+            if !is_canonical {
+                aliases.insert(alias, entry.name.clone());
+            }
+        }
+    }
+    aliases
+}
+
+/// create, update and remove alias links to match canonical versions
+pub fn sync_aliases(versions_dir: &Path) -> io::Result<()> {
+    let versions = list_versions(versions_dir);
+    let aliases = expected_aliases(&versions);
+    for entry in versions.iter().filter(|entry| entry.alias_of.is_some()) {
+        if aliases.get(&entry.name) != entry.alias_of.as_ref() {
+            symlink::remove_symlink_dir(&entry.path)?;
+        }
+    }
+    for (alias, canonical_name) in &aliases {
+        let alias_path = versions_dir.join(alias);
+        if !alias_path.is_symlink() {
+            symlink::symlink_dir(canonical_name, &alias_path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jdk_name() {
+        let jdk_name = JdkName::parse("temurin64-21.0.2").unwrap();
+        assert_eq!(jdk_name, JdkName { vendor: Some("temurin".to_owned()), arch: None, version: "21.0.2".to_owned() });
+        assert_eq!(jdk_name.aliases(), ["21", "21.0", "21.0.2", "openjdk64-21.0.2"]);
+        let jdk_name = JdkName::parse("1.8.0_332").unwrap();
+        assert_eq!(jdk_name.canonical_name(), "openjdk64-1.8.0.332");
+        assert_eq!(jdk_name.aliases(), ["8", "1.8", "1.8.0", "1.8.0.332"]);
+        assert_eq!(JdkName::parse("system"), None);
+        let jdk_name = JdkName::parse("temurin64-x86_64-21.0.2").unwrap();
+        assert_eq!(jdk_name.arch.as_deref(), Some("x86_64"));
+        assert_eq!(jdk_name.canonical_name(), "temurin64-x86_64-21.0.2");
+        assert!(jdk_name.aliases().is_empty());
+        assert_eq!(detect_vendor("openjdk version \"21.0.2\" 2024-01-16 LTS\nOpenJDK Runtime Environment Temurin-21.0.2+13"), "temurin");
+        assert_eq!(detect_vendor("openjdk version \"17.0.15\" 2025-04-15"), "openjdk");
+    }
+
+    #[test]
+    fn test_jdk_name_from_release() {
+        let release = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+        };
+        let host_arch = std::env::consts::ARCH;
+        let jdk_name = JdkName::from_release(&release(&[("IMPLEMENTOR", "Eclipse Adoptium"),
+            ("JAVA_RUNTIME_VERSION", "21.0.2+13-LTS"), ("OS_ARCH", host_arch)])).unwrap();
+        assert_eq!(jdk_name.canonical_name(), "temurin64-21.0.2");
+        let jdk_name = JdkName::from_release(&release(&[("IMPLEMENTOR", "Azul Systems, Inc."),
+            ("JAVA_RUNTIME_VERSION", "1.8.0_332-b09"), ("OS_ARCH", host_arch)])).unwrap();
+        assert_eq!(jdk_name.canonical_name(), "zulu64-1.8.0.332");
+        let jdk_name = JdkName::from_release(&release(&[("IMPLEMENTOR", "Debian"), ("JAVA_VERSION", "17.0.15")])).unwrap();
+        assert_eq!(jdk_name.canonical_name(), "openjdk64-17.0.15");
+        let other_arch = if host_arch == "riscv64" { "aarch64" } else { "riscv64" };
+        let jdk_name = JdkName::from_release(&release(&[("IMPLEMENTOR", "Oracle Corporation"), ("GRAALVM_VERSION", "23.1.2"),
+            ("JAVA_RUNTIME_VERSION", "21.0.2+13-jvmci-23.1-b30"), ("OS_ARCH", other_arch)])).unwrap();
+        assert_eq!(jdk_name.canonical_name(), format!("graalvm64-{}-21.0.2", other_arch));
+        assert_eq!(JdkName::from_release(&release(&[("IMPLEMENTOR", "Eclipse Adoptium")])), None);
+    }
+
+    #[test]
+    fn test_sync_aliases() {
+        let dir = std::env::temp_dir().join("wukong-jenv-aliases");
+        let _ = std::fs::remove_dir_all(&dir);
+        let versions_dir = dir.join("versions");
+        for name in ["temurin64-21.0.2", "zulu64-21.0.3", "temurin64-17.0.10", "openjdk64-1.8.0.332"] {
+            std::fs::create_dir_all(versions_dir.join(name).join("bin")).unwrap();
+        }
+        sync_aliases(&versions_dir).unwrap();
+        let aliases = |name: &str| versions_dir.join(name).read_link().unwrap().to_string_lossy().to_string();
+        assert_eq!(aliases("21"), "zulu64-21.0.3");
+        assert_eq!(aliases("21.0.2"), "temurin64-21.0.2");
+        assert_eq!(aliases("openjdk64-21.0.2"), "temurin64-21.0.2");
+        assert_eq!(aliases("8"), "openjdk64-1.8.0.332");
+        assert!(versions_dir.join("17").join("bin").exists());
+        assert_eq!(match_version(&versions_dir, "17"), Some("temurin64-17.0.10".to_owned()));
+        assert_eq!(match_version(&versions_dir, "temurin-21"), Some("temurin64-21.0.2".to_owned()));
+        assert_eq!(match_version(&versions_dir, "openjdk64-21.0.3"), Some("zulu64-21.0.3".to_owned()));
+        assert_eq!(match_version(&versions_dir, "11"), None);
+        // aliases follow removed versions
+        std::fs::remove_dir_all(versions_dir.join("zulu64-21.0.3")).unwrap();
+        sync_aliases(&versions_dir).unwrap();
+        assert_eq!(aliases("21"), "temurin64-21.0.2");
+        assert!(!versions_dir.join("21.0.3").is_symlink());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
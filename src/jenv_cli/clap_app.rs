@@ -1,4 +1,6 @@
 use clap::{Arg, Command};
+use crate::jenv_cli::options::build_options_command;
+use crate::jenv_cli::plugins::{build_disable_plugin_command, build_enable_plugin_command, build_plugins_command};
 
 pub const VERSION: &str = "0.2.0";
 
@@ -23,7 +25,14 @@ pub fn build_jenv_app() -> Command {
         .about("Set or show the shell-specific Java version");
 
     let versions_command = Command::new("versions")
-        .about("List all Java versions available to jenv");
+        .about("List all Java versions available to jenv")
+        .arg(
+            Arg::new("verbose")
+                .long("verbose")
+                .help("Show path and aliases of each version")
+                .num_args(0)
+                .required(false)
+        );
 
     let version_command = Command::new("version")
         .about("Show the current Java version and its origin");
@@ -44,12 +53,20 @@ pub fn build_jenv_app() -> Command {
                 .required(false)
         );
     let add_command = Command::new("add")
-        .about("Add JDK into jenv. A alias name will be generated by parsing \"java -version\"")
+        .about("Add JDK into jenv. The name is generated from IMPLEMENTOR, JAVA_RUNTIME_VERSION and OS_ARCH of the release file")
         .arg(
             Arg::new("versionOrPath")
                 .help("The java version or java home path")
                 .index(1)
                 .required(false)
+        )
+        .arg(
+            Arg::new("discover")
+                .long("discover")
+                .help("Add all JDKs found in JBang, SDKMAN, Gradle, IntelliJ and system locations")
+                .num_args(0)
+                .required(false)
+                .conflicts_with("versionOrPath")
         );
     let remove_command = Command::new("remove")
         .about("Remove JDK installations")
@@ -70,6 +87,27 @@ pub fn build_jenv_app() -> Command {
                 .required(true)
                 .value_parser(["bash", "zsh", "fish"])
         );
+    let rehash_command = Command::new("rehash")
+        .about("Create shims for all executables of Java versions in ~/.jenv/shims");
+    let exec_command = Command::new("exec")
+        .about("Run an executable with the active Java version")
+        .arg(
+            Arg::new("command")
+                .help("java command name")
+                .index(1)
+                .required(true)
+        )
+        .arg(
+            Arg::new("args")
+                .help("arguments of the command")
+                .index(2)
+                .num_args(0..)
+                .trailing_var_arg(true)
+                .allow_hyphen_values(true)
+                .required(false)
+        );
+    let javahome_command = Command::new("javahome")
+        .about("Display JAVA_HOME of the active Java version");
     Command::new("jenv")
         .version(VERSION)
         .about("sdk - Manage your Java environment")
@@ -84,4 +122,11 @@ pub fn build_jenv_app() -> Command {
         .subcommand(add_command)
         .subcommand(remove_command)
         .subcommand(completion_command)
+        .subcommand(rehash_command)
+        .subcommand(exec_command)
+        .subcommand(javahome_command)
+        .subcommand(build_plugins_command())
+        .subcommand(build_enable_plugin_command())
+        .subcommand(build_disable_plugin_command())
+        .subcommand(build_options_command())
 }
@@ -1,13 +1,21 @@
+pub mod aliases;
 pub mod clap_app;
+pub mod options;
+pub mod plugins;
+pub mod shims;
 
 use std::fs;
-use std::path::PathBuf;
-use wukong::common::capture_command;
+use std::io;
+use std::path::{Path, PathBuf};
+use wukong::common::{capture_command, discover_jdks, read_release, resolve_java_version_file, JdkLocation};
+use wukong::error::{exit_with_error, report_error};
 use wukong::foojay::install_jdk;
+use crate::jenv_cli::aliases::{detect_vendor, list_versions, match_version, sync_aliases, JdkName};
+use crate::jenv_cli::plugins::{enabled_plugins, export_hook, plugins_dir, Plugin};
+use crate::jenv_cli::shims::{find_executable, rehash_command, shims_dir};
 
 pub fn jenv_home() -> PathBuf {
-    let home = dirs::home_dir().unwrap();
-    home.join(".jenv")
+    wukong::common::home_dir().unwrap_or_else(|e| exit_with_error("jenv", &e)).join(".jenv")
 }
 
 fn get_shell_name() -> String {
@@ -18,21 +26,73 @@ fn get_shell_name() -> String {
     }
 }
 
-pub fn init() {
+/// init script for the shell, such as `jenv init -` or `jenv init - fish`, and `$SHELL` is used if not given
+pub fn init(shell_name: Option<&str>) {
     let jenv_home = jenv_home();
-    let shell_name = get_shell_name();
-    println!("export PATH=\"/{}/shims:${{PATH}}\"", jenv_home.to_str().unwrap());
-    println!("export JENV_SHELL={}", shell_name);
-    println!("export JENV_LOADED=1");
-    println!("unset JAVA_HOME");
-    println!("unset JDK_HOME");
-    println!("source <(jenv completion {})", shell_name);
+    let shell_name = shell_name.map(|name| name.to_string()).unwrap_or_else(get_shell_name);
+    if shell_name == "fish" {
+        println!("set -gx PATH \"{}/shims\" $PATH", jenv_home.to_str().unwrap());
+        println!("set -gx JENV_SHELL fish");
+        println!("set -gx JENV_LOADED 1");
+        println!("jenv completion fish | source");
+    } else {
+        println!("export PATH=\"{}/shims:${{PATH}}\"", jenv_home.to_str().unwrap());
+        println!("export JENV_SHELL={}", shell_name);
+        println!("export JENV_LOADED=1");
+        println!("source <(jenv completion {})", shell_name);
+    }
+    println!("command jenv rehash >/dev/null 2>&1");
+    if enabled_plugins(&plugins_dir()).contains(&Plugin::Export) {
+        println!("{}", export_hook(&shell_name));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VersionSource {
+    /// `JENV_VERSION` environment variable
+    Shell,
+    /// `.java-version` in current or parent directories
+    Local(PathBuf),
+    /// `~/.jenv/version`
+    Global(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveVersion {
+    pub version: String,
+    pub source: VersionSource,
+}
+
+impl ActiveVersion {
+    /// origin of the version, such as `set by /path/to/project/.java-version`
+    pub fn origin(&self) -> String {
+        match &self.source {
+            VersionSource::Shell => "set by JENV_VERSION environment variable".to_owned(),
+            VersionSource::Local(path) | VersionSource::Global(path) => format!("set by {}", path.display()),
+        }
+    }
+}
+
+/// active Java version: `JENV_VERSION`, then `.java-version` in current or parent directories, then global version
+pub fn resolve_version() -> Option<ActiveVersion> {
+    if let Ok(jenv_version) = std::env::var("JENV_VERSION") && !jenv_version.trim().is_empty() {
+        return Some(ActiveVersion { version: jenv_version.trim().to_string(), source: VersionSource::Shell });
+    }
+    if let Ok(current_dir) = std::env::current_dir() && let Some(version_file) = resolve_java_version_file(&current_dir) {
+        return Some(ActiveVersion { version: version_file.version, source: VersionSource::Local(version_file.path) });
+    }
+    let global_version_file = jenv_home().join("version");
+    fs::read_to_string(&global_version_file).ok()
+        .map(|version| version.trim().to_string())
+        .filter(|version| !version.is_empty())
+        .map(|version| ActiveVersion { version, source: VersionSource::Global(global_version_file) })
 }
 
 pub fn commands_command() {
     let commands = vec!["--version", "--help", "commands",
                         "local", "global", "shell", "version", "versions",
-                        "which", "whence", "add", "remove", "completion"];
+                        "which", "whence", "add", "remove", "completion", "rehash", "exec",
+                        "javahome", "plugins", "enable-plugin", "disable-plugin", "options"];
     println!("{}", commands.join(" "));
 }
 
@@ -56,35 +116,35 @@ pub fn completion_command(command_matches: &clap::ArgMatches) {
     }
 }
 
-pub fn local_command(command_matches: &clap::ArgMatches) {
+pub fn local_command(command_matches: &clap::ArgMatches) -> wukong::Result<()> {
     if let Some(version) = command_matches.get_one::<String>("version") {
-        fs::write(".java-version", version).unwrap();
+        fs::write(".java-version", version)?;
     } else {
-        let java_version_file = PathBuf::from(".java-version");
-        if java_version_file.exists() {
-            let version = fs::read_to_string(java_version_file).unwrap();
-            println!("{}", version);
-        } else {
-            println!("jenv: no local version configured for this directory");
+        match resolve_java_version_file(&std::env::current_dir()?) {
+            Some(version_file) => println!("{}", version_file.version),
+            None => println!("jenv: no local version configured for this directory"),
         }
     }
+    Ok(())
 }
 
-pub fn global_command(command_matches: &clap::ArgMatches) {
+pub fn global_command(command_matches: &clap::ArgMatches) -> wukong::Result<()> {
     if let Some(version) = command_matches.get_one::<String>("version") {
         let jenv_home = jenv_home();
         let global_version_file = jenv_home.join("version");
-        fs::write(global_version_file, version).unwrap();
+        fs::create_dir_all(&jenv_home)?;
+        fs::write(global_version_file, version)?;
     } else {
         let jenv_home = jenv_home();
         let global_version_file = jenv_home.join("version");
         if global_version_file.exists() {
-            let version = fs::read_to_string(global_version_file).unwrap();
+            let version = fs::read_to_string(global_version_file)?;
             println!("{}", version.trim());
         } else {
             println!("system");
         }
     }
+    Ok(())
 }
 
 pub fn shell_command() {
@@ -96,41 +156,49 @@ pub fn shell_command() {
 }
 
 pub fn version_command() {
-    if let Ok(jenv_version) = std::env::var("JENV_VERSION") {
-        println!("{} (set by JENV_VERSION environment variable)", jenv_version);
-    } else if PathBuf::from(".java-version").exists() {
-        let version = fs::read_to_string(".java-version").unwrap();
-        println!("{} (set by .java-version)", version.trim());
-    } else if jenv_home().join("version").exists() {
-        let jenv_home = jenv_home();
-        let version = fs::read_to_string(jenv_home.join("version")).unwrap();
-        println!("{}(set by global)", version.trim());
-    } else {
-        println!("system");
+    match resolve_version() {
+        Some(active_version) => println!("{} ({})", active_version.version, active_version.origin()),
+        None => println!("system"),
     }
 }
 
-pub fn versions_command() {
-    let jenv_home = jenv_home();
-    let versions_dir = jenv_home.join("versions");
-    let (current_version, reason) = if let Ok(jenv_version) = std::env::var("JENV_VERSION") {
-        (jenv_version, "set by JENV_VERSION environment variable".to_owned())
-    } else if PathBuf::from(".java-version").exists() {
-        let version = fs::read_to_string(".java-version").unwrap();
-        (version.trim().to_string(), "set by .java-version".to_owned())
+pub fn versions_command(command_matches: &clap::ArgMatches) {
+    let verbose = command_matches.get_flag("verbose");
+    let versions_dir = jenv_home().join("versions");
+    let (current_version, reason) = match resolve_version() {
+        Some(active_version) => (active_version.version.clone(), active_version.origin()),
+        None => ("".to_owned(), "".to_owned()),
+    };
+    if current_version.is_empty() || current_version == "system" {
+        println!("* system");
+    } else {
+        println!("  system");
+    }
+    let versions = list_versions(&versions_dir);
+    // fuzzy version, such as `temurin-21`, marks the matched version
+    let current_name = if !verbose && versions.iter().any(|entry| entry.name == current_version) {
+        Some(current_version.clone())
     } else {
-        ("".to_owned(), "".to_owned())
+        match_version(&versions_dir, &current_version)
     };
-    println!("  system");
-    if versions_dir.exists() {
-        for entry in fs::read_dir(versions_dir).unwrap() {
-            let entry = entry.unwrap();
-            let file_name = entry.file_name();
-            let java_version = file_name.to_str().unwrap();
-            if java_version == current_version {
-                println!("* {} ({})", java_version, reason);
-            } else {
-                println!("  {}", java_version);
+    for entry in &versions {
+        if verbose && entry.alias_of.is_some() {
+            continue;
+        }
+        if current_name.as_ref() == Some(&entry.name) {
+            println!("* {} ({})", entry.name, reason);
+        } else {
+            println!("  {}", entry.name);
+        }
+        if verbose {
+            let java_home = entry.path.canonicalize().unwrap_or_else(|_| entry.path.clone());
+            println!("      path: {}", java_home.display());
+            let aliases: Vec<&str> = versions.iter()
+                .filter(|alias| alias.alias_of.as_ref() == Some(&entry.name))
+                .map(|alias| alias.name.as_str())
+                .collect();
+            if !aliases.is_empty() {
+                println!("      aliases: {}", aliases.join(", "));
             }
         }
     }
@@ -138,20 +206,10 @@ pub fn versions_command() {
 
 pub fn which_command(command_matches: &clap::ArgMatches) {
     if let Some(command) = command_matches.get_one::<String>("command") {
-        if let Some(jenv_version) = std::env::var("JENV_VERSION").ok() {
-            let jenv_home = jenv_home();
-            let java_home = jenv_home.join("versions").join(&jenv_version);
-            println!("{}", java_home.join("bin").join(command).to_str().unwrap());
-        } else if PathBuf::from(".java-version").exists() {
-            let version = fs::read_to_string(".java-version").unwrap();
-            let jenv_home = jenv_home();
-            let java_home = jenv_home.join("versions").join(version.trim());
-            println!("{}", java_home.join("bin").join(command).to_str().unwrap());
-        } else if jenv_home().join("version").exists() {
-            let jenv_home = jenv_home();
-            let version = fs::read_to_string(jenv_home.join("version")).unwrap();
-            let java_home = jenv_home.join("versions").join(version.trim());
-            println!("{}", java_home.join("bin").join(command).to_str().unwrap());
+        let java_version = resolve_version().map(|active_version| active_version.version);
+        match find_executable(command, java_version.as_deref(), &jenv_home().join("versions"), &shims_dir()) {
+            Some(executable) => println!("{}", executable.display()),
+            None => println!("jenv: {}: command not found in version {}", command, java_version.as_deref().unwrap_or("system")),
         }
     }
 }
@@ -173,42 +231,189 @@ pub fn whence_command(command_matches: &clap::ArgMatches) {
 }
 
 pub fn add_command(command_matches: &clap::ArgMatches) {
-    if let Some(version_or_path) = command_matches.get_one::<String>("versionOrPath") {
+    let versions_dir = jenv_home().join("versions");
+    if command_matches.get_flag("discover") {
+        add_discovered_jdks(&versions_dir);
+    } else if let Some(version_or_path) = command_matches.get_one::<String>("versionOrPath") {
         if let Ok(num_version) = version_or_path.parse::<u32>() { // number
             let java_version = num_version.to_string();
-            let java_home = jenv_home().join("versions").join(&java_version);
-            if java_home.exists() {
-                println!("version {} already exists", num_version);
+            if let Some(name) = match_version(&versions_dir, &java_version) {
+                println!("version {} already exists: {}", num_version, name);
             } else {
                 println!("installing version {}", num_version);
-                install_jdk(&java_version, &java_home);
-                println!("version {} installed", num_version);
+                match install_version(&versions_dir, &java_version) {
+                    Ok(name) => {
+                        println!("version {} installed", name);
+                        add_aliases(&versions_dir, &name);
+                    }
+                    Err(e) => report_error("jenv", &e),
+                }
             }
         } else { // link java home with path
-            let java_install_path = PathBuf::from(version_or_path);
-            if java_install_path.exists() {
-                let java_exec = java_exec(&java_install_path);
-                if let Ok(output) = capture_command(&java_exec, &["-version"]) {
-                    let result = if !output.stdout.is_empty() {
-                        String::from_utf8_lossy(&output.stdout)
-                    } else {
-                        String::from_utf8_lossy(&output.stderr)
-                    };
-                    if result.is_empty() {
-                        println!("Failed to execute {} -version", java_exec);
-                        return;
-                    }
-                    let java_version = extract_java_version(&result);
-                    let java_home = jenv_home().join("versions").join(&java_version);
-                    symlink::symlink_dir(&java_install_path, &java_home).unwrap();
-                } else {
-                    println!("path {} is not a valid Java home", version_or_path);
+            match link_java_home(&versions_dir, Path::new(version_or_path)) {
+                Ok(name) => {
+                    println!("version {} added", name);
+                    add_aliases(&versions_dir, &name);
                 }
-            } else {
-                println!("path {} not exists", version_or_path);
+                Err(message) => println!("{}", message),
+            }
+        }
+    }
+}
+
+/// install Temurin JDK of the major version as `temurin64-<major>`, then rename it to the name from `release`,
+/// such as `temurin64-21.0.2`. The installed JDK is removed if the name exists already.
+fn install_version(versions_dir: &Path, java_version: &str) -> wukong::Result<String> {
+    let temp_name = format!("temurin64-{}", java_version);
+    let java_home = versions_dir.join(&temp_name);
+    install_jdk(java_version, &java_home)?;
+    let name = JdkName::from_release(&read_release(&java_home.join("release")).unwrap_or_default())
+        .map(|jdk_name| jdk_name.canonical_name())
+        .unwrap_or(temp_name);
+    rename_version(versions_dir, &java_home, &name)?;
+    Ok(name)
+}
+
+/// rename installed JDK to the version name, and the JDK is removed if the rename fails
+fn rename_version(versions_dir: &Path, java_home: &Path, name: &str) -> wukong::Result<()> {
+    let target = versions_dir.join(name);
+    if target == java_home {
+        return Ok(());
+    }
+    let result = if target.exists() || target.is_symlink() {
+        Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("version {} already exists: {}", name, target.display())))
+    } else {
+        fs::rename(java_home, &target)
+    };
+    if let Err(e) = result {
+        let _ = fs::remove_dir_all(java_home);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// link JDKs found in JBang, SDKMAN, Gradle, IntelliJ and system locations, and JDKs linked already are skipped
+fn add_discovered_jdks(versions_dir: &Path) {
+    let jdks = match discover_jdks() {
+        Ok(jdks) => jdks,
+        Err(e) => {
+            report_error("jenv", &e);
+            return;
+        }
+    };
+    let added = link_discovered_jdks(versions_dir, &jdks);
+    println!("{} JDKs added", added);
+    if added > 0 {
+        if let Err(e) = sync_aliases(versions_dir) {
+            eprintln!("jenv: failed to create aliases: {}", e);
+        }
+        if let Err(e) = rehash_command() {
+            report_error("jenv", &e);
+        }
+    }
+}
+
+/// link discovered JDKs not linked yet, and returns the number of added versions.
+/// A JDK with the same vendor and version as a linked one, such as the same JDK from JBang and SDKMAN, is skipped.
+fn link_discovered_jdks(versions_dir: &Path, jdks: &[JdkLocation]) -> usize {
+    let mut linked_paths: Vec<PathBuf> = list_versions(versions_dir).into_iter()
+        .filter(|entry| entry.alias_of.is_none())
+        .filter_map(|entry| entry.path.canonicalize().ok())
+        .collect();
+    let mut added = 0;
+    for jdk in jdks {
+        let Ok(java_home) = jdk.path.canonicalize() else {
+            continue;
+        };
+        if linked_paths.contains(&java_home) {
+            continue;
+        }
+        linked_paths.push(java_home.clone());
+        let name = match jdk_name_of(&java_home) {
+            Ok(jdk_name) => jdk_name.canonical_name(),
+            Err(message) => {
+                println!("{}", message);
+                continue;
+            }
+        };
+        let version_path = versions_dir.join(&name);
+        if version_path.exists() {
+            let linked_path = version_path.canonicalize().unwrap_or(version_path);
+            println!("{} from {} skipped: version {} is linked to {} with the same vendor and version",
+                     jdk.path.display(), jdk.source, name, linked_path.display());
+            continue;
+        }
+        match link_version(versions_dir, &java_home, &name) {
+            Ok(_) => {
+                println!("version {} added from {}: {}", name, jdk.source, jdk.path.display());
+                added += 1;
             }
+            Err(message) => println!("{}", message),
         }
     }
+    added
+}
+
+/// link the Java home into versions directory with the name from `release` file, and returns the name
+fn link_java_home(versions_dir: &Path, java_install_path: &Path) -> Result<String, String> {
+    if !java_install_path.exists() {
+        return Err(format!("path {} not exists", java_install_path.display()));
+    }
+    let name = jdk_name_of(java_install_path)?.canonical_name();
+    link_version(versions_dir, java_install_path, &name)?;
+    Ok(name)
+}
+
+/// link the Java home into versions directory with the version name
+fn link_version(versions_dir: &Path, java_install_path: &Path, name: &str) -> Result<(), String> {
+    let java_home = versions_dir.join(name);
+    if java_home.exists() {
+        return Err(format!("version {} already exists", name));
+    }
+    let java_install_path = java_install_path.canonicalize().unwrap_or_else(|_| java_install_path.to_path_buf());
+    fs::create_dir_all(versions_dir).map_err(|e| e.to_string())?;
+    symlink::symlink_dir(&java_install_path, &java_home).map_err(|e| e.to_string())
+}
+
+/// name of the Java home from `release` file, and from `java -version` output for JDKs without `release` file
+fn jdk_name_of(java_install_path: &Path) -> Result<JdkName, String> {
+    if let Some(jdk_name) = JdkName::from_release(&read_release(&java_install_path.join("release")).unwrap_or_default()) {
+        return Ok(jdk_name);
+    }
+    let java_exec = java_exec(&java_install_path.to_path_buf());
+    let Ok(output) = capture_command(&java_exec, &["-version"]) else {
+        return Err(format!("path {} is not a valid Java home", java_install_path.display()));
+    };
+    let result = if !output.stdout.is_empty() {
+        String::from_utf8_lossy(&output.stdout)
+    } else {
+        String::from_utf8_lossy(&output.stderr)
+    };
+    if result.is_empty() {
+        return Err(format!("Failed to execute {} -version", java_exec));
+    }
+    let java_version = extract_java_version(&result);
+    let Some(jdk_name) = JdkName::parse(&java_version) else {
+        return Err(format!("Failed to parse Java version {}", java_version));
+    };
+    Ok(JdkName { vendor: Some(detect_vendor(&result)), ..jdk_name })
+}
+
+/// refresh aliases and shims after a version added
+fn add_aliases(versions_dir: &Path, name: &str) {
+    if let Err(e) = sync_aliases(versions_dir) {
+        eprintln!("jenv: failed to create aliases: {}", e);
+    }
+    let aliases: Vec<String> = list_versions(versions_dir).into_iter()
+        .filter(|entry| entry.alias_of.as_deref() == Some(name))
+        .map(|entry| entry.name)
+        .collect();
+    if !aliases.is_empty() {
+        println!("aliases: {}", aliases.join(", "));
+    }
+    if let Err(e) = rehash_command() {
+        report_error("jenv", &e);
+    }
 }
 
 fn extract_java_version(text: &str) -> String {
@@ -223,13 +428,25 @@ fn extract_java_version(text: &str) -> String {
 
 pub fn remove_command(command_matches: &clap::ArgMatches) {
     let java_version = command_matches.get_one::<String>("version").unwrap();
-    let java_home = jenv_home().join("versions").join(&java_version);
-    if java_home.exists() {
+    let versions_dir = jenv_home().join("versions");
+    // an alias removes the version it links to, with all aliases
+    let canonical_name = list_versions(&versions_dir).into_iter()
+        .find(|entry| &entry.name == java_version)
+        .map(|entry| entry.alias_of.unwrap_or(entry.name));
+    if let Some(canonical_name) = canonical_name {
+        let java_home = versions_dir.join(&canonical_name);
         if java_home.is_symlink() {
             symlink::remove_symlink_dir(&java_home).unwrap();
         } else {
             fs::remove_dir_all(java_home).unwrap();
         }
+        println!("version {} removed", canonical_name);
+        if let Err(e) = sync_aliases(&versions_dir) {
+            eprintln!("jenv: failed to remove aliases: {}", e);
+        }
+        if let Err(e) = rehash_command() {
+            report_error("jenv", &e);
+        }
     } else {
         println!("version {} not exists", java_version);
     }
@@ -266,6 +483,58 @@ OpenJDK 64-Bit Server VM (build 21+35-2513, mixed mode, sharing)"#;
         add_command(matches.subcommand_matches("add").unwrap());
     }
 
+    #[test]
+    fn test_rename_version() {
+        let versions_dir = tempfile::tempdir().unwrap();
+        let versions_dir = versions_dir.path();
+        let java_home = versions_dir.join("temurin64-21");
+        std::fs::create_dir_all(java_home.join("bin")).unwrap();
+        rename_version(versions_dir, &java_home, "temurin64-21.0.2").unwrap();
+        assert!(versions_dir.join("temurin64-21.0.2").join("bin").is_dir());
+        assert!(!java_home.exists());
+        // existing name is kept, and the new install is removed
+        std::fs::create_dir_all(java_home.join("bin")).unwrap();
+        let result = rename_version(versions_dir, &java_home, "temurin64-21.0.2");
+        assert!(matches!(result, Err(wukong::Error::Io(ref e)) if e.kind() == io::ErrorKind::AlreadyExists));
+        assert!(!java_home.exists());
+        assert!(versions_dir.join("temurin64-21.0.2").join("bin").is_dir());
+        rename_version(versions_dir, &versions_dir.join("temurin64-21.0.2"), "temurin64-21.0.2").unwrap();
+    }
+
+    /// fake JDK with `release` file and `bin/java`
+    fn fake_jdk(java_home: &Path, implementor: &str, java_version: &str) {
+        std::fs::create_dir_all(java_home.join("bin")).unwrap();
+        std::fs::write(java_home.join("bin").join("java"), "").unwrap();
+        std::fs::write(java_home.join("release"), format!("IMPLEMENTOR=\"{}\"\nJAVA_VERSION=\"{}\"\n", implementor, java_version)).unwrap();
+    }
+
+    #[test]
+    fn test_link_discovered_jdks() {
+        let dir = tempfile::tempdir().unwrap();
+        let versions_dir = dir.path().join("versions");
+        let jbang_jdk = dir.path().join("jbang").join("21");
+        let sdkman_jdk = dir.path().join("sdkman").join("21.0.2-tem");
+        let zulu_jdk = dir.path().join("sdkman").join("17.0.10-zulu");
+        fake_jdk(&jbang_jdk, "Eclipse Adoptium", "21.0.2");
+        fake_jdk(&sdkman_jdk, "Eclipse Adoptium", "21.0.2");
+        fake_jdk(&zulu_jdk, "Azul Systems, Inc.", "17.0.10");
+        assert_eq!(jdk_name_of(&jbang_jdk).unwrap().canonical_name(), "temurin64-21.0.2");
+        assert!(jdk_name_of(&dir.path().join("missing")).is_err());
+        let jdks: Vec<JdkLocation> = [("JBang", &jbang_jdk), ("SDKMAN", &sdkman_jdk), ("SDKMAN", &zulu_jdk)].iter()
+            .map(|(source, path)| JdkLocation {
+                source,
+                name: path.file_name().unwrap().to_string_lossy().to_string(),
+                path: path.to_path_buf(),
+            })
+            .collect();
+        // the same vendor and version from SDKMAN is skipped
+        assert_eq!(link_discovered_jdks(&versions_dir, &jdks), 2);
+        assert_eq!(versions_dir.join("temurin64-21.0.2").canonicalize().unwrap(), jbang_jdk.canonicalize().unwrap());
+        assert!(versions_dir.join("zulu64-17.0.10").join("release").is_file());
+        assert_eq!(link_discovered_jdks(&versions_dir, &jdks), 0);
+        assert_eq!(link_java_home(&versions_dir, &sdkman_jdk), Err("version temurin64-21.0.2 already exists".to_owned()));
+    }
+
     #[test]
     fn test_remove() {
         let app = build_jenv_app();
@@ -0,0 +1,346 @@
+//! JVM options of Java versions and projects, injected into `JDK_JAVA_OPTIONS` by shims.
+//!
+//! - global: `~/.jenv/options/<version>`, options of the canonical version, such as `temurin64-21.0.2`
+//! - local: `.jenv-options` in the current or parent directories, options of the project
+//!
+//! Both files have options separated by whitespace, such as `--add-opens java.base/java.lang=ALL-UNNAMED`,
+//! and an option with whitespace is quoted. Lines starting with `#` are comments. A long option with a separate
+//! value is read as `--add-opens=java.base/java.lang=ALL-UNNAMED`, so duplicates are removed as one option.
+//! Options are merged in order: global, local, then `JDK_JAVA_OPTIONS` of the environment,
+//! and a later option wins because `java` uses the last one for options such as `-Xmx`.
+//!
+//! `JDK_JAVA_OPTIONS` is supported by the `java` launcher since Java 9, and Java 8 launchers ignore it.
+
+use crate::jenv_cli::aliases::match_version;
+use crate::jenv_cli::{jenv_home, resolve_version};
+use clap::{Arg, ArgAction, Command};
+use std::io;
+use std::path::{Path, PathBuf};
+use wukong::error::{Error, Result};
+
+pub const LOCAL_OPTIONS_FILE: &str = ".jenv-options";
+
+pub fn options_dir() -> PathBuf {
+    jenv_home().join("options")
+}
+
+/// global options file of the version, `system` for no version
+pub fn global_options_file(options_dir: &Path, java_version: Option<&str>, versions_dir: &Path) -> PathBuf {
+    let name = match java_version {
+        Some(java_version) if java_version != "system" => {
+            match_version(versions_dir, java_version).unwrap_or_else(|| java_version.to_string())
+        }
+        _ => "system".to_owned(),
+    };
+    options_dir.join(name)
+}
+
+/// nearest `.jenv-options` in the directory or its parents
+pub fn find_local_options_file(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|dir| dir.join(LOCAL_OPTIONS_FILE))
+        .find(|path| path.is_file())
+}
+
+pub fn read_options(path: &Path) -> Vec<String> {
+    let Ok(text) = std::fs::read_to_string(path) else {
+        return vec![];
+    };
+    let args = text.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .flat_map(split_options)
+        .collect();
+    join_option_values(args)
+}
+
+/// `--add-opens java.base/java.lang=ALL-UNNAMED` to `--add-opens=java.base/java.lang=ALL-UNNAMED`
+fn join_option_values(args: Vec<String>) -> Vec<String> {
+    let mut options: Vec<String> = vec![];
+    for arg in args {
+        match options.last_mut() {
+            Some(option) if option.starts_with("--") && !option.contains('=') && !arg.starts_with('-') => {
+                option.push('=');
+                option.push_str(&arg);
+            }
+            _ => options.push(arg),
+        }
+    }
+    options
+}
+
+/// write options, and the file is removed without options
+pub fn write_options(path: &Path, options: &[String]) -> io::Result<()> {
+    if options.is_empty() {
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut text = options.iter().map(|option| quote_option(option)).collect::<Vec<String>>().join("\n");
+    text.push('\n');
+    std::fs::write(path, text)
+}
+
+/// options joined in order with duplicates removed, and the last occurrence of a duplicate is kept
+pub fn merge_options(global_options: &[String], local_options: &[String], env_options: Option<&str>) -> Vec<String> {
+    let env_options: Vec<String> = env_options.map(split_options).unwrap_or_default();
+    let all_options: Vec<&String> = global_options.iter().chain(local_options).chain(&env_options).collect();
+    all_options.iter().enumerate()
+        .filter(|(index, option)| !all_options[index + 1..].contains(option))
+        .map(|(_, option)| option.to_string())
+        .collect()
+}
+
+/// split `JDK_JAVA_OPTIONS` or a line of options file by whitespace, and quoted text is kept as one option
+fn split_options(text: &str) -> Vec<String> {
+    let mut options = vec![];
+    let mut option = String::new();
+    let mut quote: Option<char> = None;
+    for c in text.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => option.push(c),
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c.is_whitespace() => {
+                if !option.is_empty() {
+                    options.push(std::mem::take(&mut option));
+                }
+            }
+            None => option.push(c),
+        }
+    }
+    if !option.is_empty() {
+        options.push(option);
+    }
+    options
+}
+
+fn quote_option(option: &str) -> String {
+    if option.contains(char::is_whitespace) { format!("\"{}\"", option) } else { option.to_string() }
+}
+
+/// value of `JDK_JAVA_OPTIONS`, and options with whitespace are quoted
+pub fn format_options(options: &[String]) -> String {
+    options.iter()
+        .map(|option| quote_option(option))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// `JDK_JAVA_OPTIONS` for the version in the directory, None without any options
+pub fn java_options_env(java_version: Option<&str>, versions_dir: &Path, options_dir: &Path, dir: &Path) -> Option<String> {
+    let global_options = read_options(&global_options_file(options_dir, java_version, versions_dir));
+    let local_options = find_local_options_file(dir).map(|path| read_options(&path)).unwrap_or_default();
+    let env_options = std::env::var("JDK_JAVA_OPTIONS").ok();
+    let options = merge_options(&global_options, &local_options, env_options.as_deref());
+    if options.is_empty() {
+        None
+    } else {
+        Some(format_options(&options))
+    }
+}
+
+/// options file to edit: the nearest `.jenv-options` or a new one in the current directory for `--local`,
+/// otherwise the global options file of the active version
+fn options_file_of(command_matches: &clap::ArgMatches) -> PathBuf {
+    if command_matches.get_flag("local") {
+        let current_dir = std::env::current_dir().unwrap();
+        find_local_options_file(&current_dir).unwrap_or_else(|| current_dir.join(LOCAL_OPTIONS_FILE))
+    } else {
+        let java_version = resolve_version().map(|active_version| active_version.version);
+        global_options_file(&options_dir(), java_version.as_deref(), &jenv_home().join("versions"))
+    }
+}
+
+fn get_options_arg(command_matches: &clap::ArgMatches) -> Vec<String> {
+    let args = command_matches.get_many::<String>("options")
+        .map(|options| options.cloned().collect())
+        .unwrap_or_default();
+    join_option_values(args)
+}
+
+/// `--local` and `--global` after `--` are taken as JVM options by clap, and they would break every java launch
+fn check_options(options: &[String]) -> Result<()> {
+    match options.iter().find(|option| *option == "--local" || *option == "--global") {
+        Some(option) => Err(Error::Config(format!("{} should be placed before --, such as `jenv options add {} -- -Xmx2g`", option, option))),
+        None => Ok(()),
+    }
+}
+
+pub fn options_command(command_matches: &clap::ArgMatches) -> Result<()> {
+    match command_matches.subcommand() {
+        Some(("add", add_matches)) => {
+            let new_options = get_options_arg(add_matches);
+            check_options(&new_options)?;
+            let options_file = options_file_of(add_matches);
+            let mut options = read_options(&options_file);
+            for option in new_options {
+                if options.contains(&option) {
+                    println!("{} already added", option);
+                } else {
+                    println!("{} added", option);
+                    options.push(option);
+                }
+            }
+            save_options(&options_file, &options)
+        }
+        Some(("remove", remove_matches)) => {
+            let removed_options = get_options_arg(remove_matches);
+            check_options(&removed_options)?;
+            let options_file = options_file_of(remove_matches);
+            let mut options = read_options(&options_file);
+            for option in removed_options {
+                if options.contains(&option) {
+                    println!("{} removed", option);
+                    options.retain(|existing| *existing != option);
+                } else {
+                    println!("{} not found", option);
+                }
+            }
+            save_options(&options_file, &options)
+        }
+        Some(("list", list_matches)) => {
+            list_options(list_matches);
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn save_options(options_file: &Path, options: &[String]) -> Result<()> {
+    write_options(options_file, options)
+        .map_err(|e| Error::Io(io::Error::new(e.kind(), format!("failed to write {}: {}", options_file.display(), e))))
+}
+
+/// options of `--local` or `--global`, otherwise the merged options for the active version
+fn list_options(command_matches: &clap::ArgMatches) {
+    let options = if command_matches.get_flag("local") || command_matches.get_flag("global") {
+        read_options(&options_file_of(command_matches))
+    } else {
+        let java_version = resolve_version().map(|active_version| active_version.version);
+        let current_dir = std::env::current_dir().unwrap();
+        let options_env = java_options_env(java_version.as_deref(), &jenv_home().join("versions"), &options_dir(), &current_dir);
+        options_env.as_deref().map(split_options).unwrap_or_default()
+    };
+    for option in options {
+        println!("{}", option);
+    }
+}
+
+fn build_scope_args(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("local")
+                .long("local")
+                .help("Options of the project in .jenv-options")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("global")
+        )
+        .arg(
+            Arg::new("global")
+                .long("global")
+                .help("Options of the active version in ~/.jenv/options")
+                .action(ArgAction::SetTrue)
+        )
+}
+
+/// JVM options after `--`, such as `jenv options add --local -- -Xmx2g`, so they are never mixed up with
+/// `--local` and `--global`
+fn build_options_arg() -> Arg {
+    Arg::new("options")
+        .help("JVM options after --, such as -- -Xmx2g --enable-preview")
+        .index(1)
+        .num_args(1..)
+        .last(true)
+        .required(true)
+}
+
+pub fn build_options_command() -> Command {
+    Command::new("options")
+        .about("Manage JVM options of Java versions and projects, passed to java by JDK_JAVA_OPTIONS. Usage: jenv options add --local -- -Xmx2g")
+        .subcommand_required(true)
+        .subcommand(
+            build_scope_args(Command::new("add").about("Add JVM options, global by default"))
+                .arg(build_options_arg())
+        )
+        .subcommand(
+            build_scope_args(Command::new("remove").about("Remove JVM options, global by default"))
+                .arg(build_options_arg())
+        )
+        .subcommand(
+            build_scope_args(Command::new("list").about("List JVM options, merged options of the active version by default"))
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(options: &[&str]) -> Vec<String> {
+        options.iter().map(|option| option.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_scope() {
+        let parse = |args: &[&str]| build_options_command().try_get_matches_from(["options"].iter().chain(args));
+        assert!(parse(&["add", "-Xmx2g", "--local"]).is_err());
+        for args in [["add", "--local", "--", "-Xmx2g"], ["add", "--", "-Xmx2g", "--local"]] {
+            let matches = parse(&args).unwrap();
+            let (_, add_matches) = matches.subcommand().unwrap();
+            let options = get_options_arg(add_matches);
+            if add_matches.get_flag("local") {
+                assert_eq!(options, ["-Xmx2g"]);
+            } else {
+                // scope flags after `--` are rejected instead of being saved as JVM options
+                assert!(check_options(&options).is_err());
+            }
+        }
+        assert!(check_options(&["-Xmx2g".to_owned(), "--enable-preview".to_owned()]).is_ok());
+        let matches = parse(&["add", "--", "--add-opens", "java.base/java.lang=ALL-UNNAMED", "-Xmx2g"]).unwrap();
+        let (_, add_matches) = matches.subcommand().unwrap();
+        assert_eq!(get_options_arg(add_matches), ["--add-opens=java.base/java.lang=ALL-UNNAMED", "-Xmx2g"]);
+    }
+
+    #[test]
+    fn test_merge_options() {
+        let global_options = strings(&["-Xmx2g", "-XX:+UseZGC"]);
+        let local_options = strings(&["--enable-preview", "-Xmx2g", "-Dapp.name=demo app"]);
+        let merged = merge_options(&global_options, &local_options, Some("-Xmx4g '-Dapp.name=demo app'"));
+        assert_eq!(merged, ["-XX:+UseZGC", "--enable-preview", "-Xmx2g", "-Xmx4g", "-Dapp.name=demo app"]);
+        assert_eq!(format_options(&merged), "-XX:+UseZGC --enable-preview -Xmx2g -Xmx4g \"-Dapp.name=demo app\"");
+        assert!(merge_options(&[], &[], None).is_empty());
+    }
+
+    #[test]
+    fn test_options_files() {
+        let dir = std::env::temp_dir().join("wukong-jenv-options");
+        let _ = std::fs::remove_dir_all(&dir);
+        let versions_dir = dir.join("versions");
+        std::fs::create_dir_all(versions_dir.join("temurin64-21.0.2")).unwrap();
+        let options_dir = dir.join("options");
+        let global_file = global_options_file(&options_dir, Some("temurin64-21.0.2"), &versions_dir);
+        assert_eq!(global_file, options_dir.join("temurin64-21.0.2"));
+        assert_eq!(global_options_file(&options_dir, None, &versions_dir), options_dir.join("system"));
+        write_options(&global_file, &strings(&["-Xmx2g"])).unwrap();
+        let project_dir = dir.join("project");
+        std::fs::create_dir_all(project_dir.join("src")).unwrap();
+        std::fs::write(project_dir.join(LOCAL_OPTIONS_FILE), "# project options\n--enable-preview\n\n").unwrap();
+        assert_eq!(find_local_options_file(&project_dir.join("src")), Some(project_dir.join(LOCAL_OPTIONS_FILE)));
+        assert_eq!(read_options(&project_dir.join(LOCAL_OPTIONS_FILE)), ["--enable-preview"]);
+        // an option and its value on one line are separate arguments of java
+        std::fs::write(project_dir.join(LOCAL_OPTIONS_FILE),
+                       "--add-opens java.base/java.lang=ALL-UNNAMED --enable-preview\n--add-opens java.base/java.io=ALL-UNNAMED\n\"-Dapp.name=demo app\"\n").unwrap();
+        let local_options = read_options(&project_dir.join(LOCAL_OPTIONS_FILE));
+        assert_eq!(local_options, ["--add-opens=java.base/java.lang=ALL-UNNAMED", "--enable-preview",
+            "--add-opens=java.base/java.io=ALL-UNNAMED", "-Dapp.name=demo app"]);
+        write_options(&global_file, &local_options).unwrap();
+        assert_eq!(read_options(&global_file), local_options);
+        write_options(&global_file, &[]).unwrap();
+        assert!(!global_file.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,228 @@
+//! Built-in jenv plugins, enabled by `jenv enable-plugin <name>` with a marker file in `~/.jenv/plugins`.
+//!
+//! - export: shell hook to export `JAVA_HOME` and `JDK_HOME` of the active version before each prompt
+//! - maven, gradle and sbt: shims of `mvn`, `gradle` and `sbt` run the tool on PATH with `JAVA_HOME` of the
+//!   active version, and `MAVEN_OPTS`, `GRADLE_OPTS` and `SBT_OPTS` are kept as they are
+
+use crate::jenv_cli::jenv_home;
+use crate::jenv_cli::shims::rehash_command;
+use clap::{Arg, Command};
+use std::io;
+use std::path::{Path, PathBuf};
+use wukong::error::report_error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Plugin {
+    Export,
+    Maven,
+    Gradle,
+    Sbt,
+}
+
+pub const PLUGINS: [Plugin; 4] = [Plugin::Export, Plugin::Maven, Plugin::Gradle, Plugin::Sbt];
+
+impl Plugin {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Plugin::Export => "export",
+            Plugin::Maven => "maven",
+            Plugin::Gradle => "gradle",
+            Plugin::Sbt => "sbt",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Plugin::Export => "export JAVA_HOME and JDK_HOME of the active version in shell",
+            Plugin::Maven => "run mvn and mvnd with JAVA_HOME of the active version",
+            Plugin::Gradle => "run gradle with JAVA_HOME of the active version",
+            Plugin::Sbt => "run sbt with JAVA_HOME of the active version",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Plugin> {
+        PLUGINS.into_iter().find(|plugin| plugin.name() == name)
+    }
+
+    /// commands wrapped by shims
+    pub fn commands(&self) -> &'static [&'static str] {
+        match self {
+            Plugin::Export => &[],
+            Plugin::Maven => &["mvn", "mvnd"],
+            Plugin::Gradle => &["gradle"],
+            Plugin::Sbt => &["sbt"],
+        }
+    }
+}
+
+pub fn plugins_dir() -> PathBuf {
+    jenv_home().join("plugins")
+}
+
+pub fn enabled_plugins(plugins_dir: &Path) -> Vec<Plugin> {
+    PLUGINS.into_iter()
+        .filter(|plugin| plugins_dir.join(plugin.name()).exists())
+        .collect()
+}
+
+/// returns false if the plugin was enabled already
+pub fn enable_plugin(plugins_dir: &Path, plugin: Plugin) -> io::Result<bool> {
+    let marker_file = plugins_dir.join(plugin.name());
+    if marker_file.exists() {
+        return Ok(false);
+    }
+    std::fs::create_dir_all(plugins_dir)?;
+    std::fs::write(marker_file, "")?;
+    Ok(true)
+}
+
+/// returns false if the plugin was not enabled
+pub fn disable_plugin(plugins_dir: &Path, plugin: Plugin) -> io::Result<bool> {
+    let marker_file = plugins_dir.join(plugin.name());
+    if !marker_file.exists() {
+        return Ok(false);
+    }
+    std::fs::remove_file(marker_file)?;
+    Ok(true)
+}
+
+/// enabled plugin wrapping the command, such as `maven` for `mvn`
+pub fn find_wrapping_plugin(plugins: &[Plugin], command: &str) -> Option<Plugin> {
+    plugins.iter().copied().find(|plugin| plugin.commands().contains(&command))
+}
+
+/// commands of enabled plugins, shims are created for them even if they are not in Java versions
+pub fn plugin_commands(plugins: &[Plugin]) -> Vec<&'static str> {
+    plugins.iter().flat_map(|plugin| plugin.commands()).copied().collect()
+}
+
+/// hook of the export plugin, `JAVA_HOME` and `JDK_HOME` are exported before each prompt
+pub fn export_hook(shell_name: &str) -> &'static str {
+    match shell_name {
+        "fish" => r#"function _jenv_export_hook --on-event fish_prompt
+    set -l java_home (command jenv javahome 2>/dev/null)
+    if test -n "$java_home"
+        set -gx JAVA_HOME $java_home
+        set -gx JDK_HOME $java_home
+    end
+end"#,
+        "zsh" => r#"_jenv_export_hook() {
+  local java_home
+  java_home="$(command jenv javahome 2>/dev/null)"
+  if [ -n "$java_home" ]; then
+    export JAVA_HOME="$java_home"
+    export JDK_HOME="$java_home"
+  fi
+}
+typeset -ag precmd_functions
+if (( ! ${precmd_functions[(I)_jenv_export_hook]} )); then
+  precmd_functions=(_jenv_export_hook $precmd_functions)
+fi"#,
+        _ => r#"_jenv_export_hook() {
+  local java_home
+  java_home="$(command jenv javahome 2>/dev/null)"
+  if [ -n "$java_home" ]; then
+    export JAVA_HOME="$java_home"
+    export JDK_HOME="$java_home"
+  fi
+}
+if [[ ";${PROMPT_COMMAND:-};" != *";_jenv_export_hook;"* ]]; then
+  PROMPT_COMMAND="_jenv_export_hook${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+fi"#,
+    }
+}
+
+pub fn plugins_command() {
+    let enabled = enabled_plugins(&plugins_dir());
+    for plugin in PLUGINS {
+        let marker = if enabled.contains(&plugin) { "*" } else { " " };
+        println!("{} {:<8} {}", marker, plugin.name(), plugin.description());
+    }
+}
+
+pub fn enable_plugin_command(command_matches: &clap::ArgMatches) {
+    let plugin = get_plugin(command_matches);
+    match enable_plugin(&plugins_dir(), plugin) {
+        Ok(true) => {
+            println!("{} plugin activated", plugin.name());
+            after_plugin_changed(plugin);
+        }
+        Ok(false) => println!("{} plugin already activated", plugin.name()),
+        Err(e) => eprintln!("jenv: failed to activate {} plugin: {}", plugin.name(), e),
+    }
+}
+
+pub fn disable_plugin_command(command_matches: &clap::ArgMatches) {
+    let plugin = get_plugin(command_matches);
+    match disable_plugin(&plugins_dir(), plugin) {
+        Ok(true) => {
+            println!("{} plugin deactivated", plugin.name());
+            after_plugin_changed(plugin);
+        }
+        Ok(false) => println!("{} plugin not activated", plugin.name()),
+        Err(e) => eprintln!("jenv: failed to deactivate {} plugin: {}", plugin.name(), e),
+    }
+}
+
+fn get_plugin(command_matches: &clap::ArgMatches) -> Plugin {
+    let name = command_matches.get_one::<String>("plugin").unwrap();
+    Plugin::from_name(name).unwrap()
+}
+
+/// shims for tool plugins, and shell hooks for the export plugin
+fn after_plugin_changed(plugin: Plugin) {
+    if plugin == Plugin::Export {
+        println!("Please restart your shell or run `eval \"$(jenv init -)\"` to apply the change");
+    } else if let Err(e) = rehash_command() {
+        report_error("jenv", &e);
+    }
+}
+
+fn build_plugin_arg() -> Arg {
+    Arg::new("plugin")
+        .help("The plugin name")
+        .index(1)
+        .required(true)
+        .value_parser(PLUGINS.map(|plugin| plugin.name()))
+}
+
+pub fn build_enable_plugin_command() -> Command {
+    Command::new("enable-plugin")
+        .about("Activate a jenv plugin: export, maven, gradle or sbt")
+        .arg(build_plugin_arg())
+}
+
+pub fn build_disable_plugin_command() -> Command {
+    Command::new("disable-plugin")
+        .about("Deactivate a jenv plugin")
+        .arg(build_plugin_arg())
+}
+
+pub fn build_plugins_command() -> Command {
+    Command::new("plugins")
+        .about("List all plugins, activated plugins are marked with *")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plugins_state() {
+        let plugins_dir = std::env::temp_dir().join("wukong-jenv-plugins");
+        let _ = std::fs::remove_dir_all(&plugins_dir);
+        assert!(enabled_plugins(&plugins_dir).is_empty());
+        assert!(enable_plugin(&plugins_dir, Plugin::Maven).unwrap());
+        assert!(!enable_plugin(&plugins_dir, Plugin::Maven).unwrap());
+        assert!(enable_plugin(&plugins_dir, Plugin::Export).unwrap());
+        let enabled = enabled_plugins(&plugins_dir);
+        assert_eq!(enabled, [Plugin::Export, Plugin::Maven]);
+        assert_eq!(find_wrapping_plugin(&enabled, "mvn"), Some(Plugin::Maven));
+        assert_eq!(find_wrapping_plugin(&enabled, "gradle"), None);
+        assert_eq!(plugin_commands(&enabled), ["mvn", "mvnd"]);
+        assert!(disable_plugin(&plugins_dir, Plugin::Maven).unwrap());
+        assert!(!disable_plugin(&plugins_dir, Plugin::Gradle).unwrap());
+        assert_eq!(enabled_plugins(&plugins_dir), [Plugin::Export]);
+        std::fs::remove_dir_all(&plugins_dir).unwrap();
+    }
+}
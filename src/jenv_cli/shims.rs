@@ -0,0 +1,340 @@
+//! jenv shims: `~/.jenv/shims` has an entry for every executable in `versions/*/bin`, and each entry is
+//! the jenv binary itself. jenv invoked under the name of an executable, such as `java`, runs the executable
+//! of the active Java version.
+
+use crate::jenv_cli::aliases::match_version;
+use crate::jenv_cli::options::{java_options_env, options_dir};
+use crate::jenv_cli::plugins::{enabled_plugins, find_wrapping_plugin, plugin_commands, plugins_dir};
+use crate::jenv_cli::{jenv_home, resolve_version};
+use std::collections::BTreeSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use wukong::error::{Error, Result};
+
+/// sequence of temporary shim names in the process
+static TEMP_SHIM_SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
+pub fn shims_dir() -> PathBuf {
+    jenv_home().join("shims")
+}
+
+/// command name if jenv is invoked as a shim, such as `java` or `javac.exe`: `arg0` is in the shims directory,
+/// or `arg0` is the name of an existing shim. A renamed jenv binary, such as `/usr/local/bin/j`, is not a shim.
+pub fn shim_name(arg0: &str, shims_dir: &Path) -> Option<String> {
+    let path = Path::new(arg0);
+    let name = path.file_stem()?.to_string_lossy().to_string();
+    if name.is_empty() || name.starts_with("jenv") {
+        return None;
+    }
+    let in_shims_dir = path.parent().is_some_and(|parent| {
+        parent == shims_dir || parent.canonicalize().is_ok_and(|parent| shims_dir.canonicalize().is_ok_and(|shims_dir| parent == shims_dir))
+    });
+    let shim_path = shim_path(shims_dir, &name);
+    if in_shims_dir || shim_path.is_symlink() || shim_path.exists() {
+        Some(name)
+    } else {
+        None
+    }
+}
+
+/// executable names in `bin` of all versions
+pub fn list_executables(versions_dir: &Path) -> BTreeSet<String> {
+    let mut executables = BTreeSet::new();
+    let Ok(entries) = versions_dir.read_dir() else {
+        return executables;
+    };
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let Ok(bin_entries) = entry.path().join("bin").read_dir() else {
+            continue;
+        };
+        for bin_entry in bin_entries.flatten() {
+            let path = bin_entry.path();
+            if is_executable(&path) && let Some(name) = path.file_stem() {
+                executables.insert(name.to_string_lossy().to_string());
+            }
+        }
+    }
+    executables
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata().is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file() && path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("exe"))
+}
+
+/// create shims for executables of all versions and commands of plugins, and remove shims of executables
+/// not available anymore. Returns the number of shims.
+///
+/// Shells run `jenv rehash` at startup concurrently, so a shim is created with a temporary name and renamed into place,
+/// and existing shims are never missing. Temporary shims start with `.` and are left to their rehash.
+pub fn rehash(versions_dir: &Path, shims_dir: &Path, jenv_binary: &Path, plugin_commands: &[&str]) -> io::Result<usize> {
+    let mut executables = list_executables(versions_dir);
+    executables.extend(plugin_commands.iter().map(|command| command.to_string()));
+    std::fs::create_dir_all(shims_dir)?;
+    for entry in shims_dir.read_dir()?.flatten() {
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let stale = path.file_stem().is_none_or(|name| !executables.contains(name.to_string_lossy().as_ref()));
+        if stale {
+            match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+    }
+    for executable in &executables {
+        let shim_path = shim_path(shims_dir, executable);
+        if is_shim_of(&shim_path, jenv_binary) {
+            continue;
+        }
+        let sequence = TEMP_SHIM_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        let temp_path = shims_dir.join(format!(".{}.{}.{}", executable, std::process::id(), sequence));
+        if temp_path.is_symlink() || temp_path.exists() {
+            std::fs::remove_file(&temp_path)?;
+        }
+        create_shim(jenv_binary, &temp_path)?;
+        if let Err(e) = std::fs::rename(&temp_path, &shim_path) {
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(e);
+        }
+    }
+    Ok(executables.len())
+}
+
+fn shim_path(shims_dir: &Path, executable: &str) -> PathBuf {
+    if cfg!(windows) {
+        shims_dir.join(format!("{}.exe", executable))
+    } else {
+        shims_dir.join(executable)
+    }
+}
+
+#[cfg(unix)]
+fn create_shim(jenv_binary: &Path, shim_path: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(jenv_binary, shim_path)
+}
+
+#[cfg(unix)]
+fn is_shim_of(shim_path: &Path, jenv_binary: &Path) -> bool {
+    shim_path.read_link().is_ok_and(|link_target| link_target == jenv_binary)
+}
+
+/// symbolic links need privileges on Windows, and the binary is copied
+#[cfg(not(unix))]
+fn create_shim(jenv_binary: &Path, shim_path: &Path) -> io::Result<()> {
+    std::fs::copy(jenv_binary, shim_path).map(|_| ())
+}
+
+/// copied shims are refreshed every time, because the jenv binary may be updated
+#[cfg(not(unix))]
+fn is_shim_of(_shim_path: &Path, _jenv_binary: &Path) -> bool {
+    false
+}
+
+pub fn rehash_command() -> Result<()> {
+    let jenv_binary = std::env::current_exe()?;
+    let plugin_commands = plugin_commands(&enabled_plugins(&plugins_dir()));
+    let count = rehash(&jenv_home().join("versions"), &shims_dir(), &jenv_binary, &plugin_commands)
+        .map_err(|e| Error::Io(io::Error::new(e.kind(), format!("failed to rehash shims: {}", e))))?;
+    println!("jenv: {} shims in {}", count, shims_dir().display());
+    Ok(())
+}
+
+/// executable of the Java version, matched by name, alias or major version, and executable on PATH without shims
+/// for `system` or no version
+pub fn find_executable(command: &str, java_version: Option<&str>, versions_dir: &Path, shims_dir: &Path) -> Option<PathBuf> {
+    match java_version {
+        Some(java_version) if java_version != "system" => {
+            let bin_dir = versions_dir.join(match_version(versions_dir, java_version)?).join("bin");
+            [bin_dir.join(command), bin_dir.join(format!("{}.exe", command))].into_iter()
+                .find(|path| path.is_file())
+        }
+        _ => {
+            let paths = std::env::var_os("PATH")?;
+            let paths: Vec<PathBuf> = std::env::split_paths(&paths)
+                .filter(|path| path != shims_dir)
+                .collect();
+            let paths = std::env::join_paths(paths).ok()?;
+            which::which_in(command, Some(paths), std::env::current_dir().ok()?).ok()
+        }
+    }
+}
+
+/// Java home of the version, and Java home of `java` on PATH without shims for `system` or no version
+pub fn find_java_home(java_version: Option<&str>, versions_dir: &Path, shims_dir: &Path) -> Option<PathBuf> {
+    match java_version {
+        Some(java_version) if java_version != "system" => Some(versions_dir.join(match_version(versions_dir, java_version)?)),
+        _ => {
+            let java_exec = find_executable("java", None, versions_dir, shims_dir)?.canonicalize().ok()?;
+            java_exec.parent()?.parent().map(|java_home| java_home.to_path_buf())
+        }
+    }
+}
+
+pub fn javahome_command() {
+    let java_version = resolve_version().map(|active_version| active_version.version);
+    if let Some(java_home) = find_java_home(java_version.as_deref(), &jenv_home().join("versions"), &shims_dir()) {
+        println!("{}", java_home.display());
+    }
+}
+
+/// run the command of the active Java version with arguments, and exit with its exit code.
+/// Commands of plugins, such as `mvn`, run from PATH with `JAVA_HOME` of the active version,
+/// and JVM options of the version and the project are passed by `JDK_JAVA_OPTIONS`.
+pub fn exec_command(command: &str, args: &[String]) -> ! {
+    let java_version = resolve_version().map(|active_version| active_version.version);
+    let versions_dir = jenv_home().join("versions");
+    let plugin = find_wrapping_plugin(&enabled_plugins(&plugins_dir()), command);
+    let version_of_command = if plugin.is_some() { None } else { java_version.as_deref() };
+    let Some(executable) = find_executable(command, version_of_command, &versions_dir, &shims_dir()) else {
+        eprintln!("jenv: {}: command not found in version {}", command, java_version.as_deref().unwrap_or("system"));
+        std::process::exit(127);
+    };
+    let mut process = std::process::Command::new(&executable);
+    process.args(args);
+    if plugin.is_some() && let Some(java_home) = find_java_home(java_version.as_deref(), &versions_dir, &shims_dir()) {
+        process.env("JAVA_HOME", &java_home).env("JDK_HOME", &java_home);
+    }
+    if let Ok(current_dir) = std::env::current_dir()
+        && let Some(java_options) = java_options_env(java_version.as_deref(), &versions_dir, &options_dir(), &current_dir) {
+        process.env("JDK_JAVA_OPTIONS", java_options);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        let e = process.exec();
+        eprintln!("jenv: failed to execute {}: {}", executable.display(), e);
+        std::process::exit(126);
+    }
+    #[cfg(not(unix))]
+    {
+        match process.status() {
+            Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+            Err(e) => {
+                eprintln!("jenv: failed to execute {}: {}", executable.display(), e);
+                std::process::exit(126);
+            }
+        }
+    }
+}
+
+pub fn exec_command_line(command_matches: &clap::ArgMatches) {
+    let command = command_matches.get_one::<String>("command").unwrap();
+    let args: Vec<String> = command_matches.get_many::<String>("args")
+        .map(|args| args.cloned().collect())
+        .unwrap_or_default();
+    exec_command(command, &args);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_executable(path: &Path) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_shim_name() {
+        let shims_dir = std::env::temp_dir().join("wukong-jenv-shim-name").join("shims");
+        let _ = std::fs::remove_dir_all(&shims_dir);
+        std::fs::create_dir_all(&shims_dir).unwrap();
+        std::fs::write(shim_path(&shims_dir, "javac"), "").unwrap();
+        assert_eq!(shim_name(shims_dir.join("java").to_str().unwrap(), &shims_dir), Some("java".to_owned()));
+        assert_eq!(shim_name("javac", &shims_dir), Some("javac".to_owned()));
+        // renamed or aliased jenv binary
+        assert_eq!(shim_name("/usr/local/bin/j", &shims_dir), None);
+        assert_eq!(shim_name("java", &shims_dir), None);
+        assert_eq!(shim_name("/usr/local/bin/jenv", &shims_dir), None);
+        assert_eq!(shim_name("jenv.exe", &shims_dir), None);
+        std::fs::remove_dir_all(shims_dir.parent().unwrap()).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rehash() {
+        let dir = std::env::temp_dir().join("wukong-jenv-shims");
+        let _ = std::fs::remove_dir_all(&dir);
+        let versions_dir = dir.join("versions");
+        let shims_dir = dir.join("shims");
+        write_executable(&versions_dir.join("17").join("bin").join("java"));
+        write_executable(&versions_dir.join("17").join("bin").join("javac"));
+        write_executable(&versions_dir.join("21").join("bin").join("java"));
+        write_executable(&versions_dir.join("21").join("bin").join("jshell"));
+        std::fs::write(versions_dir.join("21").join("bin").join("README"), "").unwrap();
+        write_executable(&shims_dir.join("jconsole"));
+        let jenv_binary = dir.join("jenv");
+        write_executable(&jenv_binary);
+        assert_eq!(rehash(&versions_dir, &shims_dir, &jenv_binary, &["mvn"]).unwrap(), 4);
+        let mut shims: Vec<String> = shims_dir.read_dir().unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        shims.sort();
+        assert_eq!(shims, ["java", "javac", "jshell", "mvn"]);
+        // rehash again keeps shims in place
+        assert_eq!(rehash(&versions_dir, &shims_dir, &jenv_binary, &[]).unwrap(), 3);
+        let mut shims: Vec<String> = shims_dir.read_dir().unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        shims.sort();
+        assert_eq!(shims, ["java", "javac", "jshell"]);
+        assert_eq!(find_java_home(Some("21"), &versions_dir, &shims_dir), Some(versions_dir.join("21")));
+        assert_eq!(shims_dir.join("java").read_link().unwrap(), jenv_binary);
+        assert_eq!(find_executable("jshell", Some("21"), &versions_dir, &shims_dir), Some(versions_dir.join("21").join("bin").join("jshell")));
+        assert_eq!(find_executable("jshell", Some("17"), &versions_dir, &shims_dir), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_concurrent_rehash() {
+        let dir = std::env::temp_dir().join("wukong-jenv-concurrent-rehash");
+        let _ = std::fs::remove_dir_all(&dir);
+        let versions_dir = dir.join("versions");
+        let shims_dir = dir.join("shims");
+        write_executable(&versions_dir.join("21").join("bin").join("java"));
+        let jenv_binaries = [dir.join("jenv1"), dir.join("jenv2")];
+        for jenv_binary in &jenv_binaries {
+            write_executable(jenv_binary);
+        }
+        rehash(&versions_dir, &shims_dir, &jenv_binaries[0], &[]).unwrap();
+        let java_shim = shims_dir.join("java");
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..4).map(|worker| {
+                let (versions_dir, shims_dir, jenv_binaries) = (&versions_dir, &shims_dir, &jenv_binaries);
+                scope.spawn(move || {
+                    for round in 0..50 {
+                        // another binary every round, so shims are replaced every time
+                        rehash(versions_dir, shims_dir, &jenv_binaries[(worker + round) % 2], &[]).unwrap();
+                    }
+                })
+            }).collect();
+            while !workers.iter().all(|worker| worker.is_finished()) {
+                assert!(java_shim.is_symlink(), "java shim is missing during rehash");
+            }
+        });
+        let leftovers: Vec<String> = shims_dir.read_dir().unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(leftovers, ["java"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,2 +1,7 @@
 pub mod common;
+pub mod error;
 pub mod foojay;
+pub mod http;
+pub mod platform;
+
+pub use error::{Error, Result};
@@ -3,6 +3,9 @@ use pad::PadStr;
 use serde::{Deserialize, Serialize};
 use std::io::Read;
 use std::path::Path;
+use wukong::error::exit_with_error;
+use wukong::http::{http_client, http_get, rewrite_url};
+use wukong::Error;
 
 pub mod clap_app;
 
@@ -115,14 +118,7 @@ pub fn search(command_matches: &clap::ArgMatches) {
         query, limit
     );
     println!("Searching for containing {}...", query);
-    let client = reqwest::blocking::Client::new();
-    let result = client
-        .get(&url)
-        .header(reqwest::header::USER_AGENT, "curl/8.7.1")
-        .send()
-        .unwrap()
-        .json::<McsResult>()
-        .unwrap();
+    let result = search_maven_central(&url).unwrap_or_else(|e| exit_with_error("mcs", &e));
     let limit1 = *limit;
     println!(
         "Found {} results (showing {})",
@@ -176,14 +172,7 @@ pub fn class_search(command_matches: &clap::ArgMatches) {
         )
     };
     println!("Searching for artifacts containing {}...", query);
-    let client = reqwest::blocking::Client::new();
-    let result = client
-        .get(&url)
-        .header(reqwest::header::USER_AGENT, "curl/8.7.1")
-        .send()
-        .unwrap()
-        .json::<McsResult>()
-        .unwrap();
+    let result = search_maven_central(&url).unwrap_or_else(|e| exit_with_error("mcs", &e));
     let limit1 = *limit;
     println!(
         "Found {} results (showing {})",
@@ -219,14 +208,19 @@ pub struct Project {
     pub url: Option<String>,
 }
 
+/// query search.maven.org
+fn search_maven_central(url: &str) -> wukong::Result<McsResult> {
+    let response = http_client()?
+        .get(rewrite_url(url))
+        .header(reqwest::header::USER_AGENT, "curl/8.7.1")
+        .send()?;
+    Ok(response.error_for_status()?.json::<McsResult>()?)
+}
+
 impl Project {
-    pub fn load(url: &str) -> Self {
-        let response = reqwest::blocking::get(url).unwrap();
-        if !response.status().is_success() {
-            panic!("Failed to fetch {}", url);
-        }
-        let xml_code = response.text().unwrap();
-        quick_xml::de::from_str(&xml_code).unwrap()
+    pub fn load(url: &str) -> wukong::Result<Self> {
+        let xml_code = http_get(url)?.error_for_status()?.text()?;
+        quick_xml::de::from_str(&xml_code).map_err(|e| Error::Parse(format!("Invalid POM {}: {}", url, e)))
     }
 
     pub fn parse(xml_code: &str) -> Self {
@@ -261,7 +255,7 @@ pub fn info(command_matches: &clap::ArgMatches) {
         parts[1],
         parts[2]
     );
-    let project = Project::load(&url);
+    let project = Project::load(&url).unwrap_or_else(|e| exit_with_error("mcs", &e));
     if let Some(name) = &project.name {
         println!("{}: {}", "Name".bold(), name);
     }
@@ -384,7 +378,7 @@ mod tests {
     #[test]
     fn test_parse_pom() {
         let url = " https://repo1.maven.org/maven2/org/apache/commons/commons-lang3/3.17.0/commons-lang3-3.17.0.pom";
-        let pom = Project::load(url);
+        let pom = Project::load(url).unwrap();
         println!("{:?}", pom);
     }
 }
@@ -1,87 +1,42 @@
 use std::path::{Path, PathBuf};
 use colored::Colorize;
-use wukong::common::{is_java_home, jbang_home, sdkman_home};
+use wukong::common::discover_jdks;
+use wukong::error::{exit_with_error, report_error};
 use crate::mt_cli::models::Toolchains;
 use crate::sdkman_cli;
-use crate::sdkman_cli::list::list_candidate;
+use crate::sdkman_cli::list::{list_candidate_with_options, ListOptions};
 
 pub mod models;
 pub mod clap_app;
 
+fn jbang_home() -> PathBuf {
+    wukong::common::jbang_home().unwrap_or_else(|e| exit_with_error("mt", &e))
+}
+
+fn sdkman_home() -> PathBuf {
+    wukong::common::sdkman_home().unwrap_or_else(|e| exit_with_error("mt", &e))
+}
+
 pub fn m2_dir() -> PathBuf {
-    dirs::home_dir().unwrap().join(".m2")
+    wukong::common::home_dir().unwrap_or_else(|e| exit_with_error("mt", &e)).join(".m2")
 }
 
 pub fn jdks_command() {
-    // list all JDKs on from JBang
-    let jbang_home = jbang_home();
-    if jbang_home.exists() {
-        let jdks = jbang_home.join("cache").join("jdks");
-        if jdks.exists() {
-            let lines = list_jdks(&jdks);
-            print_jdks(&lines, "JBang");
-        }
-    }
-    // list all JDKs from SDKMAN
-    let sdkman_home = sdkman_home();
-    if sdkman_home.exists() {
-        let jdks = sdkman_home.join("candidates").join("java");
-        if jdks.exists() {
-            let lines = list_jdks(&jdks);
-            print_jdks(&lines, "SDKMAN");
+    // list all JDKs from JBang, SDKMAN, Gradle and system locations, grouped by source
+    let jdks = discover_jdks().unwrap_or_else(|e| exit_with_error("mt", &e));
+    let mut sources: Vec<&str> = vec![];
+    for jdk in &jdks {
+        if !sources.contains(&jdk.source) {
+            sources.push(jdk.source);
         }
     }
-    // list all JDKs from Gradle
-    let home_dir = dirs::home_dir().unwrap();
-    let gradle_jdks = home_dir.join(".gradle").join("jdks");
-    if gradle_jdks.exists() {
-        let lines = list_jdks(&gradle_jdks);
-        print_jdks(&lines, "Gradle");
-    }
-    if cfg!(target_os = "macos") {
-        // list all JDKs from /Library/Java/JavaVirtualMachines
-        let jdks = PathBuf::from("/Library/Java/JavaVirtualMachines");
-        if jdks.exists() {
-            let lines = list_jdks(&gradle_jdks);
-            print_jdks(&lines, "System");
-        }
-        let jdks = home_dir.join("Library").join("Java").join("JavaVirtualMachines");
-        if jdks.exists() {
-            let lines = list_jdks(&jdks);
-            print_jdks(&lines, "User");
-        }
-        // homebrew
-        let cellar_dirs = ["/opt/homebrew/Cellar", "/usr/local/Cellar"];
-        let mut lines: Vec<String> = Vec::new();
-        for cellar_dir in cellar_dirs.iter() {
-            let cellar_path = PathBuf::from(*cellar_dir);
-            if cellar_path.exists() {
-                cellar_path.read_dir().unwrap().for_each(|entry| {
-                    if let Ok(entry) = entry {
-                        let child = entry.path();
-                        let file_name = child.file_name().unwrap().to_str().unwrap();
-                        if file_name.starts_with("openjdk") {
-                            lines.extend(list_jdks(&child))
-                        }
-                    }
-                });
-            }
-        }
-        print_jdks(&lines, "Homebrew");
-    } else if cfg!(target_os = "windows") {
-        // list all JDKs from C:\Program Files\Java
-        let jdks = PathBuf::from("C:\\Program Files\\Java");
-        if jdks.exists() {
-            let lines = list_jdks(&jdks);
-            print_jdks(&lines, "System");
-        }
-    } else if cfg!(target_os = "linux") {
-        // list all JDKs from /usr/lib/jvm
-        let jdks = PathBuf::from("/usr/lib/jvm");
-        if jdks.exists() {
-            let lines = list_jdks(&jdks);
-            print_jdks(&lines, "System");
-        }
+    for source in sources {
+        let mut lines: Vec<String> = jdks.iter()
+            .filter(|jdk| jdk.source == source)
+            .map(|jdk| format!("{}:\n {}", jdk.name, jdk.path.display()))
+            .collect();
+        lines.sort_by(|a, b| compare_java_version(a, b));
+        print_jdks(&lines, source);
     }
 }
 
@@ -94,23 +49,6 @@ fn print_jdks(lines: &[String], title: &str) {
     }
 }
 
-fn list_jdks(base_path: &PathBuf) -> Vec<String> {
-    let mut lines = Vec::new();
-    base_path.read_dir().unwrap().for_each(|entry| {
-        if let Ok(entry) = entry {
-            let child = entry.path();
-            if child.is_dir() && is_java_home(&child) {
-                let java_version = entry.file_name();
-                if java_version != "current" {
-                    lines.push(format!("{}:\n {}", java_version.to_str().unwrap(), entry.path().display()));
-                }
-            }
-        }
-    });
-    lines.sort_by(|a, b| compare_java_version(a, b));
-    lines
-}
-
 fn compare_java_version(version1: &str, version2: &str) -> core::cmp::Ordering {
     let major_version1 = extract_major_version(version1);
     let major_version2 = extract_major_version(version2);
@@ -211,10 +149,14 @@ pub fn add_command(command_matches: &clap::ArgMatches) {
 pub fn install_jdk(version: &str) -> PathBuf {
     if version.parse::<u32>().is_ok() { // jbang
         let java_home = jbang_home().join("cache").join("jdks").join(version);
-        wukong::foojay::install_jdk(version, &java_home);
+        if let Err(e) = wukong::foojay::install_jdk(version, &java_home) {
+            report_error("mt", &e);
+        }
         java_home
     } else { // SDKMAN
-        sdkman_cli::install::install_candidate("java", version);
+        if let Err(e) = sdkman_cli::install::install_candidate("java", version) {
+            report_error("mt", &e);
+        }
         sdkman_home().join("candidates").join("java").join(version)
     }
 }
@@ -229,7 +171,7 @@ pub fn remove_command(command_matches: &clap::ArgMatches) {
 }
 
 pub fn vendors_command() {
-    list_candidate("java");
+    list_candidate_with_options("java", &ListOptions::default()).unwrap_or_else(|e| exit_with_error("mt", &e));
 }
 
 #[cfg(test)]
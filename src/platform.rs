@@ -0,0 +1,135 @@
+//! Platform detection: operating system, CPU architecture and C library.
+//!
+//! Used to build query parameters for foojay and platform ids for SDKMAN.
+
+use std::fmt;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlatformError {
+    UnsupportedOs(String),
+    UnsupportedArch(String),
+}
+
+impl fmt::Display for PlatformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlatformError::UnsupportedOs(os) => write!(f, "Unsupported operating system: {}", os),
+            PlatformError::UnsupportedArch(arch) => write!(f, "Unsupported architecture: {}", arch),
+        }
+    }
+}
+
+impl std::error::Error for PlatformError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Libc {
+    Glibc,
+    Musl,
+}
+
+/// Normalized CPU architecture, independent of the naming used by Rust, foojay or SDKMAN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arch {
+    X86,
+    X64,
+    Arm,
+    Aarch64,
+    Riscv64,
+    Ppc64le,
+    S390x,
+}
+
+impl Arch {
+    /// map `std::env::consts::ARCH` style names to `Arch`
+    pub fn from_rust_arch(arch: &str, little_endian: bool) -> Result<Arch, PlatformError> {
+        match arch {
+            "x86" => Ok(Arch::X86),
+            "x86_64" => Ok(Arch::X64),
+            "arm" => Ok(Arch::Arm),
+            "aarch64" => Ok(Arch::Aarch64),
+            "riscv64" => Ok(Arch::Riscv64),
+            "powerpc64" if little_endian => Ok(Arch::Ppc64le),
+            "s390x" => Ok(Arch::S390x),
+            _ => Err(PlatformError::UnsupportedArch(arch.to_string())),
+        }
+    }
+
+    pub fn current() -> Result<Arch, PlatformError> {
+        Arch::from_rust_arch(std::env::consts::ARCH, cfg!(target_endian = "little"))
+    }
+
+    /// architecture name used by foojay Disco API
+    pub fn foojay_name(&self) -> &'static str {
+        match self {
+            Arch::X86 => "x86",
+            Arch::X64 => "x64",
+            Arch::Arm => "arm",
+            Arch::Aarch64 => "aarch64",
+            Arch::Riscv64 => "riscv64",
+            Arch::Ppc64le => "ppc64le",
+            Arch::S390x => "s390x",
+        }
+    }
+
+    /// architecture suffix used by SDKMAN platform ids, `None` if SDKMAN has no build for it
+    pub fn sdkman_name(&self) -> Option<&'static str> {
+        match self {
+            Arch::X86 => Some("x32"),
+            Arch::X64 => Some("x64"),
+            Arch::Arm => Some("arm32hf"),
+            Arch::Aarch64 => Some("arm64"),
+            _ => None,
+        }
+    }
+}
+
+/// detect C library at runtime, musl for Alpine and other musl based distributions
+pub fn detect_libc() -> Libc {
+    if cfg!(target_env = "musl") || (cfg!(target_os = "linux") && is_musl_root(Path::new("/"))) {
+        Libc::Musl
+    } else {
+        Libc::Glibc
+    }
+}
+
+/// check musl dynamic loader, `/lib/ld-musl-<arch>.so.1`, or Alpine release file under root path
+pub fn is_musl_root(root: &Path) -> bool {
+    if root.join("etc").join("alpine-release").exists() {
+        return true;
+    }
+    if let Ok(entries) = std::fs::read_dir(root.join("lib")) {
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with("ld-musl-") {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arch_mapping() {
+        assert_eq!(Arch::from_rust_arch("x86_64", true), Ok(Arch::X64));
+        assert_eq!(Arch::from_rust_arch("arm", true).unwrap().foojay_name(), "arm");
+        assert_eq!(Arch::from_rust_arch("powerpc64", true), Ok(Arch::Ppc64le));
+        assert!(Arch::from_rust_arch("powerpc64", false).is_err());
+        assert!(Arch::from_rust_arch("mips", true).is_err());
+        assert_eq!(Arch::Riscv64.sdkman_name(), None);
+    }
+
+    #[test]
+    fn test_musl_root() {
+        let root = std::env::temp_dir().join("wukong-musl-root");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("lib")).unwrap();
+        assert!(!is_musl_root(&root));
+        std::fs::write(root.join("lib").join("ld-musl-x86_64.so.1"), "").unwrap();
+        assert!(is_musl_root(&root));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -5,22 +5,30 @@ use crate::sdkman_cli::current::manage_current;
 use crate::sdkman_cli::default::manage_default;
 use crate::sdkman_cli::direnv::manage_direnv;
 use crate::sdkman_cli::env::{manage_env};
+use crate::sdkman_cli::flush::manage_flush;
 use crate::sdkman_cli::home::manage_home;
-use crate::sdkman_cli::init::shell_hook;
+use crate::sdkman_cli::init::manage_init;
 use crate::sdkman_cli::install::manage_install;
 use crate::sdkman_cli::list::manage_list;
+use crate::sdkman_cli::offline::manage_offline;
 use crate::sdkman_cli::read_sdkman_config;
 use crate::sdkman_cli::uninstall::manage_uninstall;
 use crate::sdkman_cli::upgrade::manage_upgrade;
 use crate::sdkman_cli::use_candidate::manage_use;
+use crate::sdkman_cli::broadcast::manage_broadcast;
+use crate::sdkman_cli::selfupdate::manage_selfupdate;
+use crate::sdkman_cli::version::manage_version;
+use crate::sdkman_cli::doctor::manage_doctor;
+use crate::sdkman_cli::config::{get_config_value, is_config_enabled, manage_config, set_debug_mode};
 
 fn main() {
     let app = build_sdkman_app();
     let matches = app.get_matches();
+    wukong::common::set_quiet(matches.get_flag("quiet"));
     if let Some((command, command_matches)) = matches.subcommand() {
         load_config();
         match command {
-            "init" => shell_hook(),
+            "init" => manage_init(command_matches),
             "list" => manage_list(command_matches),
             "install" => manage_install(command_matches),
             "uninstall" => manage_uninstall(command_matches),
@@ -31,6 +39,13 @@ fn main() {
             "env" => manage_env(command_matches),
             "direnv" => manage_direnv(command_matches),
             "upgrade" => manage_upgrade(command_matches),
+            "offline" => manage_offline(command_matches),
+            "flush" => manage_flush(command_matches),
+            "version" => manage_version(command_matches),
+            "broadcast" => manage_broadcast(command_matches),
+            "selfupdate" => manage_selfupdate(command_matches),
+            "config" => manage_config(command_matches),
+            "doctor" => manage_doctor(command_matches),
             &_ => println!("Unknown command"),
         }
     }
@@ -38,20 +53,15 @@ fn main() {
 
 fn load_config() {
     let config = read_sdkman_config();
-    if let Some(insecure_ssl) = config.get("sdkman_insecure_ssl") {
-        if insecure_ssl == "true" {
-            unsafe {
-                std::env::set_var("ONEIO_ACCEPT_INVALID_CERTS", "true");
-            }
-        }
+    if is_config_enabled(&config, "sdkman_insecure_ssl") {
+        wukong::http::set_insecure(true);
     }
-    if let Some(colour_enable) = config.get("sdkman_colour_enable") {
-        if colour_enable == "false" {
-            unsafe {
-                std::env::set_var("CLICOLOR", "0");
-            }
+    if !is_config_enabled(&config, "sdkman_colour_enable") {
+        unsafe {
+            std::env::set_var("CLICOLOR", "0");
         }
     }
+    let timeout = |name: &str| get_config_value(&config, name).parse::<u64>().unwrap_or(0);
+    wukong::http::set_timeouts(timeout("sdkman_curl_connect_timeout"), timeout("sdkman_curl_max_time"));
+    set_debug_mode(is_config_enabled(&config, "sdkman_debug_mode"));
 }
-
-
@@ -0,0 +1,116 @@
+//! SDKMAN broadcast messages, cached in `$SDKMAN_DIR/var/broadcast` with the last seen id in `$SDKMAN_DIR/var/broadcast_id`.
+//!
+//! The message is only downloaded when the latest broadcast id differs from the last seen id,
+//! and the cached message is used in offline mode or if the API is not reachable.
+
+use crate::sdkman_cli::metadata::is_offline;
+use crate::sdkman_cli::{sdkman_home, SDKMAN_CANDIDATES_API};
+use clap::Command;
+use std::path::PathBuf;
+use wukong::error::exit_with_error;
+use wukong::http::http_get;
+
+pub fn manage_broadcast(_broadcast_matches: &clap::ArgMatches) {
+    let cache = BroadcastCache::new(sdkman_home().join("var"));
+    let result = if is_offline() {
+        cache.read_message().ok_or_else(|| wukong::Error::Network("Offline mode, no cached broadcast".to_owned()))
+    } else {
+        cache.latest(
+            || fetch_text(&format!("{}/broadcast/latest/id", SDKMAN_CANDIDATES_API)),
+            || fetch_text(&format!("{}/broadcast/latest", SDKMAN_CANDIDATES_API)),
+        )
+    };
+    match result {
+        Ok(message) => println!("{}", message.trim_end()),
+        Err(e) => exit_with_error("sdk", &e),
+    }
+}
+
+pub struct BroadcastCache {
+    dir: PathBuf,
+}
+
+impl BroadcastCache {
+    pub fn new(dir: PathBuf) -> Self {
+        BroadcastCache { dir }
+    }
+
+    /// latest broadcast message, downloaded only if its id is not the last seen id
+    pub fn latest<I, M>(&self, fetch_id: I, fetch_message: M) -> wukong::Result<String>
+    where
+        I: Fn() -> wukong::Result<String>,
+        M: Fn() -> wukong::Result<String>,
+    {
+        let cached_message = self.read_message();
+        let latest_id = match fetch_id() {
+            Ok(latest_id) => latest_id.trim().to_string(),
+            Err(e) if e.is_retryable() && cached_message.is_some() => {
+                eprintln!("[sdk] {}, using cached broadcast", e);
+                return Ok(cached_message.unwrap());
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(cached_message) = cached_message && self.read_last_seen_id().as_deref() == Some(latest_id.as_str()) {
+            return Ok(cached_message);
+        }
+        let message = fetch_message()?;
+        self.write(&latest_id, &message);
+        Ok(message)
+    }
+
+    pub fn read_message(&self) -> Option<String> {
+        std::fs::read_to_string(self.dir.join("broadcast")).ok()
+    }
+
+    fn read_last_seen_id(&self) -> Option<String> {
+        std::fs::read_to_string(self.dir.join("broadcast_id")).ok()
+            .map(|id| id.trim().to_string())
+    }
+
+    /// cache is best effort, write failure is ignored
+    fn write(&self, id: &str, message: &str) {
+        if std::fs::create_dir_all(&self.dir).is_ok() {
+            let _ = std::fs::write(self.dir.join("broadcast"), message);
+            let _ = std::fs::write(self.dir.join("broadcast_id"), id);
+        }
+    }
+}
+
+fn fetch_text(url: &str) -> wukong::Result<String> {
+    Ok(http_get(url)?.error_for_status()?.text()?)
+}
+
+pub fn build_broadcast_command() -> Command {
+    Command::new("broadcast")
+        .about("display the latest announcements from SDKMAN.")
+        .long_about(r#"The broadcast message is cached in $SDKMAN_DIR/var/broadcast, and downloaded again only when a new broadcast is published. In offline mode, the cached message is displayed."#)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_broadcast_cache() {
+        let dir = std::env::temp_dir().join("wukong-sdkman-broadcast");
+        let _ = std::fs::remove_dir_all(&dir);
+        let cache = BroadcastCache::new(dir.clone());
+        let downloads = Cell::new(0);
+        let fetch_message = || {
+            downloads.set(downloads.get() + 1);
+            Ok(format!("message {}", downloads.get()))
+        };
+        assert_eq!(cache.latest(|| Ok("id-1\n".to_owned()), fetch_message).unwrap(), "message 1");
+        // same id, cached message is used
+        assert_eq!(cache.latest(|| Ok("id-1".to_owned()), fetch_message).unwrap(), "message 1");
+        assert_eq!(downloads.get(), 1);
+        // new broadcast
+        assert_eq!(cache.latest(|| Ok("id-2".to_owned()), fetch_message).unwrap(), "message 2");
+        // API not reachable
+        let unreachable = || Err(wukong::Error::Network("connection refused".to_owned()));
+        assert_eq!(cache.latest(unreachable, fetch_message).unwrap(), "message 2");
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(cache.latest(unreachable, fetch_message).is_err());
+    }
+}
@@ -1,8 +1,16 @@
-use clap::{Arg, Command};
+use clap::{Arg, ArgAction, Command};
 use crate::sdkman_cli::direnv::build_direnv_command;
 use crate::sdkman_cli::env::build_env_command;
+use crate::sdkman_cli::init::build_init_command;
+use crate::sdkman_cli::flush::build_flush_command;
+use crate::sdkman_cli::broadcast::build_broadcast_command;
+use crate::sdkman_cli::selfupdate::build_selfupdate_command;
+use crate::sdkman_cli::version::build_version_command;
+use crate::sdkman_cli::config::build_config_command;
+use crate::sdkman_cli::doctor::build_doctor_command;
+use crate::sdkman_cli::offline::build_offline_command;
 
-pub const VERSION: &str = "0.2.9";
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub fn build_sdkman_app() -> Command {
     let install_command = Command::new("install")
@@ -23,7 +31,7 @@ pub fn build_sdkman_app() -> Command {
         )
         .arg(
             Arg::new("version")
-                .help("candidate version.")
+                .help("candidate version, such as 21, 21.0.2-zulu, 3.9 or latest")
                 .required(false)
                 .index(2)
                 .num_args(1)
@@ -34,10 +42,17 @@ pub fn build_sdkman_app() -> Command {
                 .required(false)
                 .index(3)
                 .num_args(1)
+        )
+        .arg(
+            Arg::new("vendor")
+                .long("vendor")
+                .help("vendor or dist, such as tem, zulu or graalce")
+                .num_args(1)
+                .required(false)
         );
     let uninstall_command = Command::new("uninstall")
         .about("uninstall a candidate version.")
-        .long_about(r#"Always follow the subcommand with two qualifiers, the candidate and version to be uninstalled. The specified version will be removed from the corresponding candidate directory under $SDKMAN_DIR/candidates and will no longer be available for use on the system."#)
+        .long_about(r#"Always follow the subcommand with two qualifiers, the candidate and version to be uninstalled. The specified version will be removed from the corresponding candidate directory under $SDKMAN_DIR/candidates and will no longer be available for use on the system. For a local version added from a path, only the link is removed and the local installation is kept. The current default version is only removed with --force."#)
         .arg(
             Arg::new("candidate")
                 .help("candidate name")
@@ -50,6 +65,13 @@ pub fn build_sdkman_app() -> Command {
                 .required(true)
                 .index(2)
                 .num_args(1)
+        )
+        .arg(
+            Arg::new("force")
+                .long("force")
+                .help("Remove the version even if it is the current default.")
+                .num_args(0)
+                .required(false)
         );
     let home_command = Command::new("home")
         .about("output the path of a specific candidate version.")
@@ -83,7 +105,38 @@ Java has a custom list view with vendor-specific details. "#)
         .arg(
             Arg::new("local")
                 .long("local")
-                .help("local installed candidates")
+                .help("local installed versions with size, source, install date and status")
+                .num_args(0)
+                .required(false)
+        )
+        .arg(
+            Arg::new("repair")
+                .long("repair")
+                .help("repair broken installations with --local: remove dangling links and reinstall versions without bin/")
+                .num_args(0)
+                .requires("local")
+                .required(false)
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .short('f')
+                .help("output format: text or json")
+                .value_parser(["text", "json"])
+                .num_args(1)
+                .required(false)
+        )
+        .arg(
+            Arg::new("vendor")
+                .long("vendor")
+                .help("Java vendor or dist, such as tem, zulu or Corretto")
+                .num_args(1)
+                .required(false)
+        )
+        .arg(
+            Arg::new("installed")
+                .long("installed")
+                .help("installed versions only")
                 .num_args(0)
                 .required(false)
         )
@@ -104,10 +157,17 @@ Java has a custom list view with vendor-specific details. "#)
         )
         .arg(
             Arg::new("version")
-                .help("candidate version.")
+                .help("candidate version, such as 21, 21.0.2-zulu or 3.9")
                 .required(true)
                 .index(2)
                 .num_args(1)
+        )
+        .arg(
+            Arg::new("vendor")
+                .long("vendor")
+                .help("vendor or dist, such as tem, zulu or graalce")
+                .num_args(1)
+                .required(false)
         );
     let default_command = Command::new("default")
         .about("set the local default version of the candidate.")
@@ -140,10 +200,25 @@ Java has a custom list view with vendor-specific details. "#)
         .arg(
             Arg::new("yes")
                 .short('y')
-                .help("Accept an installed candidate version as default.")
+                .help("Upgrade without confirmation.")
                 .num_args(0)
                 .required(false)
         )
+        .arg(
+            Arg::new("prune")
+                .long("prune")
+                .help("Remove superseded versions not used as default or by version files of projects.")
+                .num_args(0)
+                .required(false)
+        )
+        .arg(
+            Arg::new("project-root")
+                .long("project-root")
+                .help("Directory to scan for project version files with --prune, sdkman_project_roots in config by default.")
+                .num_args(1)
+                .action(ArgAction::Append)
+                .required(false)
+        )
         .arg(
             Arg::new("candidate")
                 .help("candidate name")
@@ -154,10 +229,16 @@ Java has a custom list view with vendor-specific details. "#)
         .version(VERSION)
         .about("sdk - The command line interface (CLI) for SDKMAN!")
         .long_about(r#"SDKMAN! is a tool for managing parallel versions of multiple JVM related Software Development Kits on most Unix based systems. It provides a convenient Command Line Interface (CLI) and API for installing, switching, removing and listing Candidates."#)
-        .subcommand(
-            Command::new("init")
-                .about("initialize SDKMAN! in the current shell: eval $(sdk init)")
+        .arg(
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .help("Quiet mode, no progress output.")
+                .num_args(0)
+                .global(true)
+                .required(false)
         )
+        .subcommand(build_init_command())
         .subcommand(install_command)
         .subcommand(uninstall_command)
         .subcommand(list_command)
@@ -168,4 +249,11 @@ Java has a custom list view with vendor-specific details. "#)
         .subcommand(env_command)
         .subcommand(direnv_command)
         .subcommand(upgrade_command)
+        .subcommand(build_offline_command())
+        .subcommand(build_flush_command())
+        .subcommand(build_version_command())
+        .subcommand(build_broadcast_command())
+        .subcommand(build_selfupdate_command())
+        .subcommand(build_config_command())
+        .subcommand(build_doctor_command())
 }
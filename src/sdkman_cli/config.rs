@@ -0,0 +1,277 @@
+//! Typed schema for `$SDKMAN_DIR/etc/config`, and `sdk config get|set|list|edit`.
+//!
+//! Keys are accepted with or without the `sdkman_` prefix: `sdk config set auto_answer true`.
+//! Comments, blank lines and the order of entries are kept when a value is written.
+
+use crate::sdkman_cli::version_files::parse_sdkmanrc_line;
+use crate::sdkman_cli::{read_sdkman_config, sdkman_home};
+use clap::{Arg, Command};
+use colored::Colorize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use wukong::error::exit_with_error;
+use wukong::Error;
+
+static DEBUG_MODE: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    /// `true` or `false`
+    Bool,
+    Seconds,
+    /// comma separated values
+    List,
+}
+
+#[derive(Debug)]
+pub struct ConfigKey {
+    pub name: &'static str,
+    pub value_type: ValueType,
+    pub default: &'static str,
+    pub description: &'static str,
+}
+
+/// keys of SDKMAN config, including keys only used by SDKMAN bash scripts, so a shared config is valid
+pub const CONFIG_KEYS: [ConfigKey; 14] = [
+    ConfigKey { name: "sdkman_auto_answer", value_type: ValueType::Bool, default: "false", description: "answer yes to all prompts" },
+    ConfigKey { name: "sdkman_auto_complete", value_type: ValueType::Bool, default: "true", description: "shell completion, SDKMAN scripts only" },
+    ConfigKey { name: "sdkman_auto_env", value_type: ValueType::Bool, default: "false", description: "apply .sdkmanrc on directory change" },
+    ConfigKey { name: "sdkman_auto_update", value_type: ValueType::Bool, default: "true", description: "update candidate list automatically, SDKMAN scripts only" },
+    ConfigKey { name: "sdkman_beta_channel", value_type: ValueType::Bool, default: "false", description: "beta channel, SDKMAN scripts only" },
+    ConfigKey { name: "sdkman_checksum_enable", value_type: ValueType::Bool, default: "true", description: "verify checksums of downloads" },
+    ConfigKey { name: "sdkman_colour_enable", value_type: ValueType::Bool, default: "true", description: "coloured output" },
+    ConfigKey { name: "sdkman_curl_connect_timeout", value_type: ValueType::Seconds, default: "7", description: "connect timeout of HTTP requests in seconds" },
+    ConfigKey { name: "sdkman_curl_max_time", value_type: ValueType::Seconds, default: "10", description: "max time of API requests in seconds, downloads are not limited" },
+    ConfigKey { name: "sdkman_debug_mode", value_type: ValueType::Bool, default: "false", description: "print API requests" },
+    ConfigKey { name: "sdkman_insecure_ssl", value_type: ValueType::Bool, default: "false", description: "accept invalid certificates" },
+    ConfigKey { name: "sdkman_rosetta2_compatible", value_type: ValueType::Bool, default: "false", description: "x86_64 binaries on Apple Silicon, SDKMAN scripts only" },
+    ConfigKey { name: "sdkman_selfupdate_feature", value_type: ValueType::Bool, default: "true", description: "allow sdk selfupdate" },
+    ConfigKey { name: "sdkman_project_roots", value_type: ValueType::List, default: "", description: "project directories scanned by sdk upgrade --prune" },
+];
+
+impl ConfigKey {
+    /// normalized value, or config error for invalid value
+    pub fn validate(&self, value: &str) -> wukong::Result<String> {
+        let value = value.trim();
+        let valid = match self.value_type {
+            ValueType::Bool => value == "true" || value == "false",
+            ValueType::Seconds => value.parse::<u64>().is_ok(),
+            ValueType::List => true,
+        };
+        if valid {
+            Ok(value.to_string())
+        } else {
+            let expected = match self.value_type {
+                ValueType::Bool => "true or false",
+                ValueType::Seconds => "seconds",
+                ValueType::List => "comma separated values",
+            };
+            Err(Error::Config(format!("Invalid value for {}: {}, expected {}", self.name, value, expected)))
+        }
+    }
+}
+
+/// key by name, `sdkman_` prefix is optional
+pub fn find_config_key(name: &str) -> Option<&'static ConfigKey> {
+    let name = name.trim();
+    CONFIG_KEYS.iter()
+        .find(|key| key.name == name || key.name.strip_prefix("sdkman_") == Some(name))
+}
+
+fn config_key(name: &str) -> wukong::Result<&'static ConfigKey> {
+    find_config_key(name).ok_or_else(|| Error::Config(format!("Unknown config key: {}, run `sdk config list` for supported keys", name)))
+}
+
+/// value from config, then default value of the key
+pub fn get_config_value(config: &HashMap<String, String>, name: &str) -> String {
+    let Some(key) = find_config_key(name) else {
+        return config.get(name).cloned().unwrap_or_default();
+    };
+    config.get(key.name)
+        .and_then(|value| key.validate(value).ok())
+        .unwrap_or_else(|| key.default.to_string())
+}
+
+pub fn is_config_enabled(config: &HashMap<String, String>, name: &str) -> bool {
+    get_config_value(config, name) == "true"
+}
+
+/// boolean key of `$SDKMAN_DIR/etc/config`, such as `sdkman_auto_answer`
+pub fn config_enabled(name: &str) -> bool {
+    is_config_enabled(&read_sdkman_config(), name)
+}
+
+/// `sdkman_debug_mode=true`, API requests are printed to stderr
+pub fn set_debug_mode(debug_mode: bool) {
+    DEBUG_MODE.store(debug_mode, Ordering::Relaxed);
+}
+
+pub fn is_debug_mode() -> bool {
+    DEBUG_MODE.load(Ordering::Relaxed)
+}
+
+/// config text with the value of the key replaced in place, or appended if missing
+pub fn set_config_text(text: &str, name: &str, value: &str) -> String {
+    let mut lines: Vec<String> = vec![];
+    let mut found = false;
+    for line in text.lines() {
+        if let Some((key, _)) = parse_sdkmanrc_line(line) && key == name {
+            // duplicated keys are removed, the first one is updated
+            if !found {
+                lines.push(format!("{}={}", name, value));
+                found = true;
+            }
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+    if !found {
+        lines.push(format!("{}={}", name, value));
+    }
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+/// problems of config text: unknown keys and invalid values
+pub fn validate_config_text(text: &str) -> Vec<String> {
+    text.lines()
+        .filter_map(parse_sdkmanrc_line)
+        .filter_map(|(name, value)| match CONFIG_KEYS.iter().find(|key| key.name == name) {
+            Some(key) => key.validate(&value).err().map(|e| e.to_string()),
+            None => Some(format!("Unknown config key: {}", name)),
+        })
+        .collect()
+}
+
+fn config_file_path() -> PathBuf {
+    sdkman_home().join("etc").join("config")
+}
+
+pub fn manage_config(config_matches: &clap::ArgMatches) {
+    let result = match config_matches.subcommand() {
+        Some(("get", get_matches)) => config_get(get_matches.get_one::<String>("key").unwrap()),
+        Some(("set", set_matches)) => config_set(set_matches.get_one::<String>("key").unwrap(), set_matches.get_one::<String>("value").unwrap()),
+        Some(("edit", _)) => config_edit(),
+        _ => config_list(),
+    };
+    if let Err(e) = result {
+        exit_with_error("sdk", &e);
+    }
+}
+
+fn config_get(name: &str) -> wukong::Result<()> {
+    let key = config_key(name)?;
+    println!("{}", get_config_value(&read_sdkman_config(), key.name));
+    Ok(())
+}
+
+fn config_set(name: &str, value: &str) -> wukong::Result<()> {
+    let key = config_key(name)?;
+    let value = key.validate(value)?;
+    let config_file_path = config_file_path();
+    let text = std::fs::read_to_string(&config_file_path).unwrap_or_default();
+    std::fs::create_dir_all(config_file_path.parent().unwrap())?;
+    std::fs::write(&config_file_path, set_config_text(&text, key.name, &value))?;
+    println!("{}", format!("{} set to {}", key.name, value).green());
+    Ok(())
+}
+
+/// all supported keys with values, and problems of config file
+fn config_list() -> wukong::Result<()> {
+    let config = read_sdkman_config();
+    for key in &CONFIG_KEYS {
+        let value = get_config_value(&config, key.name);
+        let source = if config.contains_key(key.name) { "" } else { " (default)" };
+        println!("{}={}{}  {}", key.name, value, source, format!("# {}", key.description).dimmed());
+    }
+    print_problems(&std::fs::read_to_string(config_file_path()).unwrap_or_default());
+    Ok(())
+}
+
+/// open config file with `$VISUAL` or `$EDITOR`, then validate it
+fn config_edit() -> wukong::Result<()> {
+    let config_file_path = config_file_path();
+    if !config_file_path.exists() {
+        std::fs::create_dir_all(config_file_path.parent().unwrap())?;
+        std::fs::write(&config_file_path, "")?;
+    }
+    let editor = std::env::var("VISUAL").or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| if cfg!(windows) { "notepad".to_owned() } else { "vi".to_owned() });
+    let status = std::process::Command::new(&editor).arg(&config_file_path).status()
+        .map_err(|e| Error::Config(format!("Failed to launch editor {}: {}", editor, e)))?;
+    if !status.success() {
+        return Err(Error::Config(format!("Editor {} exited with {}", editor, status)));
+    }
+    print_problems(&std::fs::read_to_string(&config_file_path)?);
+    Ok(())
+}
+
+fn print_problems(text: &str) {
+    for problem in validate_config_text(text) {
+        eprintln!("{}", format!("[sdk] {}", problem).yellow());
+    }
+}
+
+pub fn build_config_command() -> Command {
+    Command::new("config")
+        .about("get, set, list or edit SDKMAN config in $SDKMAN_DIR/etc/config.")
+        .long_about(r#"Without subcommand, list all supported keys with values. Keys can be given without the sdkman_ prefix: sdk config set auto_answer true"#)
+        .subcommand(
+            Command::new("get")
+                .about("print the value of a config key")
+                .arg(Arg::new("key").help("config key, such as auto_env").index(1).required(true))
+        )
+        .subcommand(
+            Command::new("set")
+                .about("set the value of a config key, comments and order of the file are kept")
+                .arg(Arg::new("key").help("config key, such as auto_env").index(1).required(true))
+                .arg(Arg::new("value").help("config value").index(2).required(true))
+        )
+        .subcommand(Command::new("list").about("list all supported keys with values"))
+        .subcommand(Command::new("edit").about("open config file with $VISUAL or $EDITOR, then validate it"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_keys() {
+        assert_eq!(find_config_key("auto_answer").unwrap().name, "sdkman_auto_answer");
+        assert_eq!(find_config_key("sdkman_curl_max_time").unwrap().value_type, ValueType::Seconds);
+        assert!(find_config_key("auto_answers").is_none());
+        assert!(find_config_key("auto_env").unwrap().validate("yes").is_err());
+        assert_eq!(find_config_key("curl_connect_timeout").unwrap().validate(" 15 ").unwrap(), "15");
+        let config = HashMap::from([
+            ("sdkman_auto_answer".to_owned(), "false".to_owned()),
+            ("sdkman_auto_env".to_owned(), "true".to_owned()),
+            ("sdkman_debug_mode".to_owned(), "maybe".to_owned()),
+        ]);
+        assert!(!is_config_enabled(&config, "sdkman_auto_answer"));
+        assert!(is_config_enabled(&config, "auto_env"));
+        assert!(is_config_enabled(&config, "sdkman_colour_enable"));
+        // invalid value falls back to default
+        assert!(!is_config_enabled(&config, "sdkman_debug_mode"));
+    }
+
+    #[test]
+    fn test_set_config_text() {
+        let text = "# SDKMAN config\nsdkman_auto_answer=false\n\n# timeouts\nsdkman_curl_max_time = 10\nsdkman_auto_env=false\n";
+        assert_eq!(set_config_text(text, "sdkman_curl_max_time", "20"),
+                   "# SDKMAN config\nsdkman_auto_answer=false\n\n# timeouts\nsdkman_curl_max_time=20\nsdkman_auto_env=false\n");
+        assert_eq!(set_config_text(text, "sdkman_debug_mode", "true"), format!("{}sdkman_debug_mode=true\n", text));
+        assert_eq!(set_config_text("", "sdkman_auto_env", "true"), "sdkman_auto_env=true\n");
+        assert_eq!(set_config_text("sdkman_auto_env=false\nsdkman_auto_env=true", "sdkman_auto_env", "true"), "sdkman_auto_env=true\n");
+    }
+
+    #[test]
+    fn test_validate_config_text() {
+        let text = "# comment\nsdkman_auto_answer=true\nsdkman_auto_anwser=true\nsdkman_curl_connect_timeout=fast\n";
+        assert_eq!(validate_config_text(text), vec![
+            "Unknown config key: sdkman_auto_anwser".to_owned(),
+            "Config error: Invalid value for sdkman_curl_connect_timeout: fast, expected seconds".to_owned(),
+        ]);
+    }
+}
@@ -1,25 +1,41 @@
-use crate::sdkman_cli::{find_candidate_home};
+use crate::sdkman_cli::{find_candidate_home, find_installed_version, get_remote_candidate_default_version};
+use colored::Colorize;
+use wukong::error::exit_with_error;
 
 pub fn manage_default(default_matches: &clap::ArgMatches) {
     let candidate_name = default_matches.get_one::<String>("candidate").unwrap();
-    let candidate_version = default_matches.get_one::<String>("version").unwrap();
-    let candidate_home = find_candidate_home(candidate_name, candidate_version);
-    if candidate_home.exists() {
-        make_candidate_default(candidate_name, candidate_version);
+    let candidate_version = match default_matches.get_one::<String>("version") {
+        Some(candidate_version) => candidate_version.clone(),
+        // SDKMAN tracked default, cached metadata is used in offline mode
+        None => match get_remote_candidate_default_version(candidate_name) {
+            Ok(default_version) if !default_version.is_empty() => default_version,
+            Ok(_) => exit_with_error("sdk", &wukong::Error::NotFound(format!("default version for {}", candidate_name))),
+            Err(e) => exit_with_error("sdk", &e),
+        },
+    };
+    let installed_version = if find_candidate_home(candidate_name, &candidate_version).exists() {
+        Some(candidate_version.clone())
     } else {
-        eprintln!("{}@{} not installed, please install it first!", candidate_name, candidate_version);
+        find_installed_version(candidate_name, &candidate_version, None)
+    };
+    match installed_version {
+        Some(installed_version) => {
+            make_candidate_default(candidate_name, &installed_version);
+            println!("{}", format!("Default {} version set to {}", candidate_name, installed_version).green());
+        }
+        None => eprintln!("{candidate_name}@{candidate_version} not installed, please use `sdk install {candidate_name} {candidate_version}` to install."),
     }
 }
 
-
 pub fn make_candidate_default(candidate_name: &str, candidate_version: &str) {
     let candidate_home = find_candidate_home(candidate_name, &candidate_version);
     if !candidate_home.exists() {
-        eprintln!("{candidate_name}@{candidate_name} not installed, please use `sdk install {candidate_name} {candidate_name}` to install.", );
+        eprintln!("{candidate_name}@{candidate_version} not installed, please use `sdk install {candidate_name} {candidate_version}` to install.");
         return;
     }
     let candidate_current_link = candidate_home.parent().unwrap().join("current");
-    if candidate_current_link.exists() && candidate_current_link.is_symlink() {
+    // broken link is replaced too
+    if candidate_current_link.is_symlink() {
         symlink::remove_symlink_dir(&candidate_current_link).unwrap();
     }
     symlink::symlink_dir(&candidate_home, &candidate_current_link).unwrap();
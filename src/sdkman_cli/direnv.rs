@@ -1,9 +1,10 @@
-use std::fs::File;
-use std::io::BufReader;
 use std::path::PathBuf;
 use clap::Command;
-use crate::sdkman_cli::install::install_candidate;
-use crate::sdkman_cli::{find_candidate_home, find_java_home, find_java_version, sdkman_home};
+use crate::sdkman_cli::env::load_versions_env_changes;
+use crate::sdkman_cli::shell::{EnvChanges, Shell};
+use crate::sdkman_cli::use_candidate::candidate_env;
+use crate::sdkman_cli::version_files::{read_project_versions_with_parents, ProjectVersion, VersionFile};
+use crate::sdkman_cli::find_java_home;
 
 pub fn manage_direnv(direnv_matches: &clap::ArgMatches) {
     if direnv_matches.subcommand_matches("init").is_some() {
@@ -26,77 +27,38 @@ pub fn direnv_init() {
     println!("direnv initialized");
 }
 
+/// print exports of installed versions from project version files, missing versions are reported to stderr,
+/// and installed by `sdk env install` instead of network access here.
+/// `.java-version` of parent directories is used as jenv does.
 pub fn direnv_hook() {
-    let mut paths: Vec<String> = vec![];
-    let mut java_version = None;
-    let candidates_path = sdkman_home().join("candidates");
-    let sdkman_rc = PathBuf::from(".sdkmanrc");
-    if sdkman_rc.exists() {
-        let pairs = java_properties::read(BufReader::new(File::open(&sdkman_rc).unwrap())).unwrap();
-        for (candidate_name, candidate_version) in &pairs {
-            let mut candidate_home = candidates_path.join(candidate_name).join(candidate_version);
-            if candidate_name == "java" && candidate_version.parse::<u32>().is_ok() {
-                if let Some(java_home) = find_java_home(candidate_version) {
-                    candidate_home = java_home;
-                }
-            }
-            if candidate_home.exists() {
-                let candidate_home_dir = candidate_home.to_str().unwrap();
-                println!("export {}_HOME={}", candidate_name.to_uppercase(), candidate_home_dir);
-                if candidate_name == "java" && candidate_home_dir.contains("graal") {
-                    println!("export GRAALVM_HOME={}", candidate_home_dir);
-                }
-                if candidate_home.join("bin").exists() {
-                    paths.push(candidate_home.join("bin").to_str().unwrap().to_string());
-                } else {
-                    paths.push(candidate_home_dir.to_string());
-                }
-                if candidate_name == "java" {
-                    java_version = Some(candidate_version.clone());
-                }
-            } else {
-                install_candidate(candidate_name, candidate_version);
-            }
-        }
+    let mut changes = EnvChanges::default();
+    let project_versions = read_project_versions_with_parents(&std::env::current_dir().unwrap());
+    let project_versions = load_jbang_java_env_changes(&mut changes, project_versions, find_java_home);
+    load_versions_env_changes(&mut changes, project_versions);
+    // direnv evaluates output with bash
+    for statement in changes.render(Shell::Bash) {
+        println!("{}", statement);
     }
-    if java_version.is_none() {
-        let java_version_file = PathBuf::from(".java-version");
-        if java_version_file.exists() {
-            let java_version = std::fs::read_to_string(java_version_file).unwrap().trim().to_string();
-            if java_version.parse::<u32>().is_ok() { // load java home from JBang
-                if let Some(java_home) = find_java_home(&java_version) {
-                    let java_home_dir = java_home.to_str().unwrap();
-                    println!("export JAVA_HOME={}", java_home_dir);
-                    if java_home_dir.contains("graal") {
-                        println!("export GRAALVM_HOME={}", java_home_dir);
-                    }
-                    paths.push(java_home.join("bin").to_str().unwrap().to_string());
-                } else {
-                    let java_version = find_java_version(&java_version).unwrap();
-                    let java_home = find_candidate_home("java", &java_version);
-                    wukong::foojay::install_jdk(&java_version, &java_home);
-                }
-            } else { // load java home from SDKMAN
-                let java_home = candidates_path.join("java").join(&java_version);
-                if java_home.exists() {
-                    let java_home_dir = java_home.to_str().unwrap();
-                    println!("export JAVA_HOME={}", java_home_dir);
-                    if java_version.contains("graal") {
-                        println!("export GRAALVM_HOME={}", java_home_dir);
-                    }
-                    paths.push(java_home.join("bin").to_str().unwrap().to_string());
-                } else {
-                    install_candidate("java", &java_version);
-                }
+}
+
+/// a major version of `.java-version`, such as `21`, is loaded from SDKMAN, then the JDK of JBang,
+/// and the other versions are returned
+fn load_jbang_java_env_changes<F>(changes: &mut EnvChanges, project_versions: Vec<ProjectVersion>, java_home_of: F) -> Vec<ProjectVersion>
+where
+    F: Fn(&str) -> Option<PathBuf>,
+{
+    project_versions.into_iter()
+        .filter(|project_version| {
+            let is_major_version = project_version.candidate == "java"
+                && project_version.source == VersionFile::JavaVersion
+                && project_version.version.parse::<u32>().is_ok();
+            if is_major_version && let Some(java_home) = java_home_of(&project_version.version) {
+                candidate_env(changes, "java", &java_home);
+                return false;
             }
-        }
-    }
-    if let Some(java_version) = java_version {
-        println!("export JENV_VERSION={}", java_version);
-    }
-    if !paths.is_empty() {
-        println!("export PATH={}:$PATH", paths.join(":"));
-    }
+            true
+        })
+        .collect()
 }
 
 pub fn build_direnv_command() -> Command {
@@ -116,5 +78,35 @@ mod tests {
     fn test_direnv_hook() {
         direnv_hook();
     }
+
+    #[test]
+    fn test_jbang_java_fallback() {
+        let jdks_dir = tempfile::tempdir().unwrap();
+        let java_home = jdks_dir.path().join("21");
+        std::fs::create_dir_all(java_home.join("bin")).unwrap();
+        std::fs::write(java_home.join("bin").join(if cfg!(windows) { "java.exe" } else { "java" }), "").unwrap();
+        let project_version = |version: &str, source: VersionFile| ProjectVersion {
+            candidate: "java".to_owned(),
+            version: version.to_owned(),
+            source,
+        };
+        let java_home_of = |major_version: &str| crate::sdkman_cli::find_jbang_java_home(jdks_dir.path(), major_version);
+        let mut changes = EnvChanges::default();
+        let project_versions = vec![project_version("21", VersionFile::JavaVersion)];
+        assert!(load_jbang_java_env_changes(&mut changes, project_versions, java_home_of).is_empty());
+        let statements = changes.render(Shell::Bash);
+        assert!(statements.iter().any(|statement| statement.starts_with("export JAVA_HOME=")
+            && statement.contains(&java_home.display().to_string())));
+        // versions with vendor, versions of other files and missing JDKs are left for SDKMAN
+        for project_version in [
+            project_version("21.0.2-tem", VersionFile::JavaVersion),
+            project_version("21", VersionFile::Sdkmanrc),
+            project_version("17", VersionFile::JavaVersion),
+        ] {
+            let mut changes = EnvChanges::default();
+            let project_versions = load_jbang_java_env_changes(&mut changes, vec![project_version.clone()], java_home_of);
+            assert_eq!(project_versions, vec![project_version]);
+        }
+    }
 }
 
@@ -0,0 +1,418 @@
+//! `sdk doctor`: diagnostics for SDKMAN layout, `JAVA_HOME`/PATH conflicts, candidate launchers,
+//! dangling links of SDKMAN, jenv and JBang, and the shell init snippet.
+
+use crate::sdkman_cli::config::validate_config_text;
+use crate::sdkman_cli::list::scan_local_versions;
+use crate::sdkman_cli::shell::Shell;
+use crate::sdkman_cli::{find_candidate_home, get_installed_candidate_default_version, list_candidate_names, sdkman_home};
+use clap::Command;
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use wukong::common::{discover_jdks, home_dir, is_java_home, jbang_home};
+use wukong::error::exit_with_error;
+
+/// max time for a launcher to print its version
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// candidate, launcher in `bin/` and version argument, `bin/<candidate> --version` for others
+const LAUNCHERS: [(&str, &str, &str); 10] = [
+    ("java", "java", "-version"),
+    ("maven", "mvn", "--version"),
+    ("mvnd", "mvnd", "--version"),
+    ("gradle", "gradle", "--version"),
+    ("kotlin", "kotlin", "-version"),
+    ("scala", "scala", "-version"),
+    ("groovy", "groovy", "--version"),
+    ("springboot", "spring", "--version"),
+    ("ant", "ant", "-version"),
+    ("sbt", "sbt", "--script-version"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnosis {
+    pub level: Level,
+    pub message: String,
+    /// actionable fix, such as a command to run
+    pub fix: Option<String>,
+}
+
+impl Diagnosis {
+    fn ok(message: impl Into<String>) -> Self {
+        Diagnosis { level: Level::Ok, message: message.into(), fix: None }
+    }
+
+    fn warning(message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Diagnosis { level: Level::Warning, message: message.into(), fix: Some(fix.into()) }
+    }
+
+    fn error(message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Diagnosis { level: Level::Error, message: message.into(), fix: Some(fix.into()) }
+    }
+}
+
+pub fn manage_doctor(_doctor_matches: &clap::ArgMatches) {
+    let sdkman_home = sdkman_home();
+    let home_dir = home_dir().unwrap_or_else(|e| exit_with_error("sdk", &e));
+    let jbang_home = jbang_home().unwrap_or_else(|e| exit_with_error("sdk", &e));
+    let java_home = std::env::var_os("JAVA_HOME").filter(|value| !value.is_empty()).map(PathBuf::from);
+    let java_dir = sdkman_home.join("candidates").join("java");
+    let current_java = Some(java_dir.join("current")).filter(|current_link| current_link.exists());
+    let path_entries: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+    let shell = Shell::current();
+    let mut diagnoses = check_layout(&sdkman_home);
+    diagnoses.extend(check_candidates(&sdkman_home));
+    diagnoses.push(check_java_home(java_home.as_deref(), &java_dir, current_java.as_deref()));
+    diagnoses.extend(check_path(&path_entries, java_home.as_deref(), &home_dir.join(".jenv").join("shims")));
+    diagnoses.extend(check_jenv(&home_dir.join(".jenv")));
+    diagnoses.extend(check_jbang_current_jdk(&jbang_home.join("currentjdk"), current_java.as_deref()));
+    diagnoses.push(check_shell_init(shell, &rc_files(shell, &home_dir)));
+    diagnoses.push(summarize_jdks());
+    print_diagnoses(&diagnoses);
+    if diagnoses.iter().any(|diagnosis| diagnosis.level == Level::Error) {
+        std::process::exit(1);
+    }
+}
+
+fn print_diagnoses(diagnoses: &[Diagnosis]) {
+    for diagnosis in diagnoses {
+        let label = match diagnosis.level {
+            Level::Ok => "[ok]".green(),
+            Level::Warning => "[warn]".yellow(),
+            Level::Error => "[error]".red(),
+        };
+        println!("{} {}", label, diagnosis.message);
+        if let Some(fix) = &diagnosis.fix {
+            println!("  fix: {}", fix);
+        }
+    }
+    let warnings = diagnoses.iter().filter(|diagnosis| diagnosis.level == Level::Warning).count();
+    let errors = diagnoses.iter().filter(|diagnosis| diagnosis.level == Level::Error).count();
+    if warnings + errors == 0 {
+        println!("{}", "No problems found.".green());
+    } else {
+        println!("{} warning(s), {} error(s) found.", warnings, errors);
+    }
+}
+
+/// `$SDKMAN_DIR` with `candidates`, `etc`, `var` and `tmp`, and valid `etc/config`
+fn check_layout(sdkman_home: &Path) -> Vec<Diagnosis> {
+    if !sdkman_home.is_dir() {
+        return vec![Diagnosis::error(format!("SDKMAN directory {} not found", sdkman_home.display()),
+                                     "install a candidate with `sdk install java`, or set SDKMAN_DIR to your SDKMAN directory")];
+    }
+    let mut diagnoses = vec![];
+    let missing_dirs: Vec<PathBuf> = ["candidates", "etc", "var", "tmp"].iter()
+        .map(|name| sdkman_home.join(name))
+        .filter(|dir| !dir.is_dir())
+        .collect();
+    if missing_dirs.is_empty() {
+        diagnoses.push(Diagnosis::ok(format!("SDKMAN directory {}", sdkman_home.display())));
+    } else {
+        let dirs: Vec<String> = missing_dirs.iter().map(|dir| dir.display().to_string()).collect();
+        diagnoses.push(Diagnosis::warning(format!("SDKMAN directories missing: {}", dirs.join(", ")),
+                                          format!("mkdir -p {}", dirs.join(" "))));
+    }
+    let config_text = std::fs::read_to_string(sdkman_home.join("etc").join("config")).unwrap_or_default();
+    for problem in validate_config_text(&config_text) {
+        diagnoses.push(Diagnosis::warning(problem, "sdk config edit"));
+    }
+    diagnoses
+}
+
+/// broken installations, and launchers of default versions
+fn check_candidates(sdkman_home: &Path) -> Vec<Diagnosis> {
+    let mut diagnoses = vec![];
+    let mut candidate_names = list_candidate_names();
+    candidate_names.sort();
+    for candidate_name in &candidate_names {
+        for local_version in scan_local_versions(&sdkman_home.join("candidates").join(candidate_name), candidate_name) {
+            if let Some(problem) = local_version.problem {
+                diagnoses.push(Diagnosis::error(format!("{} {}: {}", candidate_name, local_version.version, problem.label()),
+                                                "sdk list --local --repair"));
+            }
+        }
+        let current_version = get_installed_candidate_default_version(candidate_name);
+        let candidate_home = find_candidate_home(candidate_name, &current_version);
+        if current_version.is_empty() || !candidate_home.exists() {
+            continue;
+        }
+        let Some((launcher, version_arg)) = find_launcher(candidate_name, &candidate_home) else {
+            continue;
+        };
+        let launcher_name = launcher.file_name().unwrap_or_default().to_string_lossy().to_string();
+        match launch(&launcher, version_arg) {
+            Ok(_) => diagnoses.push(Diagnosis::ok(format!("{} {}: {} launched", candidate_name, current_version, launcher_name))),
+            Err(message) => diagnoses.push(Diagnosis::error(
+                format!("{} {}: {} failed to launch, {}", candidate_name, current_version, launcher_name, message),
+                format!("sdk uninstall {} {} --force && sdk install {} {}", candidate_name, current_version, candidate_name, current_version),
+            )),
+        }
+    }
+    diagnoses
+}
+
+/// launcher in `bin/` with version argument
+fn find_launcher(candidate_name: &str, candidate_home: &Path) -> Option<(PathBuf, &'static str)> {
+    let (launcher_name, version_arg) = LAUNCHERS.iter()
+        .find(|(name, _, _)| *name == candidate_name)
+        .map(|(_, launcher_name, version_arg)| (launcher_name.to_string(), *version_arg))
+        .unwrap_or((candidate_name.to_string(), "--version"));
+    let bin_dirs = [candidate_home.join("bin"), candidate_home.join("Contents").join("Home").join("bin")];
+    let suffixes: &[&str] = if cfg!(windows) { &[".exe", ".cmd", ".bat"] } else { &[""] };
+    bin_dirs.iter()
+        .flat_map(|bin_dir| suffixes.iter().map(|suffix| bin_dir.join(format!("{}{}", launcher_name, suffix))))
+        .find(|launcher| launcher.is_file())
+        .map(|launcher| (launcher, version_arg))
+}
+
+/// run launcher with version argument, killed after `LAUNCH_TIMEOUT`
+fn launch(launcher: &Path, version_arg: &str) -> Result<(), String> {
+    let mut child = std::process::Command::new(launcher)
+        .arg(version_arg)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok(()),
+            Ok(Some(status)) => return Err(format!("exit with {}", status)),
+            Ok(None) if started.elapsed() > LAUNCH_TIMEOUT => {
+                let _ = child.kill();
+                return Err(format!("no response in {}s", LAUNCH_TIMEOUT.as_secs()));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+}
+
+/// `JAVA_HOME` should be a JDK, and managed by SDKMAN if SDKMAN has a default Java
+fn check_java_home(java_home: Option<&Path>, java_dir: &Path, current_java: Option<&Path>) -> Diagnosis {
+    match (java_home, current_java) {
+        (None, Some(_)) => Diagnosis::warning("JAVA_HOME is not set, but SDKMAN has a default java",
+                                              "add the `sdk init` snippet to your shell rc file, or run `sdk env` in your project"),
+        (None, None) => Diagnosis::ok("JAVA_HOME is not set, and no default java in SDKMAN"),
+        (Some(java_home), _) if !is_java_home(&java_home.to_path_buf()) => {
+            Diagnosis::error(format!("JAVA_HOME {} is not a JDK", java_home.display()),
+                             "unset JAVA_HOME, or run `sdk default java <version>` and restart your shell")
+        }
+        (Some(java_home), _) if java_home.starts_with(java_dir) => {
+            Diagnosis::ok(format!("JAVA_HOME {} is managed by SDKMAN", java_home.display()))
+        }
+        (Some(java_home), Some(_)) => Diagnosis::warning(
+            format!("JAVA_HOME {} is outside SDKMAN, and the SDKMAN default java is ignored", java_home.display()),
+            "remove `export JAVA_HOME=...` from your shell rc file, or run `sdk use java <version>`"),
+        (Some(java_home), None) => Diagnosis::ok(format!("JAVA_HOME {}", java_home.display())),
+    }
+}
+
+fn java_executable_name() -> &'static str {
+    if cfg!(windows) { "java.exe" } else { "java" }
+}
+
+/// the first `java` on PATH should be from `JAVA_HOME`, and jenv shims should not shadow it
+fn check_path(path_entries: &[PathBuf], java_home: Option<&Path>, jenv_shims: &Path) -> Vec<Diagnosis> {
+    let mut diagnoses = vec![];
+    let java_dirs: Vec<&PathBuf> = path_entries.iter()
+        .filter(|entry| entry.join(java_executable_name()).is_file())
+        .collect();
+    let Some(first_java_dir) = java_dirs.first() else {
+        diagnoses.push(Diagnosis::warning("java not found on PATH", "sdk install java, and add the `sdk init` snippet to your shell rc file"));
+        return diagnoses;
+    };
+    if first_java_dir.as_path() == jenv_shims {
+        diagnoses.push(Diagnosis::warning(
+            format!("jenv shims {} take precedence over SDKMAN java on PATH", jenv_shims.display()),
+            "use either jenv or SDKMAN for java, remove `jenv init` or the `sdk init` snippet from your shell rc file"));
+    } else if let Some(java_home) = java_home && !same_dir(first_java_dir, &java_home.join("bin")) {
+        diagnoses.push(Diagnosis::warning(
+            format!("java on PATH is from {}, not from JAVA_HOME {}", first_java_dir.display(), java_home.display()),
+            format!("put {} before {} in PATH", java_home.join("bin").display(), first_java_dir.display())));
+    } else {
+        diagnoses.push(Diagnosis::ok(format!("java on PATH is from {}", first_java_dir.display())));
+    }
+    diagnoses
+}
+
+/// same directory, symlinks such as `current` resolved
+fn same_dir(dir1: &Path, dir2: &Path) -> bool {
+    match (dir1.canonicalize(), dir2.canonicalize()) {
+        (Ok(dir1), Ok(dir2)) => dir1 == dir2,
+        _ => dir1 == dir2,
+    }
+}
+
+/// dangling links in `~/.jenv/versions`
+fn check_jenv(jenv_home: &Path) -> Vec<Diagnosis> {
+    let Ok(entries) = jenv_home.join("versions").read_dir() else {
+        return vec![];
+    };
+    entries.flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_symlink() && !path.exists())
+        .map(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            Diagnosis::error(format!("jenv version {} links to a removed JDK", name), format!("jenv remove {}", name))
+        })
+        .collect()
+}
+
+/// JBang `currentjdk` link should not dangle, and should agree with SDKMAN default java
+fn check_jbang_current_jdk(current_jdk_link: &Path, current_java: Option<&Path>) -> Vec<Diagnosis> {
+    if !current_jdk_link.is_symlink() {
+        return vec![];
+    }
+    if !current_jdk_link.exists() {
+        return vec![Diagnosis::error(format!("JBang currentjdk {} links to a removed JDK", current_jdk_link.display()),
+                                     "jbang jdk default <version>")];
+    }
+    match current_java {
+        Some(current_java) if !same_dir(current_jdk_link, current_java) => vec![Diagnosis::warning(
+            format!("JBang currentjdk {} differs from SDKMAN default java {}",
+                    current_jdk_link.canonicalize().unwrap_or_default().display(), current_java.canonicalize().unwrap_or_default().display()),
+            "jbang jdk default <version> to use the same JDK, or ignore if intended")],
+        _ => vec![Diagnosis::ok("JBang currentjdk")],
+    }
+}
+
+/// rc files of the shell
+fn rc_files(shell: Shell, home_dir: &Path) -> Vec<PathBuf> {
+    let config_dir = home_dir.join(".config");
+    match shell {
+        Shell::Bash => vec![home_dir.join(".bashrc"), home_dir.join(".bash_profile"), home_dir.join(".profile")],
+        Shell::Zsh => {
+            let zdotdir = std::env::var_os("ZDOTDIR").map(PathBuf::from).unwrap_or_else(|| home_dir.to_path_buf());
+            vec![zdotdir.join(".zshrc"), zdotdir.join(".zprofile")]
+        }
+        Shell::Fish => vec![config_dir.join("fish").join("config.fish")],
+        Shell::Nushell => vec![config_dir.join("nushell").join("config.nu"), config_dir.join("nushell").join("env.nu")],
+        Shell::Pwsh => vec![
+            config_dir.join("powershell").join("Microsoft.PowerShell_profile.ps1"),
+            home_dir.join("Documents").join("PowerShell").join("Microsoft.PowerShell_profile.ps1"),
+        ],
+    }
+}
+
+/// `sdk init` of wukong or `sdkman-init.sh` of SDKMAN in rc files
+fn check_shell_init(shell: Shell, rc_files: &[PathBuf]) -> Diagnosis {
+    let found = rc_files.iter()
+        .find(|rc_file| std::fs::read_to_string(rc_file).is_ok_and(|text| has_init_snippet(&text)));
+    match found {
+        Some(rc_file) => Diagnosis::ok(format!("sdk init snippet in {}", rc_file.display())),
+        None => Diagnosis::warning(
+            format!("sdk init snippet not found in {} rc files", shell.name()),
+            format!("add `{}` to {}", init_snippet(shell), rc_files.first().map(|rc_file| rc_file.display().to_string()).unwrap_or_default())),
+    }
+}
+
+fn has_init_snippet(text: &str) -> bool {
+    text.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.starts_with('#'))
+        .any(|line| line.contains("sdk init") || line.contains("sdkman-init."))
+}
+
+fn init_snippet(shell: Shell) -> String {
+    match shell {
+        Shell::Bash | Shell::Zsh => format!("eval \"$(sdk init --shell {})\"", shell.name()),
+        Shell::Fish => "sdk init --shell fish | source".to_owned(),
+        Shell::Nushell => "sdk init --shell nushell | save -f ~/.sdkman/init.nu; source ~/.sdkman/init.nu".to_owned(),
+        Shell::Pwsh => "sdk init --shell powershell | Out-String | Invoke-Expression".to_owned(),
+    }
+}
+
+/// JDKs found on the machine, discovered the same way as `mt jdks`
+fn summarize_jdks() -> Diagnosis {
+    let jdks = match discover_jdks() {
+        Ok(jdks) => jdks,
+        Err(e) => return Diagnosis::warning(format!("Failed to discover JDKs: {}", e), "set HOME to the home directory of the current user"),
+    };
+    let mut sources: Vec<(&str, usize)> = vec![];
+    for jdk in &jdks {
+        match sources.iter_mut().find(|(source, _)| *source == jdk.source) {
+            Some((_, count)) => *count += 1,
+            None => sources.push((jdk.source, 1)),
+        }
+    }
+    let summary: Vec<String> = sources.iter().map(|(source, count)| format!("{} {}", source, count)).collect();
+    Diagnosis::ok(format!("{} JDK(s) found: {}", jdks.len(), summary.join(", ")))
+}
+
+pub fn build_doctor_command() -> Command {
+    Command::new("doctor")
+        .about("diagnose SDKMAN, JAVA_HOME, PATH, jenv and JBang setup, and print fixes.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fake_jdk(java_home: &Path) {
+        std::fs::create_dir_all(java_home.join("bin")).unwrap();
+        std::fs::write(java_home.join("bin").join(java_executable_name()), "").unwrap();
+    }
+
+    #[test]
+    fn test_check_java_and_path() {
+        let dir = std::env::temp_dir().join("wukong-sdkman-doctor");
+        let _ = std::fs::remove_dir_all(&dir);
+        let java_dir = dir.join("candidates").join("java");
+        let sdkman_java = java_dir.join("21.0.3-tem");
+        let system_java = dir.join("jvm").join("17");
+        let jenv_shims = dir.join("jenv").join("shims");
+        fake_jdk(&sdkman_java);
+        fake_jdk(&system_java);
+        std::fs::create_dir_all(&jenv_shims).unwrap();
+        std::fs::write(jenv_shims.join(java_executable_name()), "").unwrap();
+        assert_eq!(check_java_home(Some(&sdkman_java), &java_dir, Some(&sdkman_java)).level, Level::Ok);
+        assert_eq!(check_java_home(Some(&system_java), &java_dir, Some(&sdkman_java)).level, Level::Warning);
+        assert_eq!(check_java_home(Some(&dir), &java_dir, None).level, Level::Error);
+        assert_eq!(check_java_home(None, &java_dir, Some(&sdkman_java)).level, Level::Warning);
+        let path_entries = vec![dir.join("bin"), sdkman_java.join("bin"), system_java.join("bin")];
+        assert_eq!(check_path(&path_entries, Some(&sdkman_java), &jenv_shims)[0].level, Level::Ok);
+        let path_entries = vec![system_java.join("bin"), sdkman_java.join("bin")];
+        let diagnoses = check_path(&path_entries, Some(&sdkman_java), &jenv_shims);
+        assert_eq!(diagnoses[0].level, Level::Warning);
+        assert!(diagnoses[0].fix.as_ref().unwrap().starts_with(&format!("put {}", sdkman_java.join("bin").display())));
+        let path_entries = vec![jenv_shims.clone(), sdkman_java.join("bin")];
+        assert!(check_path(&path_entries, Some(&sdkman_java), &jenv_shims)[0].message.starts_with("jenv shims"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_links() {
+        let dir = std::env::temp_dir().join("wukong-sdkman-doctor-links");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("jenv").join("versions")).unwrap();
+        symlink::symlink_dir(dir.join("removed-jdk"), dir.join("jenv").join("versions").join("17")).unwrap();
+        let diagnoses = check_jenv(&dir.join("jenv"));
+        assert_eq!(diagnoses.len(), 1);
+        assert_eq!(diagnoses[0].fix.as_deref(), Some("jenv remove 17"));
+        symlink::symlink_dir(dir.join("removed-jdk"), dir.join("currentjdk")).unwrap();
+        assert_eq!(check_jbang_current_jdk(&dir.join("currentjdk"), None)[0].level, Level::Error);
+        assert!(check_jbang_current_jdk(&dir.join("missing"), None).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_init_snippet() {
+        assert!(has_init_snippet("export PATH=$PATH:~/bin\neval \"$(sdk init --shell zsh)\"\n"));
+        assert!(has_init_snippet("source \"$HOME/.sdkman/bin/sdkman-init.sh\""));
+        assert!(!has_init_snippet("# eval \"$(sdk init)\""));
+        assert_eq!(init_snippet(Shell::Bash), "eval \"$(sdk init --shell bash)\"");
+    }
+}
@@ -1,20 +1,67 @@
-use crate::sdkman_cli::get_installed_candidate_default_version;
-use crate::sdkman_cli::install::install_candidate;
+use crate::sdkman_cli::{find_candidate_home, find_installed_version, get_installed_candidate_default_version, sdkman_home};
+use crate::sdkman_cli::config::config_enabled;
+use crate::sdkman_cli::install::ensure_installed;
+use crate::sdkman_cli::shell::{EnvChanges, Shell};
+use crate::sdkman_cli::use_candidate::candidate_env;
+use crate::sdkman_cli::version_files::{has_version_files, parse_sdkmanrc_line, read_project_versions, ProjectVersion};
 use clap::Command;
 use std::fs::File;
-use std::io::{BufReader, Write};
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use wukong::error::report_error;
 
 pub fn manage_env(env_matches: &clap::ArgMatches) {
-    if let Some((command, _)) = env_matches.subcommand() {
-        match command {
-            "init" => env_init(),
-            "update" => env_update(),
-            "install" => env_install(),
-            "clear" => env_clear(),
-            &_ => println!("Unknown command"),
+    match env_matches.subcommand() {
+        Some(("init", _)) => env_init(),
+        Some(("update", _)) => env_update(),
+        Some(("install", _)) => env_install(),
+        Some(("clear", _)) => env_clear(),
+        Some(("auto", _)) => env_auto(),
+        Some(_) => println!("Unknown command"),
+        None => env_load(),
+    }
+}
+
+/// print exports for all versions of `.sdkmanrc` and other version files: `eval "$(sdk env)"`
+pub fn env_load() {
+    let current_dir = std::env::current_dir().unwrap();
+    if !has_version_files(&current_dir) {
+        eprintln!("Could not find .sdkmanrc in the current directory.");
+        eprintln!("Run `sdk env init` to create it.");
+        return;
+    }
+    let mut changes = EnvChanges::default();
+    load_env_changes(&mut changes, &current_dir);
+    changes.set("SDKMAN_ENV", &current_dir.to_string_lossy());
+    print_env_changes(&changes);
+}
+
+/// installed versions of project directory, missing versions are reported to stderr without network access
+pub fn load_env_changes(changes: &mut EnvChanges, project_dir: &Path) {
+    load_versions_env_changes(changes, read_project_versions(project_dir));
+}
+
+/// exports of installed versions, missing versions are reported to stderr
+pub fn load_versions_env_changes(changes: &mut EnvChanges, project_versions: Vec<ProjectVersion>) {
+    let mut missing = false;
+    for project_version in project_versions {
+        let candidate_name = &project_version.candidate;
+        if let Some(installed_version) = find_installed_version(candidate_name, &project_version.version, None) {
+            candidate_env(changes, candidate_name, &find_candidate_home(candidate_name, &installed_version));
+        } else {
+            eprintln!("Stop! {} {} from {} is not installed.", candidate_name, project_version.version, project_version.source.file_name());
+            missing = true;
         }
     }
+    if missing {
+        eprintln!("Run `sdk env install` to install missing versions.");
+    }
+}
+
+fn print_env_changes(changes: &EnvChanges) {
+    for statement in changes.render(Shell::current()) {
+        println!("{}", statement);
+    }
 }
 
 pub fn env_init() {
@@ -31,64 +78,128 @@ pub fn env_init() {
     }
 }
 
+/// update `.sdkmanrc` with versions in use of current shell, then default versions
 pub fn env_update() {
     let sdkmanrc_path = PathBuf::from(".sdkmanrc");
     if !sdkmanrc_path.exists() {
         env_init()
     } else {
-        let java_default_version = get_installed_candidate_default_version("java");
-        if !java_default_version.is_empty() {
-            let text = std::fs::read_to_string(sdkmanrc_path).unwrap();
-            let mut candidates: Vec<String> = vec![];
-            for line in text.lines() {
-                if line.starts_with("java=") {
-                    candidates.push(format!("java={}", java_default_version));
-                    println!("Updated Java to {}", java_default_version);
-                } else {
-                    candidates.push(line.to_string());
-                }
-            }
+        let text = std::fs::read_to_string(&sdkmanrc_path).unwrap();
+        let (text, updated_candidates) = update_sdkmanrc_text(&text, used_candidate_version);
+        if updated_candidates.is_empty() {
+            println!(".sdkmanrc is up to date.");
         } else {
-            eprintln!("No default Java version found!");
+            std::fs::write(&sdkmanrc_path, text).unwrap();
+            for (candidate_name, candidate_version) in updated_candidates {
+                println!("Updated {} to {}", candidate_name, candidate_version);
+            }
         }
     }
 }
 
-pub fn env_install() {
-    let sdkmanrc_path = PathBuf::from(".sdkmanrc");
-    if sdkmanrc_path.exists() {
-        let text = std::fs::read_to_string(sdkmanrc_path).unwrap();
-        for line in text.lines() {
-            if !line.is_empty() && !line.starts_with("#") {
-                let parts: Vec<&str> = line.trim().split("=").collect();
-                if parts.len() == 2 {
-                    install_candidate(parts[0].trim(), parts[1].trim());
-                }
+/// `.sdkmanrc` text with updated versions, comments and order are kept
+fn update_sdkmanrc_text<F>(text: &str, used_version: F) -> (String, Vec<(String, String)>)
+where
+    F: Fn(&str) -> String,
+{
+    let mut lines: Vec<String> = vec![];
+    let mut updated_candidates: Vec<(String, String)> = vec![];
+    for line in text.lines() {
+        if let Some((candidate_name, candidate_version)) = parse_sdkmanrc_line(line) {
+            let version = used_version(&candidate_name);
+            if !version.is_empty() && version != candidate_version {
+                lines.push(format!("{}={}", candidate_name, version));
+                updated_candidates.push((candidate_name, version));
+                continue;
             }
         }
-    } else {
+        lines.push(line.to_string());
+    }
+    let mut text = lines.join("\n");
+    text.push('\n');
+    (text, updated_candidates)
+}
+
+/// version in use from `<CANDIDATE>_HOME` of current shell, then the default version
+fn used_candidate_version(candidate_name: &str) -> String {
+    let candidate_dir = sdkman_home().join("candidates").join(candidate_name);
+    if let Ok(candidate_home) = std::env::var(format!("{}_HOME", candidate_name.to_uppercase()))
+        && let Ok(relative_path) = Path::new(&candidate_home).strip_prefix(&candidate_dir)
+        && let Some(version) = relative_path.iter().next()
+        && version != "current" {
+        return version.to_string_lossy().to_string();
+    }
+    get_installed_candidate_default_version(candidate_name)
+}
+
+/// install versions of `.sdkmanrc` and other version files
+pub fn env_install() {
+    let project_versions = read_project_versions(&std::env::current_dir().unwrap());
+    if project_versions.is_empty() {
         eprintln!(".sdkmanrc not exists!");
     }
+    for project_version in project_versions {
+        if let Err(e) = ensure_installed(&project_version.candidate, &project_version.version) {
+            report_error("sdk", &e);
+        }
+    }
 }
 
+/// print exports to restore default versions from `candidates/<name>/current`: `eval "$(sdk env clear)"`
 pub fn env_clear() {
-    let sdkmanrc_path = PathBuf::from(".sdkmanrc");
-    if sdkmanrc_path.exists() {
-        let f2 = File::open(&sdkmanrc_path).unwrap();
-        let map = java_properties::read(BufReader::new(f2)).unwrap();
-        let mut candidates: Vec<String> = vec![];
-        for (key, value) in map {
-            candidates.push(format!("{}={}", key, value));
+    let mut changes = EnvChanges::default();
+    if !clear_env_changes(&mut changes) {
+        eprintln!("Could not find .sdkmanrc in the current directory.");
+        return;
+    }
+    print_env_changes(&changes);
+}
+
+/// default versions for candidates of activated project, false if no project found
+fn clear_env_changes(changes: &mut EnvChanges) -> bool {
+    // `SDKMAN_ENV` is the directory of activated project, which may be left already
+    let project_dir = std::env::var("SDKMAN_ENV").ok()
+        .map(PathBuf::from)
+        .filter(|project_dir| has_version_files(project_dir))
+        .unwrap_or_else(|| std::env::current_dir().unwrap());
+    if !has_version_files(&project_dir) {
+        return false;
+    }
+    for ProjectVersion { candidate: candidate_name, .. } in read_project_versions(&project_dir) {
+        let candidate_current_link = sdkman_home().join("candidates").join(&candidate_name).join("current");
+        if candidate_current_link.exists() {
+            candidate_env(changes, &candidate_name, &candidate_current_link);
+            eprintln!("Restored {} {} (default)", candidate_name, get_installed_candidate_default_version(&candidate_name));
+        } else {
+            changes.unset(&format!("{}_HOME", candidate_name.to_uppercase()));
+            eprintln!("Cleared {}, no default version", candidate_name);
         }
-        if !candidates.is_empty() {
-            for candidate in &candidates {
-                println!("Restored {} (default)", candidate);
-            }
-            write_candidates(candidates);
+    }
+    changes.unset("SDKMAN_ENV");
+    true
+}
+
+/// `sdkman_auto_env=true` hook on directory change: clear versions of the left project,
+/// then activate `.sdkmanrc` or `.java-version` of current directory
+pub fn env_auto() {
+    if !config_enabled("sdkman_auto_env") {
+        return;
+    }
+    let current_dir = std::env::current_dir().unwrap();
+    let mut changes = EnvChanges::default();
+    if let Ok(sdkman_env) = std::env::var("SDKMAN_ENV") && !sdkman_env.is_empty() {
+        if current_dir == Path::new(&sdkman_env) {
+            return;
         }
-    } else {
-        eprintln!(".sdkmanrc not exists!");
+        if !current_dir.starts_with(&sdkman_env) {
+            clear_env_changes(&mut changes);
+        }
+    }
+    if has_version_files(&current_dir) {
+        load_env_changes(&mut changes, &current_dir);
+        changes.set("SDKMAN_ENV", &current_dir.to_string_lossy());
     }
+    print_env_changes(&changes);
 }
 
 fn write_candidates(candidates: Vec<String>) {
@@ -105,10 +216,12 @@ fn write_candidates(candidates: Vec<String>) {
 pub fn build_env_command() -> Command {
     Command::new("env")
         .about("control SDKs on a project level, setting up specific versions for a directory.")
-        .subcommand(Command::new("install").about("install and switch to the SDK versions specified in .sdkmanrc"))
+        .long_about(r#"Without subcommand, print exports for all versions in .sdkmanrc: eval "$(sdk env)""#)
+        .subcommand(Command::new("install").about("install the SDK versions specified in .sdkmanrc"))
         .subcommand(Command::new("init").about("allows for the creation of a default .sdkmanrc file with a single entry for the java candidate, set to the current default value)"))
-        .subcommand(Command::new("update").about("update .sdkmanrc file with java version from env"))
-        .subcommand(Command::new("clear").about("reset all SDK versions to their system defaults"))
+        .subcommand(Command::new("update").about("update .sdkmanrc file with versions in use, then default versions"))
+        .subcommand(Command::new("clear").about("reset all SDK versions to their system defaults: eval \"$(sdk env clear)\""))
+        .subcommand(Command::new("auto").about("clear and load .sdkmanrc or .java-version on directory change, used by shell hook with sdkman_auto_env=true"))
 }
 
 #[cfg(test)]
@@ -120,4 +233,18 @@ mod tests {
         let candidates = vec!["java=17.0.4-tem".to_owned()];
         write_candidates(candidates);
     }
+
+    #[test]
+    fn test_update_sdkmanrc_text() {
+        let text = "# Add key=value pairs of SDKs to use below\njava=17.0.4-tem\n\nmaven = 3.9.6\ngradle=8.5\n";
+        let (updated_text, updated_candidates) = update_sdkmanrc_text(text, |candidate_name| match candidate_name {
+            "java" => "21.0.3-tem".to_owned(),
+            "maven" => "3.9.6".to_owned(),
+            _ => "".to_owned(),
+        });
+        assert_eq!(updated_text, "# Add key=value pairs of SDKs to use below\njava=21.0.3-tem\n\nmaven = 3.9.6\ngradle=8.5\n");
+        assert_eq!(updated_candidates, vec![("java".to_owned(), "21.0.3-tem".to_owned())]);
+        assert_eq!(parse_sdkmanrc_line(" maven = 3.9.6 "), Some(("maven".to_owned(), "3.9.6".to_owned())));
+        assert_eq!(parse_sdkmanrc_line("# java=21"), None);
+    }
 }
@@ -0,0 +1,95 @@
+use crate::sdkman_cli::sdkman_home;
+use clap::{Arg, Command};
+use colored::Colorize;
+use indicatif::HumanBytes;
+use std::path::Path;
+use wukong::error::exit_with_error;
+
+pub fn manage_flush(flush_matches: &clap::ArgMatches) {
+    let target = flush_matches.get_one::<String>("target").map(|s| s.as_str()).unwrap_or("all");
+    let sdkman_home = sdkman_home();
+    let result = match target {
+        "version" => flush_version_file(&sdkman_home),
+        "tmp" => flush_dir(&sdkman_home.join("tmp"), "archive(s)"),
+        "metadata" => flush_dir(&sdkman_home.join("var").join("metadata"), "metadata file(s)"),
+        _ => flush_dir(&sdkman_home.join("tmp"), "archive(s)")
+            .and_then(|_| flush_dir(&sdkman_home.join("var").join("metadata"), "metadata file(s)")),
+    };
+    if let Err(e) = result {
+        exit_with_error("sdk", &e);
+    }
+}
+
+fn flush_version_file(sdkman_home: &Path) -> wukong::Result<()> {
+    let version_file = sdkman_home.join("var").join("version");
+    if version_file.exists() {
+        std::fs::remove_file(&version_file)?;
+        println!("{}", "Version file has been flushed.".green());
+    }
+    Ok(())
+}
+
+/// remove all entries in the directory, and print count of the kind, such as `archive(s)`, and freed size
+fn flush_dir(dir: &Path, kind: &str) -> wukong::Result<()> {
+    let (count, size) = clean_dir(dir)?;
+    println!("{}", format!("{} {} flushed, freeing {}.", count, kind, HumanBytes(size)).green());
+    Ok(())
+}
+
+/// remove all entries in the directory, returns count of entries and total size of files
+pub fn clean_dir(dir: &Path) -> wukong::Result<(usize, u64)> {
+    let mut count = 0;
+    let mut size = 0;
+    if dir.exists() {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            size += walkdir::WalkDir::new(&path)
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| entry.metadata().ok())
+                .filter(|metadata| metadata.is_file())
+                .map(|metadata| metadata.len())
+                .sum::<u64>();
+            if path.is_dir() && !path.is_symlink() {
+                std::fs::remove_dir_all(&path)?;
+            } else {
+                std::fs::remove_file(&path)?;
+            }
+            count += 1;
+        }
+    } else {
+        std::fs::create_dir_all(dir)?;
+    }
+    Ok((count, size))
+}
+
+pub fn build_flush_command() -> Command {
+    Command::new("flush")
+        .about("clean temporary downloads and cached metadata.")
+        .long_about(r#"Without a qualifier, both tmp and metadata are flushed. Use tmp to remove downloaded archives in $SDKMAN_DIR/tmp, metadata to remove cached candidate lists and versions in $SDKMAN_DIR/var/metadata, or version to remove the version file $SDKMAN_DIR/var/version."#)
+        .arg(
+            Arg::new("target")
+                .help("tmp, metadata or version")
+                .index(1)
+                .value_parser(["tmp", "metadata", "version"])
+                .required(false)
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_dir() {
+        let dir = std::env::temp_dir().join("wukong-sdkman-flush");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("java-21")).unwrap();
+        std::fs::write(dir.join("java-21").join("release"), "JAVA_VERSION=21").unwrap();
+        std::fs::write(dir.join("maven-3.9.9.zip.part"), "12345").unwrap();
+        assert_eq!(clean_dir(&dir).unwrap(), (2, 20));
+        assert!(dir.exists());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,276 @@
+//! Post-install layout for SDKMAN candidates.
+//!
+//! SDKMAN runs bash hooks from `/hooks/post/{candidate}/{version}/{platform}` after download to fix archive layouts.
+//! The hooks are not executed, and their intent is applied natively instead:
+//!
+//! - nested roots are flattened, such as `jdk-21.0.2+13/Contents/Home` on macOS
+//! - plain JAR candidates are wrapped with a launcher in `bin/`
+//! - scripts in `bin/` are made executable
+
+use crate::sdkman_cli::SDKMAN_CANDIDATES_API;
+use std::path::Path;
+use wukong::common::{extract_archive, http_text};
+
+/// nested home directory inside the archive root by candidate and platform prefix
+const NESTED_ROOTS: [(&str, &str, &str); 1] = [
+    ("java", "darwin", "Contents/Home"),
+];
+
+#[derive(Debug, Default, PartialEq)]
+pub struct LayoutRule {
+    /// home directory inside the archive root, such as `Contents/Home` for JDK bundles on macOS
+    pub nested_root: Option<String>,
+    /// download is a plain JAR, and `bin/<candidate>` launcher is generated
+    pub jar: bool,
+    /// executable files relative to home, besides files in `bin/`
+    pub executables: Vec<String>,
+}
+
+/// layout rule from built-in rules, extended by post-install hook
+pub fn layout_rule(candidate_name: &str, platform: &str, hook: Option<&str>) -> LayoutRule {
+    let mut rule = LayoutRule {
+        nested_root: NESTED_ROOTS.iter()
+            .find(|(name, platform_prefix, _)| *name == candidate_name && platform.starts_with(platform_prefix))
+            .map(|(_, _, nested_root)| nested_root.to_string()),
+        ..LayoutRule::default()
+    };
+    if let Some(hook) = hook {
+        if rule.nested_root.is_none() && hook.contains("Contents/Home") {
+            rule.nested_root = Some("Contents/Home".to_owned());
+        }
+        rule.jar = hook.contains("java -jar");
+        // `chmod +x "${candidate_dir}/gradlew"`, paths are relative to the candidate home
+        for line in hook.lines().map(|line| line.trim()) {
+            if let Some(path) = line.strip_prefix("chmod +x ") {
+                let path = path.trim().trim_matches('"');
+                if let Some((_, relative_path)) = path.split_once("}/") && !relative_path.contains('$') {
+                    rule.executables.push(relative_path.to_string());
+                }
+            }
+        }
+    }
+    rule
+}
+
+/// post-install hook script, and `None` if not available
+pub fn fetch_post_install_hook(candidate_name: &str, candidate_version: &str, platform: &str) -> Option<String> {
+    let hook_url = format!("{}/hooks/post/{}/{}/{}", SDKMAN_CANDIDATES_API, candidate_name, candidate_version, platform);
+    http_text(&hook_url).ok().filter(|hook| !hook.trim().is_empty())
+}
+
+/// install downloaded archive or plain JAR into home directory
+pub fn install_with_layout(archive_file_path: &Path, home_dir: &Path, candidate_name: &str, rule: &LayoutRule) -> wukong::Result<()> {
+    let file_name = archive_file_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    if rule.jar || file_name.to_lowercase().ends_with(".jar") {
+        install_jar(archive_file_path, home_dir, candidate_name)?;
+    } else {
+        extract_archive(archive_file_path, home_dir, false)?;
+        flatten_root(home_dir, rule.nested_root.as_deref(), candidate_name, archive_stem(&file_name))?;
+    }
+    make_executable(home_dir, &rule.executables)
+}
+
+/// flatten single root directories, such as `apache-maven-3.9.6/` or `gradle-8.5/gradle-8.5/`, and stop at nested
+/// root if found. A root directory is flattened only if its name matches the candidate or the archive, or it has
+/// `bin/` or the nested root, so a single content directory, such as `lib/`, is kept.
+fn flatten_root(home_dir: &Path, nested_root: Option<&str>, candidate_name: &str, archive_stem: &str) -> wukong::Result<()> {
+    loop {
+        if let Some(nested_root) = nested_root && home_dir.join(nested_root).is_dir() {
+            return hoist_dir(home_dir, nested_root);
+        }
+        let entries: Vec<_> = std::fs::read_dir(home_dir)?.flatten().collect();
+        if entries.len() != 1 || !entries[0].file_type()?.is_dir() || entries[0].file_name() == "bin" {
+            return Ok(());
+        }
+        let root_name = entries[0].file_name().to_string_lossy().to_string();
+        let is_root = root_name.to_lowercase().contains(&candidate_name.to_lowercase())
+            || archive_stem.contains(&root_name)
+            || entries[0].path().join("bin").is_dir()
+            || nested_root.is_some_and(|nested_root| entries[0].path().join(nested_root).is_dir());
+        if !is_root {
+            return Ok(());
+        }
+        hoist_dir(home_dir, &root_name)?;
+    }
+}
+
+/// archive file name without extension, such as `apache-maven-3.9.6-bin` for `apache-maven-3.9.6-bin.zip`
+fn archive_stem(file_name: &str) -> &str {
+    [".tar.gz", ".tar.xz", ".tar.bz2", ".tgz", ".zip"].iter()
+        .find_map(|extension| file_name.strip_suffix(extension))
+        .unwrap_or(file_name)
+}
+
+/// replace `dir` with its sub directory
+fn hoist_dir(dir: &Path, sub_path: &str) -> wukong::Result<()> {
+    let dir_name = dir.file_name().unwrap_or_default().to_string_lossy();
+    let temp_dir = dir.with_file_name(format!(".{}.hoist-{}", dir_name, std::process::id()));
+    std::fs::rename(dir.join(sub_path), &temp_dir)?;
+    std::fs::remove_dir_all(dir)?;
+    std::fs::rename(&temp_dir, dir)?;
+    Ok(())
+}
+
+/// copy JAR into `lib/`, and generate `bin/<candidate>` and `bin/<candidate>.cmd` launchers
+fn install_jar(jar_file_path: &Path, home_dir: &Path, candidate_name: &str) -> wukong::Result<()> {
+    let jar_file_name = jar_file_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let lib_dir = home_dir.join("lib");
+    let bin_dir = home_dir.join("bin");
+    std::fs::create_dir_all(&lib_dir)?;
+    std::fs::create_dir_all(&bin_dir)?;
+    std::fs::copy(jar_file_path, lib_dir.join(&jar_file_name))?;
+    let launcher = format!(r#"#!/bin/sh
+APP_HOME="$(cd "$(dirname "$0")/.." && pwd)"
+if [ -n "$JAVA_HOME" ]; then JAVA="$JAVA_HOME/bin/java"; else JAVA=java; fi
+exec "$JAVA" -jar "$APP_HOME/lib/{}" "$@"
+"#, jar_file_name);
+    std::fs::write(bin_dir.join(candidate_name), launcher)?;
+    let cmd_launcher = format!("@echo off\r\nset APP_HOME=%~dp0..\r\nif defined JAVA_HOME (set JAVA=\"%JAVA_HOME%\\bin\\java\") else (set JAVA=java)\r\n%JAVA% -jar \"%APP_HOME%\\lib\\{}\" %*\r\n", jar_file_name);
+    std::fs::write(bin_dir.join(format!("{}.cmd", candidate_name)), cmd_launcher)?;
+    Ok(())
+}
+
+/// files in `bin/` and extra executables are made executable, symlinks are skipped
+#[cfg(unix)]
+fn make_executable(home_dir: &Path, executables: &[String]) -> wukong::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut files: Vec<_> = executables.iter().map(|path| home_dir.join(path)).collect();
+    if let Ok(entries) = std::fs::read_dir(home_dir.join("bin")) {
+        files.extend(entries.flatten().map(|entry| entry.path()));
+    }
+    for file in files {
+        if let Ok(metadata) = std::fs::symlink_metadata(&file) && metadata.is_file() {
+            let mode = metadata.permissions().mode();
+            std::fs::set_permissions(&file, std::fs::Permissions::from_mode(mode | 0o755))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_home_dir: &Path, _executables: &[String]) -> wukong::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    fn fixture_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("wukong-sdkman-hooks-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// fixture archive with entries, `.zip` or `.tar.gz` by file name
+    fn build_archive(archive_file_path: &Path, entries: &[&str]) {
+        if archive_file_path.to_string_lossy().ends_with(".zip") {
+            let mut writer = zip::ZipWriter::new(File::create(archive_file_path).unwrap());
+            for entry in entries {
+                writer.start_file(*entry, zip::write::SimpleFileOptions::default().unix_permissions(0o644)).unwrap();
+                writer.write_all(entry.as_bytes()).unwrap();
+            }
+            writer.finish().unwrap();
+        } else {
+            let encoder = flate2::write::GzEncoder::new(File::create(archive_file_path).unwrap(), flate2::Compression::fast());
+            let mut builder = tar::Builder::new(encoder);
+            for entry in entries {
+                let mut header = tar::Header::new_gnu();
+                header.set_mode(0o644);
+                header.set_size(entry.len() as u64);
+                builder.append_data(&mut header, entry, entry.as_bytes()).unwrap();
+            }
+            builder.into_inner().unwrap().finish().unwrap();
+        }
+    }
+
+    /// candidate, platform, archive, entries, expected files, absent files
+    type LayoutCase<'a> = (&'a str, &'a str, &'a str, &'a [&'a str], &'a [&'a str], &'a [&'a str]);
+
+    #[test]
+    fn test_layout_rules() {
+        let cases: [LayoutCase; 8] = [
+            ("maven", "linuxx64", "apache-maven-3.9.6-bin.zip",
+             &["apache-maven-3.9.6/bin/mvn", "apache-maven-3.9.6/lib/maven-core.jar"],
+             &["bin/mvn", "lib/maven-core.jar"], &["apache-maven-3.9.6"]),
+            ("java", "darwinarm64", "jdk-21.0.2.tar.gz",
+             &["jdk-21.0.2+13/Contents/Home/bin/java", "jdk-21.0.2+13/Contents/Info.plist"],
+             &["bin/java"], &["Contents", "Info.plist"]),
+            ("java", "darwinx64", "zulu21.zip",
+             &["zulu21.jdk/Contents/Home/bin/java", "zulu21.jdk/Contents/MacOS/libjli.dylib"],
+             &["bin/java"], &["Contents"]),
+            ("java", "linuxx64", "jdk-21.0.2.tar.gz",
+             &["jdk-21.0.2+13/bin/java", "jdk-21.0.2+13/release"],
+             &["bin/java", "release"], &["jdk-21.0.2+13"]),
+            ("kotlin", "linuxx64", "kotlin.zip",
+             &["bin/kotlin"],
+             &["bin/kotlin"], &[]),
+            ("gradle", "linuxx64", "gradle-8.5-bin.zip",
+             &["gradle-8.5/gradle-8.5/bin/gradle", "gradle-8.5/gradle-8.5/lib/gradle.jar"],
+             &["bin/gradle", "lib/gradle.jar"], &["gradle-8.5"]),
+            // root without `bin/` is flattened by the name of the candidate and the archive
+            ("visualvm", "linuxx64", "visualvm_218.zip",
+             &["visualvm_218/platform/lib/nbexec", "visualvm_218/etc/visualvm.conf"],
+             &["platform/lib/nbexec", "etc/visualvm.conf"], &["visualvm_218"]),
+            // single content directory is kept
+            ("groovy", "linuxx64", "groovy-4.0.18.zip",
+             &["lib/groovy-4.0.18.jar"],
+             &["lib/groovy-4.0.18.jar"], &["groovy-4.0.18.jar"]),
+        ];
+        for (i, (candidate_name, platform, archive_name, entries, expected, absent)) in cases.iter().enumerate() {
+            let dir = fixture_dir(&format!("{}-{}", candidate_name, i));
+            let archive_file_path = dir.join(archive_name);
+            build_archive(&archive_file_path, entries);
+            let home_dir = dir.join("home");
+            let rule = layout_rule(candidate_name, platform, None);
+            install_with_layout(&archive_file_path, &home_dir, candidate_name, &rule).unwrap();
+            for path in *expected {
+                assert!(home_dir.join(path).is_file(), "{} {}: {} expected", candidate_name, platform, path);
+            }
+            for path in *absent {
+                assert!(!home_dir.join(path).exists(), "{} {}: {} unexpected", candidate_name, platform, path);
+            }
+            #[cfg(unix)]
+            for path in expected.iter().filter(|path| path.starts_with("bin/")) {
+                use std::os::unix::fs::PermissionsExt;
+                let mode = std::fs::metadata(home_dir.join(path)).unwrap().permissions().mode();
+                assert_eq!(mode & 0o755, 0o755, "{} should be executable", path);
+            }
+        }
+    }
+
+    #[test]
+    fn test_jar_launcher() {
+        let dir = fixture_dir("jar");
+        let jar_file_path = dir.join("ki-0.5.2.jar");
+        std::fs::write(&jar_file_path, b"PK").unwrap();
+        let home_dir = dir.join("home");
+        install_with_layout(&jar_file_path, &home_dir, "ki", &layout_rule("ki", "linuxx64", None)).unwrap();
+        assert!(home_dir.join("lib").join("ki-0.5.2.jar").is_file());
+        let launcher = std::fs::read_to_string(home_dir.join("bin").join("ki")).unwrap();
+        assert!(launcher.contains("-jar \"$APP_HOME/lib/ki-0.5.2.jar\""));
+        assert!(home_dir.join("bin").join("ki.cmd").is_file());
+    }
+
+    #[test]
+    fn test_rule_from_hook() {
+        let hook = r#"#!/usr/bin/env bash
+function __sdkman_post_installation_hook {
+    __sdkman_echo_debug "A post-install hook was found for JMC."
+    mkdir -p "${SDKMAN_CANDIDATES_DIR}/jmc/${VERSION}/Contents/Home"
+    chmod +x "${candidate_dir}/jmc"
+    chmod +x "${candidate_dir}/$name"
+}
+"#;
+        let rule = layout_rule("jmc", "darwinx64", Some(hook));
+        assert_eq!(rule.nested_root.as_deref(), Some("Contents/Home"));
+        assert_eq!(rule.executables, vec!["jmc".to_owned()]);
+        assert!(!rule.jar);
+        assert!(layout_rule("ki", "linuxx64", Some("exec java -jar \"$ki_jar\" \"$@\"")).jar);
+        assert_eq!(layout_rule("maven", "darwinx64", None), LayoutRule::default());
+    }
+}
@@ -1,25 +1,60 @@
+use crate::sdkman_cli::shell::{auto_env_hook, EnvChanges, Shell};
+use crate::sdkman_cli::use_candidate::candidate_env;
+use crate::sdkman_cli::config::config_enabled;
 use crate::sdkman_cli::{list_candidate_names, sdkman_home};
+use clap::{Arg, Command};
 
-pub fn shell_hook() {
+pub fn manage_init(init_matches: &clap::ArgMatches) {
+    let shell = match init_matches.get_one::<String>("shell") {
+        Some(name) => Shell::from_name(name).unwrap(),
+        None => Shell::current(),
+    };
+    let auto_env = config_enabled("sdkman_auto_env");
+    for line in shell_hook(shell, auto_env) {
+        println!("{}", line);
+    }
+}
+
+/// default candidate homes and paths, with `sdkman_auto_env` hook if enabled
+pub fn shell_hook(shell: Shell, auto_env: bool) -> Vec<String> {
     let candidates_dir_home = sdkman_home().join("candidates");
-    let mut paths: Vec<String> = vec![];
-    let candidate_names = list_candidate_names();
-    for candidate_name in &candidate_names {
-        let candidate_home = candidates_dir_home.join(candidate_name);
-        let candidate_current_link = candidate_home.join("current");
+    let mut changes = EnvChanges::default();
+    changes.set("SDKMAN_SHELL", shell.name());
+    for candidate_name in &list_candidate_names() {
+        let candidate_current_link = candidates_dir_home.join(candidate_name).join("current");
         if candidate_current_link.exists() && candidate_current_link.is_symlink() {
-            println!("export {}_HOME={}", candidate_name.to_uppercase(), candidate_current_link.to_str().unwrap());
-            let candidate_bin_path = candidate_current_link.join("bin");
-            if candidate_bin_path.exists() {
-                paths.push(candidate_bin_path.to_str().unwrap().to_string());
-            } else {
-                paths.push(candidate_home.to_str().unwrap().to_string());
-            }
+            candidate_env(&mut changes, candidate_name, &candidate_current_link);
         }
     }
-    if !paths.is_empty() {
-        println!("export PATH={}:$PATH", paths.join(":"));
+    // nushell init output is saved as script, and PATH is computed when the script runs
+    let mut lines = if shell == Shell::Nushell {
+        changes.render_nushell_script()
+    } else {
+        changes.render(shell)
+    };
+    if auto_env {
+        lines.push(auto_env_hook(shell).to_owned());
     }
+    lines
+}
+
+pub fn build_init_command() -> Command {
+    Command::new("init")
+        .about("initialize SDKMAN! in the current shell: eval \"$(sdk init)\"")
+        .long_about(r#"Print shell code to set up default candidates, and the auto-env hook if sdkman_auto_env=true in ~/.sdkman/etc/config.
+
+bash/zsh: eval "$(sdk init --shell bash)"
+fish: sdk init --shell fish | source
+nushell: sdk init --shell nushell | save -f ~/.sdkman/init.nu, then source ~/.sdkman/init.nu in config.nu
+PowerShell: sdk init --shell powershell | Out-String | Invoke-Expression"#)
+        .arg(
+            Arg::new("shell")
+                .long("shell")
+                .help("shell name: bash, zsh, fish, nushell or powershell, and detected from $SHELL by default")
+                .value_parser(["bash", "zsh", "fish", "nushell", "nu", "powershell", "pwsh"])
+                .num_args(1)
+                .required(false)
+        )
 }
 
 #[cfg(test)]
@@ -28,6 +63,16 @@ mod tests {
 
     #[test]
     fn test_init() {
-        shell_hook();
+        for line in shell_hook(Shell::Bash, false) {
+            println!("{}", line);
+        }
+    }
+
+    #[test]
+    fn test_init_auto_env() {
+        let lines = shell_hook(Shell::Fish, true);
+        assert_eq!(lines[0], "set -gx SDKMAN_SHELL 'fish'");
+        assert!(lines.last().unwrap().contains("--on-variable PWD"));
+        assert!(shell_hook(Shell::Nushell, false)[0].starts_with("load-env {SDKMAN_SHELL: \"nushell\""));
     }
 }
@@ -1,19 +1,22 @@
+use crate::sdkman_cli::config::config_enabled;
 use crate::sdkman_cli::default::make_candidate_default;
+use crate::sdkman_cli::hooks::{fetch_post_install_hook, install_with_layout, layout_rule};
+use crate::sdkman_cli::metadata::is_offline;
 use crate::sdkman_cli::{
-    find_candidate_home, find_java_version, get_remote_candidate_default_version,
-    get_sdkman_platform, read_sdkman_config, sdkman_home, SDKMAN_CANDIDATES_API,
+    find_candidate_home, find_installed_version, find_remote_version, get_remote_candidate_default_version,
+    get_sdkman_platform, sdkman_home, SDKMAN_CANDIDATES_API,
 };
 use colored::Colorize;
 use fs_extra::dir::CopyOptions;
 use std::path::PathBuf;
-use wukong::common::{
-    extract_tgz, extract_tgz_from_sub_path, extract_zip, get_redirect_url, http_download,
-};
+use wukong::common::{download_and_install, get_redirect, verify_sha256};
+use wukong::error::exit_with_error;
+use wukong::Error;
 
 pub fn manage_install(install_matches: &clap::ArgMatches) {
     let mut accept_as_default = install_matches.get_flag("yes");
     if !accept_as_default {
-        accept_as_default = read_sdkman_config().contains_key("sdkman_auto_answer");
+        accept_as_default = config_enabled("sdkman_auto_answer");
     }
     if let Some(candidate_name) = install_matches.get_one::<String>("candidate") {
         let installed_version =
@@ -27,25 +30,38 @@ pub fn manage_install(install_matches: &clap::ArgMatches) {
                         candidate_name,
                         candidate_version
                     );
-                    install_candidate_from_path(&candidate_name, &candidate_version, &source_path);
+                    if let Err(e) = install_candidate_from_path(candidate_name, candidate_version, &source_path) {
+                        exit_with_error("sdk", &e);
+                    }
                 } else {
                     eprintln!("{}", "Invalid path! Refusing to link".red());
                     return;
                 }
                 candidate_version.clone()
             } else {
+                let version = install_matches.get_one::<String>("version");
                 let candidate_version =
-                    if let Some(version) = install_matches.get_one::<String>("version") {
-                        version.clone()
+                    if let Some(vendor) = install_matches.get_one::<String>("vendor") {
+                        let query = version.map(|version| version.as_str()).unwrap_or("latest");
+                        match find_remote_version(candidate_name, query, Some(vendor)) {
+                            Ok(Some(version)) => version,
+                            Ok(None) => exit_with_error("sdk", &Error::NotFound(format!("{} {} from vendor {}", candidate_name, query, vendor))),
+                            Err(e) => exit_with_error("sdk", &e),
+                        }
+                    } else if let Some(version) = version {
+                        resolve_version(candidate_name, version).unwrap_or_else(|e| exit_with_error("sdk", &e))
                     } else {
                         get_remote_candidate_default_version(candidate_name)
+                            .unwrap_or_else(|e| exit_with_error("sdk", &e))
                     };
-                if candidate_version == "" {
+                if candidate_version.is_empty() {
                     eprintln!("Failed to find default version for : {}", candidate_name);
                     return;
                 }
                 println!("Installing: {} {}", candidate_name, candidate_version);
-                install_candidate(candidate_name, &candidate_version);
+                if let Err(e) = install_resolved_version(candidate_name, &candidate_version) {
+                    exit_with_error("sdk", &e);
+                }
                 candidate_version
             };
         println!("Done installing!");
@@ -69,28 +85,47 @@ pub fn install_candidate_from_path(
     candidate_name: &str,
     candidate_version: &str,
     install_path: &PathBuf,
-) {
+) -> wukong::Result<()> {
     let candidate_home = find_candidate_home(candidate_name, candidate_version);
     if candidate_home.exists() {
         println!(
             "{}@{} installed already: {}",
             candidate_name,
             candidate_version,
-            candidate_home.to_str().unwrap()
+            candidate_home.display()
         );
-        return;
+        return Ok(());
     }
-    symlink::symlink_dir(install_path, candidate_home).unwrap();
+    symlink::symlink_dir(install_path, candidate_home)?;
+    Ok(())
 }
 
-pub fn install_candidate(candidate_name: &str, candidate_version: &str) {
-    let candidate_version = if candidate_name == "java" && candidate_version.parse::<u32>().is_ok()
-    {
-        find_java_version(candidate_version).unwrap_or(candidate_version.to_string())
-    } else {
-        candidate_version.to_string()
-    };
-    let candidate_version = candidate_version.as_str();
+/// installed version matched by version query, otherwise install the matched version from SDKMAN
+pub fn ensure_installed(candidate_name: &str, candidate_version: &str) -> wukong::Result<String> {
+    if let Some(installed_version) = find_installed_version(candidate_name, candidate_version, None) {
+        return Ok(installed_version);
+    }
+    let candidate_version = resolve_version(candidate_name, candidate_version)?;
+    install_resolved_version(candidate_name, &candidate_version)?;
+    Ok(candidate_version)
+}
+
+/// identifier for version query, such as `21` or `3.9`, and the query is used as is if no remote version matches it
+fn resolve_version(candidate_name: &str, candidate_version: &str) -> wukong::Result<String> {
+    if find_candidate_home(candidate_name, candidate_version).exists() {
+        return Ok(candidate_version.to_string());
+    }
+    Ok(find_remote_version(candidate_name, candidate_version, None)?.unwrap_or_else(|| candidate_version.to_string()))
+}
+
+/// install version query, such as `21` or `3.9`, or an exact version identifier
+pub fn install_candidate(candidate_name: &str, candidate_version: &str) -> wukong::Result<()> {
+    let candidate_version = resolve_version(candidate_name, candidate_version)?;
+    install_resolved_version(candidate_name, &candidate_version)
+}
+
+/// install version identifier resolved by `resolve_version`
+fn install_resolved_version(candidate_name: &str, candidate_version: &str) -> wukong::Result<()> {
     let candidate_home = find_candidate_home(candidate_name, candidate_version);
     if candidate_home.exists() {
         println!(
@@ -99,33 +134,42 @@ pub fn install_candidate(candidate_name: &str, candidate_version: &str) {
             candidate_version,
             candidate_home.to_str().unwrap()
         );
-        return;
+        return Ok(());
+    }
+    if is_offline() {
+        return Err(Error::Network(format!("{} {} is not available while offline", candidate_name, candidate_version)));
     }
     let sdkman_platform = get_sdkman_platform();
     let download_url = format!(
         "{}/broker/download/{}/{}/{}",
         SDKMAN_CANDIDATES_API, candidate_name, candidate_version, sdkman_platform
     );
-    let real_download_url = get_redirect_url(&download_url).unwrap();
-    let archive_file_name = &real_download_url[real_download_url.rfind("/").unwrap() + 1..];
+    let (real_download_url, headers) = get_redirect(&download_url).map_err(|e| match e {
+        Error::Http(400..500, _) => Error::NotFound(format!("{} {} for {}", candidate_name, candidate_version, sdkman_platform)),
+        e => e,
+    })?;
+    let archive_file_name = archive_file_name_of(&real_download_url);
     let temp_dir = sdkman_home().join("tmp");
-    let archive_file_path = temp_dir.join(archive_file_name);
-    if archive_file_path.exists() {
-        // remove broken downloaded file
-        std::fs::remove_file(&archive_file_path).unwrap();
-    }
-    http_download(&real_download_url, archive_file_path.to_str().unwrap());
-    if archive_file_name.ends_with("tar.gz") {
-        if candidate_name == "java" && sdkman_platform.starts_with("darwin") {
-            // JDK on Mac
-            extract_tgz_from_sub_path(&archive_file_path, &candidate_home, "Contents/Home/");
-        } else {
-            extract_tgz(&archive_file_path, &candidate_home, true);
+    // interrupted download will be resumed from `<archive>.part`, and the archive is only written
+    // while holding the install lock of the candidate version
+    let archive_file_path = temp_dir.join(format!("{}-{}-{}", candidate_name, candidate_version, archive_file_name));
+    let checksum = headers.get("X-Sdkman-Checksum-SHA-256")
+        .and_then(|value| value.to_str().ok())
+        .filter(|_| config_enabled("sdkman_checksum_enable"));
+    let hook = fetch_post_install_hook(candidate_name, candidate_version, &sdkman_platform);
+    let rule = layout_rule(candidate_name, &sdkman_platform, hook.as_deref());
+    download_and_install(&real_download_url, &archive_file_path, &candidate_home, |archive_file_path, staging_dir| {
+        if let Some(checksum) = checksum {
+            verify_sha256(archive_file_path, checksum)?;
         }
-    } else {
-        extract_zip(&archive_file_path, &candidate_home, true);
-    }
-    std::fs::remove_file(&archive_file_path).unwrap();
+        install_with_layout(archive_file_path, staging_dir, candidate_name, &rule)
+    })
+}
+
+/// archive file name from the last path segment of download URL, without query string or fragment
+fn archive_file_name_of(download_url: &str) -> &str {
+    let path = download_url.split(['?', '#']).next().unwrap_or_default();
+    path.rsplit('/').next().unwrap_or_default()
 }
 
 #[cfg(test)]
@@ -133,12 +177,19 @@ mod tests {
     use super::*;
     use crate::sdkman_cli::get_remote_candidate_default_version;
 
+    #[test]
+    fn test_archive_file_name_of() {
+        assert_eq!(archive_file_name_of("https://example.com/dist/apache-ant-1.10.14-bin.zip"), "apache-ant-1.10.14-bin.zip");
+        assert_eq!(archive_file_name_of("https://bucket.s3.amazonaws.com/gradle-8.5-bin.zip?X-Amz-Signature=abc/def"), "gradle-8.5-bin.zip");
+        assert_eq!(archive_file_name_of("https://example.com/download/groovy#latest"), "groovy");
+    }
+
     #[test]
     fn test_candidate_default_version() {
         let candidate_name = "java";
         println!(
             "java: {}",
-            get_remote_candidate_default_version(candidate_name)
+            get_remote_candidate_default_version(candidate_name).unwrap()
         );
     }
 
@@ -146,14 +197,14 @@ mod tests {
     fn test_install_candidate() {
         let candidate_name = "ant";
         let version = "1.10.14";
-        install_candidate(candidate_name, version);
+        install_candidate(candidate_name, version).unwrap();
     }
 
     #[test]
     fn test_install_java_candidate() {
         let candidate_name = "java";
         let version = "21";
-        install_candidate(candidate_name, version);
+        install_candidate(candidate_name, version).unwrap();
     }
 
     #[test]
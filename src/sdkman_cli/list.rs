@@ -1,94 +1,441 @@
 use crate::sdkman_cli::{
-    get_installed_candidate_default_version, get_sdkman_platform, list_candidate_names,
-    sdkman_home, SDKMAN_CANDIDATES_API,
+    fetch_candidate_versions, get_installed_candidate_default_version, list_candidate_names,
+    list_installed_versions, sdkman_home, SDKMAN_CANDIDATES_API,
 };
+use crate::sdkman_cli::metadata::{MetadataCache, CANDIDATES_TTL};
+use crate::sdkman_cli::models::{parse_candidates, CandidateVersion, VersionStatus};
+use crate::sdkman_cli::install::install_candidate;
 use colored::Colorize;
+use indicatif::HumanBytes;
+use prettytable::{format, row, Table};
+use serde::Serialize;
+use std::path::Path;
+use wukong::common::compare_versions;
+use wukong::error::{exit_with_error, report_error};
+
+/// options of `sdk list <candidate>`
+#[derive(Debug, Default)]
+pub struct ListOptions {
+    pub json: bool,
+    /// vendor name or dist id, such as `Temurin` or `tem`, Java only
+    pub vendor: Option<String>,
+    pub installed: bool,
+}
 
 pub fn manage_list(list_matches: &clap::ArgMatches) {
-    if let Some(candidate_name) = list_matches.get_one::<String>("candidate") {
-        list_candidate(candidate_name);
+    let options = ListOptions {
+        json: list_matches.get_one::<String>("format").is_some_and(|format| format == "json"),
+        vendor: list_matches.get_one::<String>("vendor").cloned(),
+        installed: list_matches.get_flag("installed"),
+    };
+    let result = if let Some(candidate_name) = list_matches.get_one::<String>("candidate") {
+        list_candidate_with_options(candidate_name, &options)
     } else if list_matches.get_flag("local") {
-        list_installed_candidates();
+        list_local_versions(options.json, list_matches.get_flag("repair"));
+        Ok(())
     } else {
-        list_all_candidates();
+        list_all_candidates(options.json)
+    };
+    if let Err(e) = result {
+        exit_with_error("sdk", &e);
     }
 }
 
-pub fn list_all_candidates() {
+pub fn list_all_candidates(json: bool) -> wukong::Result<()> {
     let list_url = format!("{}/candidates/list", SDKMAN_CANDIDATES_API);
-    println!("{}", wukong::common::http_text(&list_url));
+    let text = MetadataCache::load().get_text("candidates", &list_url, CANDIDATES_TTL)?;
+    let candidates = parse_candidates(&text);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&candidates).unwrap());
+        return Ok(());
+    }
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row!["Candidate", "Name", "Default", "Homepage"]);
+    for candidate in &candidates {
+        table.add_row(row![candidate.candidate, candidate.name, candidate.default_version, candidate.homepage]);
+    }
+    table.printstd();
+    Ok(())
 }
 
-pub fn list_candidate(candidate_name: &str) {
+pub fn list_candidate_with_options(candidate_name: &str, options: &ListOptions) -> wukong::Result<()> {
     let mut candidate_name = candidate_name;
     if candidate_name == "jdk" {
         candidate_name = "java";
     }
+    let remote_versions = fetch_candidate_versions(candidate_name)?;
     let current_version = get_installed_candidate_default_version(candidate_name);
-    let mut installed_versions: Vec<String> = vec![];
-    let candidate_repo = sdkman_home().join("candidates").join(candidate_name);
-    // read sub directories for candidate_repo
-    if candidate_repo.exists() {
-        let entries = std::fs::read_dir(candidate_repo).unwrap();
-        for entry in entries {
-            let entry = entry.unwrap();
-            if entry.path().is_dir() {
-                let version = entry.file_name().into_string().unwrap();
-                if version != "current" {
-                    installed_versions.push(version);
-                }
-            }
+    let installed_versions = list_installed_versions(candidate_name);
+    let versions = merge_local_versions(candidate_name, remote_versions, &installed_versions, &current_version);
+    let versions = filter_versions(versions, options);
+    if options.json {
+        println!("{}", serde_json::to_string_pretty(&versions).unwrap());
+    } else if candidate_name == "java" {
+        print_java_versions(&versions);
+    } else {
+        print_candidate_versions(&versions);
+    }
+    Ok(())
+}
+
+/// mark installed and current versions, and add versions installed from local path
+pub fn merge_local_versions(candidate_name: &str, remote_versions: Vec<CandidateVersion>,
+                            installed_versions: &[String], current_version: &str) -> Vec<CandidateVersion> {
+    let mut versions: Vec<CandidateVersion> = remote_versions.into_iter()
+        .map(|mut version| {
+            version.status = if installed_versions.contains(&version.identifier) {
+                VersionStatus::Installed
+            } else {
+                VersionStatus::Available
+            };
+            version.current = version.identifier == current_version;
+            version
+        })
+        .collect();
+    for installed_version in installed_versions {
+        if !versions.iter().any(|version| &version.identifier == installed_version) {
+            let mut version = CandidateVersion::from_identifier(candidate_name == "java", installed_version);
+            version.current = installed_version == current_version;
+            versions.push(version);
+        }
+    }
+    versions
+}
+
+pub fn filter_versions(versions: Vec<CandidateVersion>, options: &ListOptions) -> Vec<CandidateVersion> {
+    versions.into_iter()
+        .filter(|version| !options.installed || version.is_installed())
+        .filter(|version| options.vendor.as_deref().is_none_or(|vendor| version.matches_vendor(vendor)))
+        .collect()
+}
+
+fn print_java_versions(versions: &[CandidateVersion]) {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row!["Vendor", "Use", "Version", "Dist", "Status", "Identifier", "LTS"]);
+    let mut last_vendor = None;
+    for version in versions {
+        // vendor is only displayed in the first row of each vendor
+        let vendor = if version.vendor != last_vendor { version.vendor.clone().unwrap_or_default() } else { "".to_owned() };
+        last_vendor = version.vendor.clone();
+        table.add_row(row![
+            vendor,
+            if version.current { ">>>" } else { "" },
+            version.version,
+            version.dist.as_deref().unwrap_or_default(),
+            version.status.label(),
+            version.identifier,
+            if version.lts { "LTS" } else { "" }
+        ]);
+    }
+    table.printstd();
+}
+
+fn print_candidate_versions(versions: &[CandidateVersion]) {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    for chunk in versions.chunks(4) {
+        let cells = chunk.iter().map(|version| {
+            let local = if version.status == VersionStatus::LocalOnly { "+" } else { " " };
+            let installed = if version.status == VersionStatus::Installed { "*" } else { " " };
+            let current = if version.current { ">" } else { " " };
+            prettytable::Cell::new(&format!("{} {}{} {}", current, installed, local, version.version))
+        }).collect();
+        table.add_row(prettytable::Row::new(cells));
+    }
+    table.printstd();
+    println!("+ - local version, * - installed, > - currently in use");
+}
+
+/// install source of a local version
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallSource {
+    /// downloaded from SDKMAN
+    Downloaded,
+    /// symlink to a local installation, `sdk install <candidate> <version> <path>`
+    Linked,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum InstallProblem {
+    MissingBin,
+    /// linked local installation was removed
+    DanglingLink,
+    /// `current` link points to a removed version
+    DanglingCurrent,
+}
+
+impl InstallProblem {
+    pub fn label(&self) -> &'static str {
+        match self {
+            InstallProblem::MissingBin => "broken: bin/ missing",
+            InstallProblem::DanglingLink => "broken: link target missing",
+            InstallProblem::DanglingCurrent => "broken: current link dangles",
         }
     }
-    let list_url = format!(
-        "{}/candidates/{}/{}/versions/list?current={}&installed={}",
-        SDKMAN_CANDIDATES_API,
-        candidate_name,
-        get_sdkman_platform(),
-        current_version,
-        installed_versions.join(",")
-    );
-    println!("{}", wukong::common::http_text(&list_url));
 }
 
-pub fn list_installed_candidates() {
+/// installed version with disk usage and status, for `sdk list --local`
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalVersion {
+    pub candidate: String,
+    pub version: String,
+    pub size: u64,
+    pub source: InstallSource,
+    /// install date, `YYYY-MM-DD`
+    pub installed: Option<String>,
+    pub current: bool,
+    pub problem: Option<InstallProblem>,
+}
+
+/// installed versions of all candidates, broken ones are repaired with `repair`
+pub fn list_local_versions(json: bool, repair: bool) {
     let candidates_dir = sdkman_home().join("candidates");
-    let candidate_names = list_candidate_names();
-    if candidate_names.is_empty() {
+    let mut candidate_names = list_candidate_names();
+    candidate_names.sort();
+    let local_versions: Vec<LocalVersion> = candidate_names.iter()
+        .flat_map(|candidate_name| scan_local_versions(&candidates_dir.join(candidate_name), candidate_name))
+        .collect();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&local_versions).unwrap());
+    } else if local_versions.is_empty() {
         eprintln!("No candidate installed yet.");
         return;
+    } else {
+        print_local_versions(&local_versions);
     }
-    println!("Installed candidates:");
-    for candidate_name in &candidate_names {
-        let candidate_repo = candidates_dir.join(candidate_name);
-        let current_version = get_installed_candidate_default_version(candidate_name);
-        // list sub directories from candidate_repo
-        let entries = std::fs::read_dir(candidate_repo).unwrap();
-        let mut installed_versions: Vec<String> = vec![];
-        for entry in entries {
-            let entry = entry.unwrap();
-            if entry.path().is_dir() {
-                let version = entry.file_name().into_string().unwrap();
-                if version != "current" {
-                    if version == current_version {
-                        installed_versions.insert(0, version.bold().to_string());
-                    } else {
-                        installed_versions.push(version);
-                    }
-                }
+    let broken: Vec<&LocalVersion> = local_versions.iter().filter(|local_version| local_version.problem.is_some()).collect();
+    if repair {
+        for local_version in broken {
+            if let Err(e) = repair_local_version(&candidates_dir.join(&local_version.candidate), local_version) {
+                report_error("sdk", &e);
             }
         }
-        println!("{}: {}", candidate_name, installed_versions.join(", "));
+    } else if !broken.is_empty() && !json {
+        eprintln!("{}", format!("{} broken installation(s) found, run `sdk list --local --repair` to repair them.", broken.len()).yellow());
     }
 }
 
+/// versions in candidate directory, including broken links
+pub fn scan_local_versions(candidate_dir: &Path, candidate_name: &str) -> Vec<LocalVersion> {
+    let mut local_versions: Vec<LocalVersion> = vec![];
+    let Ok(entries) = std::fs::read_dir(candidate_dir) else {
+        return local_versions;
+    };
+    let current_link = candidate_dir.join("current");
+    let current_version = current_link.read_link().ok()
+        .and_then(|link_target_path| link_target_path.file_name().map(|name| name.to_string_lossy().to_string()));
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let version = entry.file_name().to_string_lossy().to_string();
+        let linked = path.is_symlink();
+        // staging and backup directories of installs are hidden
+        if version == "current" || version.starts_with('.') || (!linked && !path.is_dir()) {
+            continue;
+        }
+        let problem = if !path.exists() {
+            Some(InstallProblem::DanglingLink)
+        } else if !has_bin_dir(&path) {
+            Some(InstallProblem::MissingBin)
+        } else {
+            None
+        };
+        local_versions.push(LocalVersion {
+            candidate: candidate_name.to_string(),
+            size: dir_size(&path),
+            source: if linked { InstallSource::Linked } else { InstallSource::Downloaded },
+            installed: install_date(&path),
+            current: current_version.as_ref() == Some(&version),
+            problem,
+            version,
+        });
+    }
+    local_versions.sort_by(|a, b| compare_versions(&b.version, &a.version));
+    if current_link.is_symlink() && !current_link.exists() {
+        local_versions.push(LocalVersion {
+            candidate: candidate_name.to_string(),
+            version: "current".to_owned(),
+            size: 0,
+            source: InstallSource::Linked,
+            installed: None,
+            current: true,
+            problem: Some(InstallProblem::DanglingCurrent),
+        });
+    }
+    local_versions
+}
+
+/// `bin/`, or `Contents/Home/bin/` of macOS JDK
+fn has_bin_dir(candidate_home: &Path) -> bool {
+    candidate_home.join("bin").is_dir() || candidate_home.join("Contents").join("Home").join("bin").is_dir()
+}
+
+/// total size of files, the target of a linked installation is included
+fn dir_size(path: &Path) -> u64 {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// date of the version directory or link
+fn install_date(path: &Path) -> Option<String> {
+    let metadata = std::fs::symlink_metadata(path).ok()?;
+    let time = metadata.created().or_else(|_| metadata.modified()).ok()?;
+    let date: chrono::DateTime<chrono::Local> = time.into();
+    Some(date.format("%Y-%m-%d").to_string())
+}
+
+fn print_local_versions(local_versions: &[LocalVersion]) {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row!["Candidate", "Use", "Version", "Size", "Source", "Installed", "Status"]);
+    let mut last_candidate = "";
+    for local_version in local_versions {
+        // candidate is only displayed in the first row of each candidate
+        let candidate = if local_version.candidate != last_candidate { local_version.candidate.as_str() } else { "" };
+        last_candidate = &local_version.candidate;
+        let status = match &local_version.problem {
+            Some(problem) => problem.label().red().to_string(),
+            None => "ok".to_owned(),
+        };
+        table.add_row(row![
+            candidate,
+            if local_version.current { ">>>" } else { "" },
+            local_version.version,
+            HumanBytes(local_version.size),
+            match local_version.source {
+                InstallSource::Downloaded => "downloaded",
+                InstallSource::Linked => "linked",
+            },
+            local_version.installed.as_deref().unwrap_or_default(),
+            status
+        ]);
+    }
+    table.printstd();
+}
+
+/// remove dangling links, and reinstall downloaded versions without `bin/`
+fn repair_local_version(candidate_dir: &Path, local_version: &LocalVersion) -> wukong::Result<()> {
+    let candidate_name = &local_version.candidate;
+    let candidate_home = candidate_dir.join(&local_version.version);
+    match (local_version.problem, local_version.source) {
+        (Some(InstallProblem::DanglingCurrent), _) => {
+            symlink::remove_symlink_dir(&candidate_home)?;
+            println!("Removed dangling current link of {}, run `sdk default {}` to set a default version.", candidate_name, candidate_name);
+        }
+        (Some(InstallProblem::DanglingLink), _) => {
+            symlink::remove_symlink_dir(&candidate_home)?;
+            println!("Removed dangling link {} {}", candidate_name, local_version.version);
+        }
+        (Some(InstallProblem::MissingBin), InstallSource::Downloaded) => {
+            println!("Reinstalling {} {}", candidate_name, local_version.version);
+            // broken installation is restored if reinstall fails
+            let broken_dir = candidate_dir.join(format!(".{}.broken-{}", local_version.version, std::process::id()));
+            std::fs::rename(&candidate_home, &broken_dir)?;
+            if let Err(e) = install_candidate(candidate_name, &local_version.version) {
+                std::fs::rename(&broken_dir, &candidate_home)?;
+                return Err(e);
+            }
+            std::fs::remove_dir_all(&broken_dir)?;
+        }
+        (Some(InstallProblem::MissingBin), InstallSource::Linked) => {
+            eprintln!("{} {} is linked to {}, which has no bin/ directory, please check it manually.",
+                      candidate_name, local_version.version, candidate_home.read_link()?.display());
+        }
+        (None, _) => {}
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sdkman_cli::models::is_java_lts;
+
+    fn java_version(identifier: &str, vendor: &str) -> CandidateVersion {
+        let (version, dist) = identifier.rsplit_once('-').unwrap();
+        CandidateVersion {
+            vendor: Some(vendor.to_string()),
+            version: version.to_string(),
+            dist: Some(dist.to_string()),
+            identifier: identifier.to_string(),
+            status: VersionStatus::Available,
+            current: false,
+            lts: is_java_lts(version),
+        }
+    }
+
+    #[test]
+    fn test_merge_and_filter_versions() {
+        let remote_versions = vec![
+            java_version("22.0.1-amzn", "Corretto"),
+            java_version("21.0.3-tem", "Temurin"),
+            java_version("17.0.11-tem", "Temurin"),
+        ];
+        let installed_versions = vec!["21.0.3-tem".to_owned(), "21.0.1-local".to_owned()];
+        let versions = merge_local_versions("java", remote_versions, &installed_versions, "21.0.3-tem");
+        assert_eq!(versions.len(), 4);
+        assert!(versions[1].current);
+        assert_eq!(versions[1].status, VersionStatus::Installed);
+        assert_eq!(versions[3].status, VersionStatus::LocalOnly);
+        assert_eq!(versions[3].dist.as_deref(), Some("local"));
+        assert_eq!(versions[3].vendor.as_deref(), Some("Unclassified"));
+        let options = ListOptions {
+            vendor: Some("tem".to_owned()),
+            ..ListOptions::default()
+        };
+        assert_eq!(filter_versions(versions.clone(), &options).len(), 2);
+        let options = ListOptions {
+            vendor: Some("temurin".to_owned()),
+            installed: true,
+            ..ListOptions::default()
+        };
+        let filtered = filter_versions(versions, &options);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].identifier, "21.0.3-tem");
+    }
+
+    #[test]
+    fn test_scan_local_versions() {
+        let dir = std::env::temp_dir().join("wukong-sdkman-local-versions");
+        let _ = std::fs::remove_dir_all(&dir);
+        let candidate_dir = dir.join("candidates").join("java");
+        std::fs::create_dir_all(candidate_dir.join("21.0.3-tem").join("bin")).unwrap();
+        std::fs::write(candidate_dir.join("21.0.3-tem").join("bin").join("java"), "12345").unwrap();
+        std::fs::create_dir_all(candidate_dir.join("17.0.11-tem").join("lib")).unwrap();
+        std::fs::create_dir_all(dir.join("jdk-11").join("bin")).unwrap();
+        symlink::symlink_dir(dir.join("jdk-11"), candidate_dir.join("11-local")).unwrap();
+        symlink::symlink_dir(dir.join("jdk-8"), candidate_dir.join("8-local")).unwrap();
+        symlink::symlink_dir(candidate_dir.join("22-tem"), candidate_dir.join("current")).unwrap();
+        let local_versions = scan_local_versions(&candidate_dir, "java");
+        let summary: Vec<(&str, InstallSource, Option<InstallProblem>)> = local_versions.iter()
+            .map(|local_version| (local_version.version.as_str(), local_version.source, local_version.problem))
+            .collect();
+        assert_eq!(summary, vec![
+            ("21.0.3-tem", InstallSource::Downloaded, None),
+            ("17.0.11-tem", InstallSource::Downloaded, Some(InstallProblem::MissingBin)),
+            ("11-local", InstallSource::Linked, None),
+            ("8-local", InstallSource::Linked, Some(InstallProblem::DanglingLink)),
+            ("current", InstallSource::Linked, Some(InstallProblem::DanglingCurrent)),
+        ]);
+        assert_eq!(local_versions[0].size, 5);
+        assert!(local_versions[0].installed.is_some());
+        repair_local_version(&candidate_dir, &local_versions[3]).unwrap();
+        repair_local_version(&candidate_dir, &local_versions[4]).unwrap();
+        assert_eq!(scan_local_versions(&candidate_dir, "java").len(), 3);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 
     #[test]
     fn test_list_candidate() {
         let candidate_name = "java";
-        list_candidate(candidate_name);
+        list_candidate_with_options(candidate_name, &ListOptions::default()).unwrap();
     }
 }
@@ -0,0 +1,157 @@
+//! Local cache for SDKMAN candidates API responses under `$SDKMAN_DIR/var/metadata`.
+//!
+//! Fresh entries are served without network access, stale entries are refreshed,
+//! and used as fallback if the API is not reachable. In offline mode only cached entries are used.
+
+use crate::sdkman_cli::config::is_debug_mode;
+use crate::sdkman_cli::sdkman_home;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use wukong::http::http_get;
+use wukong::Error;
+
+pub const CANDIDATES_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+pub const VERSIONS_TTL: Duration = Duration::from_secs(60 * 60);
+pub const DEFAULT_VERSION_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedResponse {
+    url: String,
+    body: String,
+}
+
+pub fn metadata_dir() -> PathBuf {
+    sdkman_home().join("var").join("metadata")
+}
+
+fn offline_mode_file() -> PathBuf {
+    sdkman_home().join("var").join("offline_mode")
+}
+
+/// offline mode from `SDKMAN_OFFLINE_MODE` env variable, then `sdk offline enable|disable`
+pub fn is_offline() -> bool {
+    if let Ok(offline_mode) = std::env::var("SDKMAN_OFFLINE_MODE") {
+        return offline_mode == "true";
+    }
+    offline_mode_file().exists()
+}
+
+pub fn set_offline(offline: bool) -> wukong::Result<()> {
+    let offline_mode_file = offline_mode_file();
+    if offline {
+        std::fs::create_dir_all(offline_mode_file.parent().unwrap())?;
+        std::fs::write(&offline_mode_file, "true")?;
+    } else if offline_mode_file.exists() {
+        std::fs::remove_file(&offline_mode_file)?;
+    }
+    Ok(())
+}
+
+pub struct MetadataCache {
+    dir: PathBuf,
+    offline: bool,
+}
+
+impl MetadataCache {
+    pub fn new(dir: PathBuf, offline: bool) -> Self {
+        MetadataCache { dir, offline }
+    }
+
+    /// cache under `sdkman_home()` with offline mode from `is_offline()`
+    pub fn load() -> Self {
+        MetadataCache::new(metadata_dir(), is_offline())
+    }
+
+    /// response text of `url`, cached as `key`.
+    /// A fresh entry is only used if it was fetched from the same `url`, because version lists embed query parameters.
+    pub fn get_text(&self, key: &str, url: &str, ttl: Duration) -> wukong::Result<String> {
+        let cached = self.read(key);
+        if self.offline {
+            return cached.map(|cached| cached.body)
+                .ok_or_else(|| Error::Network(format!("Offline mode, no cached metadata for {}", key)));
+        }
+        if let Some(cached) = &cached && cached.url == url && self.is_fresh(key, ttl) {
+            return Ok(cached.body.clone());
+        }
+        match fetch_text(url) {
+            Ok(body) => {
+                self.write(key, url, &body);
+                Ok(body)
+            }
+            Err(e) if e.is_retryable() && cached.is_some() => {
+                eprintln!("[sdk] {}, using cached metadata", e);
+                Ok(cached.unwrap().body)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    fn read(&self, key: &str) -> Option<CachedResponse> {
+        let text = std::fs::read_to_string(self.entry_path(key)).ok()?;
+        serde_json::from_str(&text).ok()
+    }
+
+    fn is_fresh(&self, key: &str, ttl: Duration) -> bool {
+        std::fs::metadata(self.entry_path(key))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age < ttl)
+    }
+
+    /// cache is best effort, write failure is ignored
+    fn write(&self, key: &str, url: &str, body: &str) {
+        let cached = CachedResponse {
+            url: url.to_string(),
+            body: body.to_string(),
+        };
+        if std::fs::create_dir_all(&self.dir).is_ok() {
+            let _ = std::fs::write(self.entry_path(key), serde_json::to_string(&cached).unwrap());
+        }
+    }
+}
+
+fn fetch_text(url: &str) -> wukong::Result<String> {
+    if is_debug_mode() {
+        eprintln!("[sdk] GET {}", url);
+    }
+    Ok(http_get(url)?.error_for_status()?.text()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // nothing listens on port 9, so requests fail fast
+    const UNREACHABLE_URL: &str = "http://127.0.0.1:9/2/candidates/list";
+
+    fn seeded_cache(name: &str, offline: bool) -> MetadataCache {
+        let dir = std::env::temp_dir().join(format!("wukong-sdkman-metadata-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        let cache = MetadataCache::new(dir, offline);
+        cache.write("candidates", UNREACHABLE_URL, "ant, gradle, java, maven");
+        cache
+    }
+
+    #[test]
+    fn test_offline_cache() {
+        let cache = seeded_cache("offline", true);
+        assert_eq!(cache.get_text("candidates", "https://api.sdkman.io/2/candidates/list", CANDIDATES_TTL).unwrap(), "ant, gradle, java, maven");
+        assert!(cache.get_text("versions-java-linuxx64", UNREACHABLE_URL, VERSIONS_TTL).is_err());
+    }
+
+    #[test]
+    fn test_online_cache() {
+        let cache = seeded_cache("online", false);
+        // fresh entry, no network access
+        assert_eq!(cache.get_text("candidates", UNREACHABLE_URL, CANDIDATES_TTL).unwrap(), "ant, gradle, java, maven");
+        // stale entry, fallback to cache when API is not reachable
+        assert_eq!(cache.get_text("candidates", UNREACHABLE_URL, Duration::ZERO).unwrap(), "ant, gradle, java, maven");
+        assert!(cache.get_text("default-java", "http://127.0.0.1:9/2/candidates/default/java", DEFAULT_VERSION_TTL).is_err());
+    }
+}
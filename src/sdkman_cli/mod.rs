@@ -1,7 +1,12 @@
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::BufReader;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use wukong::error::exit_with_error;
+use wukong::platform::Arch;
+use crate::sdkman_cli::metadata::{MetadataCache, DEFAULT_VERSION_TTL, VERSIONS_TTL};
+use crate::sdkman_cli::models::{parse_versions, CandidateVersion};
+use crate::sdkman_cli::version_match::find_best_match;
 
 pub mod list;
 pub mod install;
@@ -15,29 +20,38 @@ pub mod upgrade;
 pub mod clap_app;
 pub mod direnv;
 pub mod init;
+pub mod metadata;
+pub mod offline;
+pub mod flush;
+pub mod models;
+pub mod version_match;
+pub mod hooks;
+pub mod shell;
+pub mod version_files;
+pub mod broadcast;
+pub mod selfupdate;
+pub mod version;
+pub mod config;
+pub mod doctor;
 
 const SDKMAN_CANDIDATES_API: &str = "https://api.sdkman.io/2";
+/// SDKMAN platform id, such as `linuxx64`, `linuxarm32hf` or `darwinarm64`.
+/// `exotic` is returned for platforms SDKMAN has no native builds for, and only universal candidates are available.
 pub fn get_sdkman_platform() -> String {
     let os_name = match std::env::consts::OS {
         "macos" => "darwin",
         "windows" => "windows",
         "linux" => "linux",
-        &_ => ""
+        &_ => return "exotic".to_owned(),
     };
-    let arch_name = match std::env::consts::ARCH {
-        "aarch64" => "arm64",
-        "x86_64" => "x64",
-        &_ => "exotic"
-    };
-    if arch_name == "exotic" {
-        "exotic".to_owned()
-    } else {
-        format!("{}{}", os_name, arch_name)
+    match Arch::current().ok().and_then(|arch| arch.sdkman_name()) {
+        Some(arch_name) => format!("{}{}", os_name, arch_name),
+        None => "exotic".to_owned(),
     }
 }
 
 pub fn sdkman_home() -> PathBuf {
-    wukong::common::sdkman_home()
+    wukong::common::sdkman_home().unwrap_or_else(|e| exit_with_error("sdk", &e))
 }
 
 pub fn read_sdkman_config() -> HashMap<String, String> {
@@ -71,37 +85,73 @@ pub fn find_candidate_home(candidate_name: &str, candidate_version: &str) -> Pat
         .join(candidate_name).join(candidate_version)
 }
 
+/// Java home of the major version installed in SDKMAN, then the JDK of JBang in `~/.jbang/cache/jdks/<major>`
 pub fn find_java_home(major_version: &str) -> Option<PathBuf> {
-    let java_repo = sdkman_home().join("candidates").join("java");
-    // list sub directories from java_repo
-    let entries = std::fs::read_dir(java_repo).unwrap();
-    for entry in entries {
-        let entry = entry.unwrap();
-        if entry.path().is_dir() {
-            let version = entry.file_name().into_string().unwrap();
-            if version.starts_with(major_version) {
-                return Some(entry.path());
-            }
-        }
+    find_installed_version("java", major_version, None)
+        .map(|java_version| find_candidate_home("java", &java_version))
+        .or_else(|| {
+            let jdks_dir = wukong::common::jbang_home().ok()?.join("cache").join("jdks");
+            find_jbang_java_home(&jdks_dir, major_version)
+        })
+}
+
+fn find_jbang_java_home(jdks_dir: &Path, major_version: &str) -> Option<PathBuf> {
+    Some(jdks_dir.join(major_version)).filter(wukong::common::is_java_home)
+}
+
+/// Temurin is preferred for Java if vendor is not specified
+fn default_vendor(candidate_name: &str) -> Option<&'static str> {
+    if candidate_name == "java" { Some("tem") } else { None }
+}
+
+/// installed version for version query, such as `21`, `21-zulu`, `3.9` or `latest`, without network access
+pub fn find_installed_version(candidate_name: &str, query: &str, vendor: Option<&str>) -> Option<String> {
+    match_installed_version(candidate_name, &list_installed_versions(candidate_name), query, vendor)
+}
+
+pub fn match_installed_version(candidate_name: &str, installed_versions: &[String], query: &str, vendor: Option<&str>) -> Option<String> {
+    if vendor.is_none() && installed_versions.iter().any(|version| version == query) {
+        return Some(query.to_string());
     }
-    None
+    let versions: Vec<CandidateVersion> = installed_versions.iter()
+        .map(|version| CandidateVersion::from_identifier(candidate_name == "java", version))
+        .collect();
+    find_best_match(&versions, query, vendor, default_vendor(candidate_name))
+        .map(|version| version.identifier.clone())
+}
+
+/// available version from SDKMAN for version query, such as `21`, `21.0.2-zulu`, `3.9` or `latest`
+pub fn find_remote_version(candidate_name: &str, query: &str, vendor: Option<&str>) -> wukong::Result<Option<String>> {
+    let versions = fetch_candidate_versions(candidate_name)?;
+    Ok(find_best_match(&versions, query, vendor, default_vendor(candidate_name))
+        .map(|version| version.identifier.clone()))
 }
 
-pub fn find_java_version(major_version: &str) -> Option<String> {
-    let list_url = format!("{}/candidates/{}/{}/versions/list?current={}&installed={}",
+pub fn fetch_candidate_versions(candidate_name: &str) -> wukong::Result<Vec<CandidateVersion>> {
+    let sdkman_platform = get_sdkman_platform();
+    let list_url = format!("{}/candidates/{}/{}/versions/list?current=&installed=",
                            SDKMAN_CANDIDATES_API,
-                           "java",
-                           get_sdkman_platform(),
-                           "",
-                           ""
+                           candidate_name,
+                           sdkman_platform
     );
-    let text = wukong::common::http_text(&list_url);
-    let versions: Vec<&str> = text.lines()
-        .filter(|line| line.trim().ends_with("-tem"))
-        .map(|line| line.split('|').last().unwrap().trim())
-        .filter(|version| version.trim().starts_with(major_version))
-        .collect();
-    versions.get(0).map(|s| s.to_string())
+    let cache_key = format!("versions-{}-{}", candidate_name, sdkman_platform);
+    let text = MetadataCache::load().get_text(&cache_key, &list_url, VERSIONS_TTL)?;
+    Ok(parse_versions(candidate_name, &text))
+}
+
+/// installed versions of candidate, `current` link excluded
+pub fn list_installed_versions(candidate_name: &str) -> Vec<String> {
+    let mut installed_versions: Vec<String> = vec![];
+    let candidate_repo = sdkman_home().join("candidates").join(candidate_name);
+    if let Ok(entries) = std::fs::read_dir(candidate_repo) {
+        for entry in entries.flatten() {
+            let version = entry.file_name().to_string_lossy().to_string();
+            if entry.path().is_dir() && version != "current" {
+                installed_versions.push(version);
+            }
+        }
+    }
+    installed_versions
 }
 
 pub fn get_installed_candidate_default_version(candidate_name: &str) -> String {
@@ -113,9 +163,15 @@ pub fn get_installed_candidate_default_version(candidate_name: &str) -> String {
     "".to_owned()
 }
 
-pub fn get_remote_candidate_default_version(candidate_name: &str) -> String {
+pub fn get_remote_candidate_default_version(candidate_name: &str) -> wukong::Result<String> {
     let default_version_url = format!("{}/candidates/default/{}", SDKMAN_CANDIDATES_API, candidate_name);
-    wukong::common::http_text(&default_version_url).trim().to_string()
+    let cache_key = format!("default-{}", candidate_name);
+    match MetadataCache::load().get_text(&cache_key, &default_version_url, DEFAULT_VERSION_TTL) {
+        Ok(text) => Ok(text.trim().to_string()),
+        // unknown candidate
+        Err(wukong::Error::Http(400..500, _)) => Ok("".to_owned()),
+        Err(e) => Err(e),
+    }
 }
 
 #[cfg(test)]
@@ -147,7 +203,7 @@ mod tests {
     #[test]
     fn test_find_java_version() {
         let major_version = "17";
-        let version = find_java_version(major_version);
+        let version = find_remote_version("java", major_version, None);
         print!("{:?}", version);
     }
 }
@@ -0,0 +1,307 @@
+//! Typed models for SDKMAN candidates API, parsed from the text format of
+//! `/candidates/list` and `/candidates/{candidate}/{platform}/versions/list`.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Candidate {
+    /// candidate id for `sdk install`, such as `java`
+    pub candidate: String,
+    pub name: String,
+    pub default_version: String,
+    pub homepage: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionStatus {
+    Available,
+    Installed,
+    /// installed from local path, not available in SDKMAN
+    LocalOnly,
+}
+
+impl VersionStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            VersionStatus::Available => "",
+            VersionStatus::Installed => "installed",
+            VersionStatus::LocalOnly => "local only",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CandidateVersion {
+    /// vendor name, Java only
+    pub vendor: Option<String>,
+    pub version: String,
+    /// distribution id, such as `tem` for Temurin, Java only
+    pub dist: Option<String>,
+    /// version used by `sdk install`, such as `21.0.2-tem`
+    pub identifier: String,
+    pub status: VersionStatus,
+    pub current: bool,
+    pub lts: bool,
+}
+
+impl CandidateVersion {
+    pub fn is_installed(&self) -> bool {
+        self.status != VersionStatus::Available
+    }
+
+    /// local only version from identifier, dist is the suffix for Java, such as `tem` of `21.0.2-tem`
+    pub fn from_identifier(java: bool, identifier: &str) -> Self {
+        let (version, dist) = match identifier.rsplit_once('-') {
+            Some((version, dist)) if java => (version.to_string(), Some(dist.to_string())),
+            _ => (identifier.to_string(), None),
+        };
+        CandidateVersion {
+            vendor: dist.as_ref().map(|_| "Unclassified".to_owned()),
+            lts: java && is_java_lts(&version),
+            version,
+            dist,
+            identifier: identifier.to_string(),
+            status: VersionStatus::LocalOnly,
+            current: false,
+        }
+    }
+
+    /// vendor matches dist or vendor name, case-insensitive: `tem` or `Temurin`
+    pub fn matches_vendor(&self, vendor: &str) -> bool {
+        self.dist.as_deref().is_some_and(|dist| dist.eq_ignore_ascii_case(vendor))
+            || self.vendor.as_deref().is_some_and(|name| name.eq_ignore_ascii_case(vendor))
+    }
+}
+
+/// Java LTS releases: 8, 11, 17, then every 4th release
+pub fn is_java_lts(version: &str) -> bool {
+    let major = version.split(['.', '-', '+']).next().unwrap_or_default();
+    match major.parse::<u32>() {
+        Ok(8) | Ok(11) => true,
+        Ok(major) => major >= 17 && (major - 17) % 4 == 0,
+        Err(_) => false,
+    }
+}
+
+/// lines between the 2nd and 3rd `====` separators
+fn table_body(text: &str) -> Vec<&str> {
+    text.lines()
+        .skip_while(|line| !line.starts_with("===="))
+        .skip(1)
+        .skip_while(|line| !line.starts_with("===="))
+        .skip(1)
+        .take_while(|line| !line.starts_with("===="))
+        .collect()
+}
+
+/// parse Java versions table with columns `Vendor | Use | Version | Dist | Status | Identifier`,
+/// vendor is only present in the first row of each vendor
+pub fn parse_java_versions(text: &str) -> Vec<CandidateVersion> {
+    let mut versions = vec![];
+    let mut vendor = String::new();
+    for line in table_body(text) {
+        let columns: Vec<&str> = line.split('|').map(|column| column.trim()).collect();
+        if columns.len() != 6 || columns[0] == "Vendor" {
+            continue;
+        }
+        if !columns[0].is_empty() {
+            vendor = columns[0].to_string();
+        }
+        let status = match columns[4] {
+            "installed" => VersionStatus::Installed,
+            "local only" => VersionStatus::LocalOnly,
+            _ => VersionStatus::Available,
+        };
+        versions.push(CandidateVersion {
+            vendor: Some(vendor.clone()),
+            version: columns[2].to_string(),
+            dist: Some(columns[3].to_string()),
+            identifier: columns[5].to_string(),
+            status,
+            current: columns[1] == ">>>",
+            lts: is_java_lts(columns[2]),
+        });
+    }
+    versions
+}
+
+/// parse versions grid of other candidates, every version may be prefixed with markers:
+/// `+` local version, `*` installed and `>` currently in use
+pub fn parse_candidate_versions(text: &str) -> Vec<CandidateVersion> {
+    let mut versions = vec![];
+    let (mut local, mut installed, mut current) = (false, false, false);
+    for token in table_body(text).iter().flat_map(|line| line.split_whitespace()) {
+        match token {
+            "+" => local = true,
+            "*" => installed = true,
+            ">" => current = true,
+            version => {
+                let status = if local {
+                    VersionStatus::LocalOnly
+                } else if installed || current {
+                    VersionStatus::Installed
+                } else {
+                    VersionStatus::Available
+                };
+                versions.push(CandidateVersion {
+                    vendor: None,
+                    version: version.to_string(),
+                    dist: None,
+                    identifier: version.to_string(),
+                    status,
+                    current,
+                    lts: false,
+                });
+                (local, installed, current) = (false, false, false);
+            }
+        }
+    }
+    versions
+}
+
+pub fn parse_versions(candidate_name: &str, text: &str) -> Vec<CandidateVersion> {
+    if candidate_name == "java" {
+        parse_java_versions(text)
+    } else {
+        parse_candidate_versions(text)
+    }
+}
+
+/// parse candidate blocks separated by `----` lines:
+/// `Name (version)   homepage`, description, then `$ sdk install <candidate>`
+pub fn parse_candidates(text: &str) -> Vec<Candidate> {
+    let mut candidates = vec![];
+    for block in text.split("\n---") {
+        let lines: Vec<&str> = block.lines()
+            .filter(|line| !line.starts_with("---") && !line.trim().is_empty())
+            .collect();
+        let Some(install_line) = lines.iter().find(|line| line.trim().starts_with("$ sdk install ")) else {
+            continue;
+        };
+        let candidate = install_line.trim().trim_start_matches("$ sdk install ").trim().to_string();
+        let Some(title) = lines.first() else {
+            continue;
+        };
+        let (title, homepage) = match title.trim().rsplit_once(char::is_whitespace) {
+            Some((title, homepage)) if homepage.starts_with("http") => (title.trim(), homepage.to_string()),
+            _ => (title.trim(), String::new()),
+        };
+        let (name, default_version) = match title.rsplit_once(" (") {
+            Some((name, version)) => (name.to_string(), version.trim_end_matches(')').to_string()),
+            None => (title.to_string(), String::new()),
+        };
+        let description = lines[1..].iter()
+            .filter(|line| !line.trim().starts_with("$ sdk install "))
+            .map(|line| line.trim())
+            .collect::<Vec<&str>>()
+            .join(" ");
+        candidates.push(Candidate {
+            candidate,
+            name,
+            default_version,
+            homepage,
+            description,
+        });
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JAVA_VERSIONS: &str = r#"================================================================================
+Available Java Versions for Linux 64bit
+================================================================================
+ Vendor        | Use | Version      | Dist    | Status     | Identifier
+--------------------------------------------------------------------------------
+ Corretto      |     | 22.0.1       | amzn    |            | 22.0.1-amzn
+               |     | 21.0.3       | amzn    |            | 21.0.3-amzn
+ Temurin       | >>> | 21.0.3       | tem     | installed  | 21.0.3-tem
+               |     | 17.0.11      | tem     | local only | 17.0.11-tem
+================================================================================
+Omit Identifier to install default version 21.0.3-tem:
+    $ sdk install java
+================================================================================
+"#;
+
+    const MAVEN_VERSIONS: &str = r#"================================================================================
+Available Maven Versions
+================================================================================
+ > * 3.9.6               3.8.8               3.6.3
+     3.9.5             + 3.8.7             * 3.6.2
+
+================================================================================
++ - local version
+* - installed
+> - currently in use
+================================================================================
+"#;
+
+    const CANDIDATES: &str = r#"================================================================================
+Available Candidates
+================================================================================
+q-quit                                  /-search down
+--------------------------------------------------------------------------------
+Apache Ant (1.10.14)                                  https://ant.apache.org/
+
+Apache Ant is a Java library and command-line tool whose mission is to drive
+processes described in build files.
+
+                                                               $ sdk install ant
+--------------------------------------------------------------------------------
+Java (21.0.3-tem)                                           https://projects.eclipse.org/projects/adoptium.temurin/
+
+Java Platform, Standard Edition.
+
+                                                              $ sdk install java
+--------------------------------------------------------------------------------
+"#;
+
+    #[test]
+    fn test_parse_java_versions() {
+        let versions = parse_java_versions(JAVA_VERSIONS);
+        assert_eq!(versions.len(), 4);
+        assert_eq!(versions[1].vendor.as_deref(), Some("Corretto"));
+        assert_eq!(versions[2].identifier, "21.0.3-tem");
+        assert!(versions[2].current);
+        assert!(versions[2].lts);
+        assert_eq!(versions[2].status, VersionStatus::Installed);
+        assert_eq!(versions[3].status, VersionStatus::LocalOnly);
+        assert!(!versions[0].lts);
+    }
+
+    #[test]
+    fn test_parse_candidate_versions() {
+        let versions = parse_candidate_versions(MAVEN_VERSIONS);
+        let identifiers: Vec<&str> = versions.iter().map(|version| version.identifier.as_str()).collect();
+        assert_eq!(identifiers, vec!["3.9.6", "3.8.8", "3.6.3", "3.9.5", "3.8.7", "3.6.2"]);
+        assert!(versions[0].current);
+        assert_eq!(versions[0].status, VersionStatus::Installed);
+        assert_eq!(versions[4].status, VersionStatus::LocalOnly);
+        assert_eq!(versions[5].status, VersionStatus::Installed);
+        assert_eq!(versions[1].status, VersionStatus::Available);
+    }
+
+    #[test]
+    fn test_parse_candidates() {
+        let candidates = parse_candidates(CANDIDATES);
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].candidate, "ant");
+        assert_eq!(candidates[0].name, "Apache Ant");
+        assert_eq!(candidates[0].default_version, "1.10.14");
+        assert_eq!(candidates[0].homepage, "https://ant.apache.org/");
+        assert!(candidates[0].description.starts_with("Apache Ant is a Java library"));
+        assert_eq!(candidates[1].default_version, "21.0.3-tem");
+    }
+
+    #[test]
+    fn test_java_lts() {
+        assert!(is_java_lts("8.0.412"));
+        assert!(is_java_lts("25-ea"));
+        assert!(!is_java_lts("22.0.1"));
+        assert!(!is_java_lts("22.1.0.1.r17"));
+    }
+}
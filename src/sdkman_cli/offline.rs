@@ -0,0 +1,29 @@
+use crate::sdkman_cli::metadata::set_offline;
+use clap::{Arg, Command};
+use colored::Colorize;
+use wukong::error::exit_with_error;
+
+pub fn manage_offline(offline_matches: &clap::ArgMatches) {
+    let offline = offline_matches.get_one::<String>("mode").unwrap() == "enable";
+    if let Err(e) = set_offline(offline) {
+        exit_with_error("sdk", &e);
+    }
+    if offline {
+        println!("{}", "Offline mode enabled.".green());
+    } else {
+        println!("{}", "Online mode re-enabled!".green());
+    }
+}
+
+pub fn build_offline_command() -> Command {
+    Command::new("offline")
+        .about("enable or disable offline mode, cached metadata is used in offline mode.")
+        .long_about(r#"In offline mode, candidates and versions are listed from the metadata cache in $SDKMAN_DIR/var/metadata, and no download is attempted. SDKMAN_OFFLINE_MODE=true|false environment variable takes precedence over this setting."#)
+        .arg(
+            Arg::new("mode")
+                .help("enable or disable")
+                .index(1)
+                .value_parser(["enable", "disable"])
+                .required(true)
+        )
+}
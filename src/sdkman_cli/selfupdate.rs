@@ -0,0 +1,287 @@
+//! `sdk selfupdate`: check the wukong GitHub release feed, download the archive for current platform,
+//! and replace the running binary atomically.
+//!
+//! The archive is verified with its `.sha256` asset, and the new binary is written next to the current binary first,
+//! then renamed over it, so an interrupted update never leaves a partial binary.
+
+use crate::sdkman_cli::clap_app::VERSION;
+use crate::sdkman_cli::config::config_enabled;
+use clap::{Arg, Command};
+use colored::Colorize;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use wukong::common::{compare_versions, extract_archive, http_download, verify_sha256};
+use wukong::error::exit_with_error;
+use wukong::http::http_get;
+use wukong::Error;
+
+const WUKONG_RELEASES_API: &str = "https://api.github.com/repos/linux-china/wukong/releases/latest";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    /// tag name, such as `v0.3.3` or `0.3.3`
+    #[serde(rename = "tag_name")]
+    pub tag: String,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    #[serde(rename = "browser_download_url")]
+    pub url: String,
+}
+
+impl Release {
+    pub fn version(&self) -> &str {
+        self.tag.trim_start_matches('v')
+    }
+
+    /// archive for the target triple, such as `wukong-x86_64-unknown-linux-gnu.tar.gz`
+    pub fn find_asset(&self, target: &str) -> Option<&ReleaseAsset> {
+        let prefix = format!("wukong-{}.", target);
+        self.assets.iter()
+            .find(|asset| asset.name.starts_with(&prefix) && !asset.name.ends_with(".sha256"))
+    }
+
+    /// checksum of the archive, such as `wukong-x86_64-unknown-linux-gnu.tar.gz.sha256`
+    pub fn find_checksum_asset(&self, asset: &ReleaseAsset) -> Option<&ReleaseAsset> {
+        let checksum_name = format!("{}.sha256", asset.name);
+        self.assets.iter().find(|checksum_asset| checksum_asset.name == checksum_name)
+    }
+}
+
+/// source of wukong releases, a local stub is used in tests
+pub trait ReleaseFeed {
+    fn latest_release(&self) -> wukong::Result<Release>;
+
+    fn download(&self, asset: &ReleaseAsset, target_file_path: &Path) -> wukong::Result<()>;
+}
+
+pub struct GithubReleases;
+
+impl ReleaseFeed for GithubReleases {
+    fn latest_release(&self) -> wukong::Result<Release> {
+        let text = http_get(WUKONG_RELEASES_API)?.error_for_status()?.text()?;
+        serde_json::from_str(&text).map_err(|e| Error::Parse(format!("Invalid release feed: {}", e)))
+    }
+
+    fn download(&self, asset: &ReleaseAsset, target_file_path: &Path) -> wukong::Result<()> {
+        http_download(&asset.url, target_file_path)
+    }
+}
+
+pub fn manage_selfupdate(selfupdate_matches: &clap::ArgMatches) {
+    let force = selfupdate_matches.get_flag("force");
+    if !force && !config_enabled("sdkman_selfupdate_feature") {
+        println!("Self-update is disabled by sdkman_selfupdate_feature=false, use --force to update anyway.");
+        return;
+    }
+    let current_exe = std::env::current_exe().unwrap_or_else(|e| exit_with_error("sdk", &e.into()));
+    match self_update(&GithubReleases, VERSION, &current_exe, force) {
+        Ok(Some(version)) => println!("{}", format!("Successfully upgraded to wukong {}.", version).green()),
+        Ok(None) => println!("No update available at this time."),
+        Err(e) => exit_with_error("sdk", &e),
+    }
+}
+
+/// newer release than current version, `None` if up to date
+pub fn check_update(feed: &dyn ReleaseFeed, current_version: &str) -> wukong::Result<Option<Release>> {
+    let release = feed.latest_release()?;
+    if compare_versions(release.version(), current_version) == Ordering::Greater {
+        Ok(Some(release))
+    } else {
+        Ok(None)
+    }
+}
+
+/// replace the binary with the `sdk` binary of the latest release, returns the installed version
+pub fn self_update(feed: &dyn ReleaseFeed, current_version: &str, binary_path: &Path, force: bool) -> wukong::Result<Option<String>> {
+    let release = if force {
+        feed.latest_release()?
+    } else {
+        match check_update(feed, current_version)? {
+            Some(release) => release,
+            None => return Ok(None),
+        }
+    };
+    let target = current_target().ok_or_else(|| Error::UnsupportedPlatform("No wukong release for current platform".to_owned()))?;
+    let asset = release.find_asset(target)
+        .ok_or_else(|| Error::NotFound(format!("wukong {} archive for {}", release.version(), target)))?;
+    let checksum_asset = release.find_checksum_asset(asset)
+        .ok_or_else(|| Error::NotFound(format!("checksum of {}", asset.name)))?;
+    println!("Upgrading wukong {} to {}", current_version, release.version());
+    let work_dir = std::env::temp_dir().join(format!("wukong-selfupdate-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&work_dir);
+    std::fs::create_dir_all(&work_dir)?;
+    let archive_path = work_dir.join(&asset.name);
+    let result = feed.download(asset, &archive_path)
+        .and_then(|_| verify_archive(feed, checksum_asset, &archive_path, &work_dir.join(&checksum_asset.name)))
+        .and_then(|_| extract_archive(&archive_path, &work_dir.join("dist"), false))
+        .and_then(|_| find_binary(&work_dir.join("dist"), binary_path))
+        .and_then(|new_binary| replace_binary(binary_path, &new_binary));
+    let _ = std::fs::remove_dir_all(&work_dir);
+    result.map(|_| Some(release.version().to_string()))
+}
+
+/// download the checksum asset, `<sha256>  <file name>` or `<sha256>`, and verify the archive with it
+fn verify_archive(feed: &dyn ReleaseFeed, checksum_asset: &ReleaseAsset, archive_path: &Path, checksum_path: &Path) -> wukong::Result<()> {
+    feed.download(checksum_asset, checksum_path)?;
+    let text = std::fs::read_to_string(checksum_path)?;
+    let expected = text.split_whitespace().next()
+        .ok_or_else(|| Error::Archive(format!("Empty checksum file: {}", checksum_asset.name)))?;
+    verify_sha256(archive_path, expected)
+}
+
+/// binary with the same file name as the current binary in extracted archive
+fn find_binary(dist_dir: &Path, binary_path: &Path) -> wukong::Result<PathBuf> {
+    let file_name = binary_path.file_name().unwrap_or_default();
+    walkdir::WalkDir::new(dist_dir).into_iter()
+        .flatten()
+        .find(|entry| entry.file_type().is_file() && entry.file_name() == file_name)
+        .map(|entry| entry.into_path())
+        .ok_or_else(|| Error::Archive(format!("{} not found in release archive", file_name.to_string_lossy())))
+}
+
+/// copy new binary next to the binary, then rename it over the binary
+fn replace_binary(binary_path: &Path, new_binary: &Path) -> wukong::Result<()> {
+    let file_name = binary_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let temp_path = binary_path.with_file_name(format!(".{}.selfupdate-{}", file_name, std::process::id()));
+    std::fs::copy(new_binary, &temp_path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&temp_path, std::fs::Permissions::from_mode(0o755))?;
+    }
+    // running binary can not be replaced on Windows, but can be renamed
+    #[cfg(windows)]
+    {
+        let old_path = binary_path.with_file_name(format!("{}.old", file_name));
+        let _ = std::fs::remove_file(&old_path);
+        std::fs::rename(binary_path, &old_path)?;
+    }
+    if let Err(e) = std::fs::rename(&temp_path, binary_path) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// target triple of wukong release archives
+fn current_target() -> Option<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") => Some("x86_64-unknown-linux-gnu"),
+        ("macos", "x86_64") => Some("x86_64-apple-darwin"),
+        ("macos", "aarch64") => Some("aarch64-apple-darwin"),
+        ("windows", "x86_64") => Some("x86_64-pc-windows-msvc"),
+        _ => None,
+    }
+}
+
+pub fn build_selfupdate_command() -> Command {
+    Command::new("selfupdate")
+        .about("upgrade the sdk binary to the latest wukong release.")
+        .long_about(r#"Check the latest wukong release on GitHub, and replace the sdk binary if a newer version is available. Use --force to reinstall the latest release."#)
+        .arg(
+            Arg::new("force")
+                .long("force")
+                .help("Reinstall the latest release even if it is not newer.")
+                .num_args(0)
+                .required(false)
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+
+    /// release feed with a local archive and its checksum
+    struct LocalReleases {
+        tag: String,
+        archive: PathBuf,
+        checksum: String,
+    }
+
+    impl ReleaseFeed for LocalReleases {
+        fn latest_release(&self) -> wukong::Result<Release> {
+            let asset_name = format!("wukong-{}.tar.gz", current_target().unwrap());
+            Ok(Release {
+                tag: self.tag.clone(),
+                assets: vec![
+                    ReleaseAsset { name: format!("{}.sha256", asset_name), url: "".to_owned() },
+                    ReleaseAsset { name: asset_name, url: self.archive.to_string_lossy().to_string() },
+                ],
+            })
+        }
+
+        fn download(&self, asset: &ReleaseAsset, target_file_path: &Path) -> wukong::Result<()> {
+            if asset.name.ends_with(".sha256") {
+                std::fs::write(target_file_path, format!("{}  {}\n", self.checksum, asset.name.trim_end_matches(".sha256")))?;
+            } else {
+                std::fs::copy(&asset.url, target_file_path)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn sha256_hex(path: &Path) -> String {
+        use sha2::{Digest, Sha256};
+        format!("{:x}", Sha256::digest(std::fs::read(path).unwrap()))
+    }
+
+    /// archive with `wukong-dist/sdk` of `new-build`
+    fn build_release_archive(archive: &Path) {
+        let mut builder = tar::Builder::new(GzEncoder::new(std::fs::File::create(archive).unwrap(), flate2::Compression::default()));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(9);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder.append_data(&mut header, "wukong-dist/sdk", "new-build".as_bytes()).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn test_self_update() {
+        if current_target().is_none() {
+            return;
+        }
+        let dir = std::env::temp_dir().join("wukong-sdkman-selfupdate");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let archive = dir.join("release.tar.gz");
+        build_release_archive(&archive);
+        let binary_path = dir.join("sdk");
+        std::fs::write(&binary_path, "old-build").unwrap();
+        let feed = LocalReleases { tag: "v0.3.3".to_owned(), checksum: sha256_hex(&archive), archive };
+        assert!(check_update(&feed, "0.3.3").unwrap().is_none());
+        assert_eq!(self_update(&feed, "0.3.3", &binary_path, false).unwrap(), None);
+        assert_eq!(std::fs::read_to_string(&binary_path).unwrap(), "old-build");
+        assert_eq!(self_update(&feed, "0.3.2", &binary_path, false).unwrap(), Some("0.3.3".to_owned()));
+        assert_eq!(std::fs::read_to_string(&binary_path).unwrap(), "new-build");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_self_update_bad_checksum() {
+        if current_target().is_none() {
+            return;
+        }
+        let dir = std::env::temp_dir().join("wukong-sdkman-selfupdate-checksum");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let archive = dir.join("release.tar.gz");
+        build_release_archive(&archive);
+        let binary_path = dir.join("sdk");
+        std::fs::write(&binary_path, "old-build").unwrap();
+        let feed = LocalReleases { tag: "v0.3.3".to_owned(), archive, checksum: "0".repeat(64) };
+        let result = self_update(&feed, "0.3.2", &binary_path, false);
+        assert!(matches!(result, Err(Error::Checksum { .. })));
+        assert_eq!(std::fs::read_to_string(&binary_path).unwrap(), "old-build");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,285 @@
+//! Shell-specific syntax for environment changes printed by `sdk init`, `sdk use` and `sdk env`.
+//!
+//! The shell is from `sdk init --shell <name>`, then `SDKMAN_SHELL` exported by `sdk init`, then `$SHELL`.
+//! PATH is printed as a complete value, and old entries of the same candidate are removed,
+//! so repeated `sdk use` calls do not grow PATH.
+
+use crate::sdkman_cli::sdkman_home;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    /// statements are printed as a record for `load-env`
+    Nushell,
+    /// PowerShell
+    Pwsh,
+}
+
+impl Shell {
+    pub fn from_name(name: &str) -> Option<Shell> {
+        match name.to_lowercase().as_str() {
+            "bash" | "sh" => Some(Shell::Bash),
+            "zsh" => Some(Shell::Zsh),
+            "fish" => Some(Shell::Fish),
+            "nu" | "nushell" => Some(Shell::Nushell),
+            "pwsh" | "powershell" => Some(Shell::Pwsh),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+            Shell::Nushell => "nushell",
+            Shell::Pwsh => "powershell",
+        }
+    }
+
+    /// shell from `SDKMAN_SHELL`, then `$SHELL`, and bash by default
+    pub fn current() -> Shell {
+        if let Ok(name) = std::env::var("SDKMAN_SHELL") && let Some(shell) = Shell::from_name(&name) {
+            return shell;
+        }
+        std::env::var("SHELL").ok()
+            .and_then(|shell_path| Path::new(&shell_path).file_name().and_then(|name| Shell::from_name(&name.to_string_lossy())))
+            .unwrap_or(Shell::Bash)
+    }
+
+    /// command line to apply output of `sdk <args>` in current shell
+    pub fn eval_hint(&self, args: &str) -> String {
+        match self {
+            Shell::Bash | Shell::Zsh => format!("eval \"$(sdk {})\"", args),
+            Shell::Fish => format!("sdk {} | source", args),
+            Shell::Nushell => format!("sdk {} | from nuon | load-env", args),
+            Shell::Pwsh => format!("sdk {} | Out-String | Invoke-Expression", args),
+        }
+    }
+
+    fn quote(&self, value: &str) -> String {
+        match self {
+            Shell::Bash | Shell::Zsh => format!("'{}'", value.replace('\'', r"'\''")),
+            Shell::Fish => format!("'{}'", value.replace('\\', r"\\").replace('\'', r"\'")),
+            Shell::Nushell => format!("\"{}\"", value.replace('\\', r"\\").replace('"', "\\\"")),
+            Shell::Pwsh => format!("'{}'", value.replace('\'', "''")),
+        }
+    }
+}
+
+/// environment variables and candidate paths to apply
+#[derive(Debug, Default)]
+pub struct EnvChanges {
+    /// name and value, `None` to unset
+    vars: Vec<(String, Option<String>)>,
+    /// candidate name and its bin directory
+    paths: Vec<(String, PathBuf)>,
+}
+
+impl EnvChanges {
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.vars.push((name.to_string(), Some(value.to_string())));
+    }
+
+    pub fn unset(&mut self, name: &str) {
+        self.vars.push((name.to_string(), None));
+    }
+
+    pub fn prepend_path(&mut self, candidate_name: &str, dir: &Path) {
+        self.paths.push((candidate_name.to_string(), dir.to_path_buf()));
+    }
+
+    /// statements with PATH merged into current `PATH`
+    pub fn render(&self, shell: Shell) -> Vec<String> {
+        let current_path = std::env::var("PATH").unwrap_or_default();
+        self.render_with_path(shell, &current_path, &sdkman_home().join("candidates"))
+    }
+
+    /// nushell script saved by `sdk init`, and PATH entries are prepended when the script runs at shell startup,
+    /// because a PATH merged while generating the script would be outdated
+    pub fn render_nushell_script(&self) -> Vec<String> {
+        let vars = EnvChanges { vars: self.vars.clone(), paths: vec![] };
+        let mut lines: Vec<String> = vars.render_with_path(Shell::Nushell, "", Path::new(""))
+            .into_iter()
+            .map(|record| format!("load-env {}", record))
+            .collect();
+        if !self.paths.is_empty() {
+            let mut entries: Vec<String> = vec![];
+            for (_, dir) in &self.paths {
+                let entry = Shell::Nushell.quote(&dir.to_string_lossy());
+                if !entries.contains(&entry) {
+                    entries.push(entry);
+                }
+            }
+            lines.push(format!("$env.PATH = ($env.PATH | split row (char esep) | prepend [{}] | uniq)", entries.join(", ")));
+        }
+        lines
+    }
+
+    fn render_with_path(&self, shell: Shell, current_path: &str, candidates_dir: &Path) -> Vec<String> {
+        let mut vars = self.vars.clone();
+        if !self.paths.is_empty() {
+            let path_entries = merge_path(current_path, &self.paths, candidates_dir);
+            vars.push(("PATH".to_owned(), Some(path_entries.join(path_separator()))));
+        }
+        if shell == Shell::Nushell {
+            let fields: Vec<String> = vars.iter()
+                .map(|(name, value)| match value {
+                    // nushell PATH is a list
+                    Some(value) if name == "PATH" => {
+                        let entries: Vec<String> = value.split(path_separator()).map(|entry| shell.quote(entry)).collect();
+                        format!("{}: [{}]", name, entries.join(", "))
+                    }
+                    Some(value) => format!("{}: {}", name, shell.quote(value)),
+                    None => format!("{}: null", name),
+                })
+                .collect();
+            return vec![format!("{{{}}}", fields.join(", "))];
+        }
+        vars.iter()
+            .map(|(name, value)| match (shell, value) {
+                (Shell::Bash | Shell::Zsh, Some(value)) => format!("export {}={}", name, shell.quote(value)),
+                (Shell::Bash | Shell::Zsh, None) => format!("unset {}", name),
+                // fish PATH is a list
+                (Shell::Fish, Some(value)) if name == "PATH" => {
+                    let entries: Vec<String> = value.split(path_separator()).map(|entry| shell.quote(entry)).collect();
+                    format!("set -gx PATH {}", entries.join(" "))
+                }
+                (Shell::Fish, Some(value)) => format!("set -gx {} {}", name, shell.quote(value)),
+                (Shell::Fish, None) => format!("set -e {}", name),
+                (Shell::Pwsh, Some(value)) => format!("$env:{} = {}", name, shell.quote(value)),
+                (Shell::Pwsh, None) => format!("Remove-Item Env:\\{} -ErrorAction SilentlyContinue", name),
+                (Shell::Nushell, _) => unreachable!(),
+            })
+            .collect()
+    }
+}
+
+fn path_separator() -> &'static str {
+    if cfg!(windows) { ";" } else { ":" }
+}
+
+/// new candidate paths first, then current entries without old paths of the same candidates, duplicates removed
+fn merge_path(current_path: &str, paths: &[(String, PathBuf)], candidates_dir: &Path) -> Vec<String> {
+    let mut entries: Vec<String> = vec![];
+    for (_, dir) in paths {
+        let dir = dir.to_string_lossy().to_string();
+        if !entries.contains(&dir) {
+            entries.push(dir);
+        }
+    }
+    for entry in current_path.split(path_separator()).filter(|entry| !entry.is_empty()) {
+        let is_replaced = paths.iter()
+            .any(|(candidate_name, _)| Path::new(entry).starts_with(candidates_dir.join(candidate_name)));
+        if !is_replaced && !entries.iter().any(|existing| existing == entry) {
+            entries.push(entry.to_string());
+        }
+    }
+    entries
+}
+
+/// `sdkman_auto_env` hook to run `sdk env auto` on directory change
+pub fn auto_env_hook(shell: Shell) -> &'static str {
+    match shell {
+        Shell::Bash => r#"__sdkman_auto_env() {
+  if [ "$SDKMAN_OLD_PWD" != "$PWD" ]; then
+    SDKMAN_OLD_PWD="$PWD"
+    eval "$(sdk env auto)"
+  fi
+}
+case ";${PROMPT_COMMAND:-};" in
+  *";__sdkman_auto_env;"*) ;;
+  *) PROMPT_COMMAND="__sdkman_auto_env${PROMPT_COMMAND:+;$PROMPT_COMMAND}" ;;
+esac"#,
+        Shell::Zsh => r#"__sdkman_auto_env() {
+  eval "$(sdk env auto)"
+}
+autoload -U add-zsh-hook
+add-zsh-hook chpwd __sdkman_auto_env
+__sdkman_auto_env"#,
+        Shell::Fish => r#"function __sdkman_auto_env --on-variable PWD
+  sdk env auto | source
+end
+__sdkman_auto_env"#,
+        Shell::Nushell => r#"$env.config = ($env.config | upsert hooks.env_change.PWD {|config|
+  ($config | get -i hooks.env_change.PWD | default []) | append {|before, after| sdk env auto | from nuon | load-env }
+})"#,
+        Shell::Pwsh => r#"$global:__sdkman_old_pwd = $null
+$global:__sdkman_prompt = $function:prompt
+function global:prompt {
+  if ($global:__sdkman_old_pwd -ne $PWD.Path) {
+    $global:__sdkman_old_pwd = $PWD.Path
+    sdk env auto | Out-String | Invoke-Expression
+  }
+  & $global:__sdkman_prompt
+}"#,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn java_changes(candidates_dir: &Path) -> EnvChanges {
+        let java_home = candidates_dir.join("java").join("21.0.3-tem");
+        let mut changes = EnvChanges::default();
+        changes.set("JAVA_HOME", java_home.to_str().unwrap());
+        changes.unset("GRAALVM_HOME");
+        changes.prepend_path("java", &java_home.join("bin"));
+        changes
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_render() {
+        let candidates_dir = Path::new("/home/demo/.sdkman/candidates");
+        let changes = java_changes(candidates_dir);
+        let current_path = "/home/demo/.sdkman/candidates/java/17.0.11-tem/bin:/usr/bin:/home/demo/.sdkman/candidates/maven/current/bin:/usr/bin";
+        let java_bin = "/home/demo/.sdkman/candidates/java/21.0.3-tem/bin";
+        let path = format!("{}:/usr/bin:/home/demo/.sdkman/candidates/maven/current/bin", java_bin);
+        assert_eq!(changes.render_with_path(Shell::Bash, current_path, candidates_dir), vec![
+            "export JAVA_HOME='/home/demo/.sdkman/candidates/java/21.0.3-tem'".to_owned(),
+            "unset GRAALVM_HOME".to_owned(),
+            format!("export PATH='{}'", path),
+        ]);
+        assert_eq!(changes.render_with_path(Shell::Fish, current_path, candidates_dir), vec![
+            "set -gx JAVA_HOME '/home/demo/.sdkman/candidates/java/21.0.3-tem'".to_owned(),
+            "set -e GRAALVM_HOME".to_owned(),
+            format!("set -gx PATH '{}' '/usr/bin' '/home/demo/.sdkman/candidates/maven/current/bin'", java_bin),
+        ]);
+        assert_eq!(changes.render_with_path(Shell::Pwsh, current_path, candidates_dir), vec![
+            "$env:JAVA_HOME = '/home/demo/.sdkman/candidates/java/21.0.3-tem'".to_owned(),
+            "Remove-Item Env:\\GRAALVM_HOME -ErrorAction SilentlyContinue".to_owned(),
+            format!("$env:PATH = '{}'", path),
+        ]);
+        assert_eq!(changes.render_with_path(Shell::Nushell, current_path, candidates_dir), vec![
+            format!("{{JAVA_HOME: \"/home/demo/.sdkman/candidates/java/21.0.3-tem\", GRAALVM_HOME: null, PATH: [\"{}\", \"/usr/bin\", \"/home/demo/.sdkman/candidates/maven/current/bin\"]}}", java_bin),
+        ]);
+        assert_eq!(changes.render_nushell_script(), vec![
+            "load-env {JAVA_HOME: \"/home/demo/.sdkman/candidates/java/21.0.3-tem\", GRAALVM_HOME: null}".to_owned(),
+            format!("$env.PATH = ($env.PATH | split row (char esep) | prepend [\"{}\"] | uniq)", java_bin),
+        ]);
+        // repeated use does not grow PATH
+        let bash_path = format!("{}:/usr/bin", java_bin);
+        assert_eq!(changes.render_with_path(Shell::Bash, &bash_path, candidates_dir).last().unwrap(), &format!("export PATH='{}'", bash_path));
+    }
+
+    #[test]
+    fn test_shell_names() {
+        assert_eq!(Shell::from_name("pwsh"), Some(Shell::Pwsh));
+        assert_eq!(Shell::from_name("nu"), Some(Shell::Nushell));
+        assert_eq!(Shell::from_name("tcsh"), None);
+        assert_eq!(Shell::Bash.quote("it's"), r"'it'\''s'");
+        assert_eq!(Shell::Pwsh.quote("it's"), "'it''s'");
+    }
+
+    #[test]
+    fn test_eval_hint() {
+        assert_eq!(Shell::Bash.eval_hint("use java 21"), "eval \"$(sdk use java 21)\"");
+        assert_eq!(Shell::Zsh.eval_hint("env clear"), "eval \"$(sdk env clear)\"");
+        assert_eq!(Shell::Fish.eval_hint("use java 21"), "sdk use java 21 | source");
+    }
+}
@@ -1,22 +1,59 @@
 use crate::sdkman_cli::find_candidate_home;
+use colored::Colorize;
+use std::path::Path;
+use wukong::error::exit_with_error;
 
 pub fn manage_uninstall(uninstall_matches: &clap::ArgMatches) {
     let candidate_name = uninstall_matches.get_one::<String>("candidate").unwrap();
     let candidate_version = uninstall_matches.get_one::<String>("version").unwrap();
+    let force = uninstall_matches.get_flag("force");
     let candidate_home = find_candidate_home(candidate_name, candidate_version);
-    if candidate_home.exists() {
-        let candidate_current_link = candidate_home.parent().unwrap().join("current");
-        if candidate_current_link.exists()  && candidate_current_link.is_symlink() {
-            let link_target_path = candidate_current_link.read_link().unwrap();
-            if candidate_home == link_target_path {
-                symlink::remove_symlink_dir(&candidate_current_link).unwrap();
-            }
+    // broken links of local versions should be removable too
+    if !candidate_home.exists() && !candidate_home.is_symlink() {
+        eprintln!("{}@{} not installed, please install it first!", candidate_name, candidate_version);
+        return;
+    }
+    let candidate_dir = candidate_home.parent().unwrap();
+    if is_current_default(candidate_dir, candidate_version) {
+        if !force {
+            eprintln!("{}", format!("Stop! {} {} is the current default, use `sdk uninstall {} {} --force` to remove it.",
+                                    candidate_name, candidate_version, candidate_name, candidate_version).red());
+            return;
         }
-        std::fs::remove_dir_all(&candidate_home).unwrap();
+        eprintln!("{}", format!("Warning: removing the current default {} {}, no default version of {} is set afterwards.",
+                                candidate_name, candidate_version, candidate_name).yellow());
+    }
+    let local_version = candidate_home.is_symlink();
+    if let Err(e) = remove_candidate_version(candidate_dir, candidate_version) {
+        exit_with_error("sdk", &e);
+    }
+    if local_version {
+        println!("{}@{} unlinked, local installation kept", candidate_name, candidate_version);
+    } else {
         println!("{}@{} removed from {}", candidate_name, candidate_version, candidate_home.to_str().unwrap());
+    }
+}
+
+/// `current` link points to the version, absolute or relative link
+fn is_current_default(candidate_dir: &Path, candidate_version: &str) -> bool {
+    candidate_dir.join("current").read_link().ok()
+        .and_then(|link_target_path| link_target_path.file_name().map(|name| name == candidate_version))
+        .unwrap_or(false)
+}
+
+/// remove version directory and the `current` link to it. Local versions are symlinks to external installations,
+/// and only the link is removed
+fn remove_candidate_version(candidate_dir: &Path, candidate_version: &str) -> wukong::Result<()> {
+    if is_current_default(candidate_dir, candidate_version) {
+        symlink::remove_symlink_dir(candidate_dir.join("current"))?;
+    }
+    let candidate_home = candidate_dir.join(candidate_version);
+    if candidate_home.is_symlink() {
+        symlink::remove_symlink_dir(&candidate_home)?;
     } else {
-        eprintln!("{}@{} not installed, please install it first!", candidate_name, candidate_version);
+        std::fs::remove_dir_all(&candidate_home)?;
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -32,4 +69,26 @@ mod tests {
         let sdk_matches = sdkman_app.get_matches_from(&vec!["sdk", "uninstall", candidate_name, candidate_version]);
         manage_uninstall(sdk_matches.subcommand_matches("uninstall").unwrap());
     }
+
+    #[test]
+    fn test_remove_candidate_version() {
+        let dir = std::env::temp_dir().join("wukong-sdkman-uninstall");
+        let _ = std::fs::remove_dir_all(&dir);
+        let candidate_dir = dir.join("candidates").join("java");
+        let external_jdk = dir.join("jdk-17");
+        std::fs::create_dir_all(external_jdk.join("bin")).unwrap();
+        std::fs::create_dir_all(candidate_dir.join("21.0.3-tem").join("bin")).unwrap();
+        symlink::symlink_dir(&external_jdk, candidate_dir.join("17-local")).unwrap();
+        symlink::symlink_dir("21.0.3-tem", candidate_dir.join("current")).unwrap();
+        assert!(is_current_default(&candidate_dir, "21.0.3-tem"));
+        assert!(!is_current_default(&candidate_dir, "17-local"));
+        // local version is unlinked only
+        remove_candidate_version(&candidate_dir, "17-local").unwrap();
+        assert!(!candidate_dir.join("17-local").is_symlink());
+        assert!(external_jdk.join("bin").exists());
+        remove_candidate_version(&candidate_dir, "21.0.3-tem").unwrap();
+        assert!(!candidate_dir.join("21.0.3-tem").exists());
+        assert!(!candidate_dir.join("current").is_symlink());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -1,45 +1,304 @@
-use crate::sdkman_cli::{find_candidate_home, get_remote_candidate_default_version, list_candidate_names, read_sdkman_config};
+use crate::sdkman_cli::{find_candidate_home, get_installed_candidate_default_version, get_remote_candidate_default_version, list_candidate_names, list_installed_versions, match_installed_version, read_sdkman_config};
+use crate::sdkman_cli::config::{get_config_value, is_config_enabled};
 use crate::sdkman_cli::default::make_candidate_default;
 use crate::sdkman_cli::install::install_candidate;
+use crate::sdkman_cli::version_files::{has_version_files, read_project_versions, ProjectVersion};
+use prettytable::{format, row, Table};
+use std::io::Write;
+use std::path::PathBuf;
+use wukong::error::{report_error, Error, Result};
 
-pub fn manage_upgrade(upgrade_matches: &clap::ArgMatches) {
-    let mut accept_as_default = upgrade_matches.get_flag("yes");
-    if !accept_as_default {
-        accept_as_default = read_sdkman_config().contains_key("sdkman_auto_answer");
+/// depth to scan project roots for version files
+const PROJECT_SCAN_DEPTH: usize = 4;
+/// directories skipped while scanning project roots
+const IGNORED_DIRS: [&str; 3] = ["node_modules", "target", "build"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpgradeStatus {
+    UpToDate,
+    /// latest version is not installed
+    Outdated,
+    /// latest version is installed, but not default
+    NotDefault,
+    /// latest version is not available
+    Unknown,
+}
+
+impl UpgradeStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            UpgradeStatus::UpToDate => "up to date",
+            UpgradeStatus::Outdated => "upgrade available",
+            UpgradeStatus::NotDefault => "installed, not default",
+            UpgradeStatus::Unknown => "unknown",
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        matches!(self, UpgradeStatus::Outdated | UpgradeStatus::NotDefault)
     }
-    if let Some(candidate_name) = upgrade_matches.get_one::<String>("candidate") {
-        upgrade_candidate(candidate_name, accept_as_default);
+}
+
+#[derive(Debug, Clone)]
+pub struct CandidateUpgrade {
+    pub candidate: String,
+    pub current: String,
+    pub latest: String,
+    pub status: UpgradeStatus,
+}
+
+pub fn upgrade_status(current: &str, latest: &str, latest_installed: bool) -> UpgradeStatus {
+    if latest.is_empty() {
+        UpgradeStatus::Unknown
+    } else if current == latest {
+        UpgradeStatus::UpToDate
+    } else if latest_installed {
+        UpgradeStatus::NotDefault
     } else {
-        let candidate_names = list_candidate_names();
-        for candidate_name in candidate_names {
-            upgrade_candidate(&candidate_name, accept_as_default);
-        }
+        UpgradeStatus::Outdated
     }
 }
 
-fn upgrade_candidate(candidate_name: &str, accept_as_default: bool) {
-    let default_remote_version = get_remote_candidate_default_version(candidate_name);
-    if default_remote_version == "" {
-        eprintln!("Failed to find default version for : {}", candidate_name);
+pub fn manage_upgrade(upgrade_matches: &clap::ArgMatches) {
+    let config = read_sdkman_config();
+    let mut auto_answer = upgrade_matches.get_flag("yes");
+    if !auto_answer {
+        auto_answer = is_config_enabled(&config, "sdkman_auto_answer");
+    }
+    let candidate_names = match upgrade_matches.get_one::<String>("candidate") {
+        Some(candidate_name) => vec![candidate_name.clone()],
+        None => list_candidate_names(),
+    };
+    let upgrades: Vec<CandidateUpgrade> = candidate_names.iter().map(|candidate_name| check_candidate(candidate_name)).collect();
+    print_upgrade_table(&upgrades);
+    let pending: Vec<&CandidateUpgrade> = upgrades.iter().filter(|upgrade| upgrade.status.is_pending()).collect();
+    let mut prune_list: Vec<(String, String)> = vec![];
+    if upgrade_matches.get_flag("prune") {
+        let project_roots = match upgrade_matches.get_many::<String>("project-root") {
+            Some(roots) => roots.map(PathBuf::from).collect(),
+            None => configured_project_roots(&get_config_value(&config, "sdkman_project_roots")),
+        };
+        let references = referenced_versions(&project_roots);
+        // versions of candidates with unknown remote state are kept
+        for upgrade in upgrades.iter().filter(|upgrade| upgrade.status != UpgradeStatus::Unknown) {
+            // default version after upgrade
+            let current = if upgrade.status.is_pending() { &upgrade.latest } else { &upgrade.current };
+            let installed_versions: Vec<String> = list_installed_versions(&upgrade.candidate).into_iter()
+                .filter(|version| !find_candidate_home(&upgrade.candidate, version).is_symlink())
+                .collect();
+            for version in prune_versions(&upgrade.candidate, &installed_versions, current, &references) {
+                prune_list.push((upgrade.candidate.clone(), version));
+            }
+        }
+    }
+    if pending.is_empty() && prune_list.is_empty() {
+        println!("All candidates are up to date.");
         return;
     }
+    if !prune_list.is_empty() {
+        println!("Versions to prune:");
+        for (candidate_name, version) in &prune_list {
+            println!("  {} {}", candidate_name, version);
+        }
+    }
+    if !auto_answer && !confirm(&format!("Upgrade {} candidate(s) and prune {} version(s)? (Y/n): ", pending.len(), prune_list.len())) {
+        println!("Upgrade cancelled.");
+        return;
+    }
+    let prune_list = upgrade_candidates(&pending, prune_list, |upgrade| upgrade_candidate(&upgrade.candidate, true));
+    for (candidate_name, version) in prune_list {
+        let candidate_home = find_candidate_home(&candidate_name, &version);
+        match std::fs::remove_dir_all(&candidate_home) {
+            Ok(_) => println!("Pruned {} {}", candidate_name, version),
+            Err(e) => report_error("sdk", &e.into()),
+        }
+    }
+}
+
+fn check_candidate(candidate_name: &str) -> CandidateUpgrade {
+    let current = get_installed_candidate_default_version(candidate_name);
+    let latest = get_remote_candidate_default_version(candidate_name).unwrap_or_else(|e| {
+        report_error("sdk", &e);
+        "".to_owned()
+    });
+    let latest_installed = !latest.is_empty() && find_candidate_home(candidate_name, &latest).exists();
+    CandidateUpgrade {
+        candidate: candidate_name.to_string(),
+        status: upgrade_status(&current, &latest, latest_installed),
+        current,
+        latest,
+    }
+}
+
+fn print_upgrade_table(upgrades: &[CandidateUpgrade]) {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row!["Candidate", "Current", "Latest", "Status"]);
+    for upgrade in upgrades {
+        table.add_row(row![upgrade.candidate, upgrade.current, upgrade.latest, upgrade.status.label()]);
+    }
+    table.printstd();
+}
+
+/// yes by default
+fn confirm(question: &str) -> bool {
+    print!("{}", question);
+    std::io::stdout().flush().unwrap();
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "" | "y" | "yes")
+}
+
+/// project roots from `sdkman_project_roots`(comma separated) in config, and current directory by default
+fn configured_project_roots(project_roots: &str) -> Vec<PathBuf> {
+    if project_roots.trim().is_empty() {
+        return vec![std::env::current_dir().unwrap()];
+    }
+    project_roots.split(',')
+        .map(|root| root.trim())
+        .filter(|root| !root.is_empty())
+        .map(|root| match root.strip_prefix("~/") {
+            Some(relative_path) => dirs::home_dir().unwrap().join(relative_path),
+            None => PathBuf::from(root),
+        })
+        .collect()
+}
+
+/// versions from version files of projects under project roots
+fn referenced_versions(project_roots: &[PathBuf]) -> Vec<ProjectVersion> {
+    let mut references = vec![];
+    for project_root in project_roots {
+        let walker = walkdir::WalkDir::new(project_root).max_depth(PROJECT_SCAN_DEPTH).into_iter()
+            .filter_entry(|entry| {
+                let name = entry.file_name().to_string_lossy();
+                entry.depth() == 0 || (!name.starts_with('.') && !IGNORED_DIRS.contains(&name.as_ref()))
+            });
+        for entry in walker.flatten() {
+            if entry.file_type().is_dir() && has_version_files(entry.path()) {
+                references.extend(read_project_versions(entry.path()));
+            }
+        }
+    }
+    references
+}
+
+/// installed versions which are neither current nor referenced by projects, and nothing without a current version
+pub fn prune_versions(candidate_name: &str, installed_versions: &[String], current: &str, references: &[ProjectVersion]) -> Vec<String> {
+    if current.is_empty() {
+        return vec![];
+    }
+    let referenced: Vec<String> = references.iter()
+        .filter(|reference| reference.candidate == candidate_name)
+        .filter_map(|reference| match_installed_version(candidate_name, installed_versions, &reference.version, None))
+        .collect();
+    installed_versions.iter()
+        .filter(|version| version.as_str() != current && !referenced.contains(version))
+        .cloned()
+        .collect()
+}
+
+/// upgrade pending candidates, and returns versions to prune. The prune list assumes the latest version is default,
+/// so versions of candidates failed to upgrade are kept, otherwise the `current` link would be left dangling.
+pub fn upgrade_candidates<F>(pending: &[&CandidateUpgrade], prune_list: Vec<(String, String)>, mut upgrade: F) -> Vec<(String, String)>
+where
+    F: FnMut(&CandidateUpgrade) -> Result<()>,
+{
+    let mut failed_candidates: Vec<&str> = vec![];
+    for candidate_upgrade in pending {
+        if let Err(e) = upgrade(candidate_upgrade) {
+            report_error("sdk", &e);
+            failed_candidates.push(&candidate_upgrade.candidate);
+        }
+    }
+    prune_list.into_iter()
+        .filter(|(candidate_name, _)| !failed_candidates.contains(&candidate_name.as_str()))
+        .collect()
+}
+
+fn upgrade_candidate(candidate_name: &str, accept_as_default: bool) -> Result<()> {
+    let default_remote_version = get_remote_candidate_default_version(candidate_name)?;
+    if default_remote_version.is_empty() {
+        return Err(Error::NotFound(format!("default version of {}", candidate_name)));
+    }
     let candidate_home = find_candidate_home(candidate_name, &default_remote_version);
     if !candidate_home.exists() {
         println!("Begin to upgrade {} to {}", candidate_name, default_remote_version);
-        install_candidate(candidate_name, &default_remote_version);
-        if accept_as_default {
-            make_candidate_default(candidate_name, &default_remote_version);
+        install_candidate(candidate_name, &default_remote_version)?;
+        if !candidate_home.exists() {
+            return Err(Error::NotFound(format!("{} {} after installation", candidate_name, default_remote_version)));
         }
     }
+    if accept_as_default {
+        make_candidate_default(candidate_name, &default_remote_version);
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sdkman_cli::version_files::VersionFile;
 
     #[test]
     fn test_upgrade_candidate() {
         let candidate_name = "ant";
-        upgrade_candidate(candidate_name, true);
+        let _ = upgrade_candidate(candidate_name, true);
+    }
+
+    #[test]
+    fn test_upgrade_candidates_install_failed() {
+        let upgrade = |candidate: &str, current: &str, latest: &str| CandidateUpgrade {
+            candidate: candidate.to_owned(),
+            current: current.to_owned(),
+            latest: latest.to_owned(),
+            status: UpgradeStatus::Outdated,
+        };
+        let java_upgrade = upgrade("java", "21.0.2-tem", "21.0.3-tem");
+        let maven_upgrade = upgrade("maven", "3.9.6", "3.9.9");
+        let prune_list = vec![
+            ("java".to_owned(), "21.0.2-tem".to_owned()),
+            ("maven".to_owned(), "3.9.6".to_owned()),
+            ("gradle".to_owned(), "8.5".to_owned()),
+        ];
+        let mut upgraded = vec![];
+        let prune_list = upgrade_candidates(&[&java_upgrade, &maven_upgrade], prune_list, |candidate_upgrade| {
+            upgraded.push(candidate_upgrade.candidate.clone());
+            if candidate_upgrade.candidate == "java" {
+                Err(Error::Network("connection reset".to_owned()))
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(upgraded, ["java", "maven"]);
+        // old default of java is kept after the failed install
+        assert_eq!(prune_list, vec![("maven".to_owned(), "3.9.6".to_owned()), ("gradle".to_owned(), "8.5".to_owned())]);
+    }
+
+    #[test]
+    fn test_upgrade_status() {
+        assert_eq!(upgrade_status("21.0.3-tem", "21.0.3-tem", true), UpgradeStatus::UpToDate);
+        assert_eq!(upgrade_status("21.0.2-tem", "21.0.3-tem", false), UpgradeStatus::Outdated);
+        assert_eq!(upgrade_status("", "21.0.3-tem", true), UpgradeStatus::NotDefault);
+        assert_eq!(upgrade_status("21.0.2-tem", "", false), UpgradeStatus::Unknown);
+    }
+
+    #[test]
+    fn test_prune_versions() {
+        let installed_versions: Vec<String> = ["21.0.3-tem", "21.0.2-tem", "17.0.11-tem", "17.0.10-zulu", "11.0.22-tem"]
+            .iter().map(|version| version.to_string()).collect();
+        let references = vec![
+            ProjectVersion { candidate: "java".to_owned(), version: "17".to_owned(), source: VersionFile::JavaVersion },
+            ProjectVersion { candidate: "java".to_owned(), version: "17.0.10-zulu".to_owned(), source: VersionFile::Sdkmanrc },
+            ProjectVersion { candidate: "maven".to_owned(), version: "11.0.22-tem".to_owned(), source: VersionFile::Sdkmanrc },
+        ];
+        assert_eq!(prune_versions("java", &installed_versions, "21.0.3-tem", &references), vec!["21.0.2-tem", "11.0.22-tem"]);
+    }
+
+    #[test]
+    fn test_prune_versions_without_current() {
+        let installed_versions: Vec<String> = ["21.0.3-tem", "17.0.11-tem"].iter().map(|version| version.to_string()).collect();
+        assert!(prune_versions("java", &installed_versions, "", &[]).is_empty());
     }
 }
@@ -1,24 +1,30 @@
 use std::path::Path;
-use crate::sdkman_cli::{find_candidate_home, find_java_home, find_java_version};
+use crate::sdkman_cli::{find_candidate_home, find_installed_version, find_remote_version};
 use crate::sdkman_cli::install::install_candidate;
+use crate::sdkman_cli::shell::{EnvChanges, Shell};
+use wukong::error::exit_with_error;
+use wukong::Error;
 
 pub fn manage_use(use_matches: &clap::ArgMatches) {
     let candidate_name = use_matches.get_one::<String>("candidate").unwrap();
-    let mut candidate_version = use_matches.get_one::<String>("version").unwrap().to_string();
-    // find java home by major version
-    if candidate_name == "java" && candidate_version.parse::<u32>().is_ok() {
-        if let Some(java_home) = find_java_home(&candidate_version) {
-            use_candidate(candidate_name, &java_home);
-            return;
-        } else {
-            let java_version = find_java_version(&candidate_version).unwrap();
-            candidate_version = java_version;
+    let version = use_matches.get_one::<String>("version").unwrap();
+    let vendor = use_matches.get_one::<String>("vendor").map(|vendor| vendor.as_str());
+    // installed version is preferred for version query, such as `21` or `3.9`
+    let candidate_version = match find_installed_version(candidate_name, version, vendor) {
+        Some(installed_version) => installed_version,
+        None => {
+            let remote_version = match find_remote_version(candidate_name, version, vendor) {
+                Ok(Some(remote_version)) => remote_version,
+                Ok(None) => exit_with_error("sdk", &Error::NotFound(format!("{} {}", candidate_name, version))),
+                Err(e) => exit_with_error("sdk", &e),
+            };
+            if let Err(e) = install_candidate(candidate_name, &remote_version) {
+                exit_with_error("sdk", &e);
+            }
+            remote_version
         }
-    }
+    };
     let candidate_home = find_candidate_home(candidate_name, &candidate_version);
-    if !candidate_home.exists() {
-        install_candidate(candidate_name, &candidate_version);
-    }
     if candidate_home.exists() {
         use_candidate(candidate_name, &candidate_home);
     } else {
@@ -27,23 +33,38 @@ pub fn manage_use(use_matches: &clap::ArgMatches) {
 }
 
 pub fn use_candidate(candidate_name: &str, candidate_home_path: &Path) {
-    let candidate_home_name = format!("{}_HOME", candidate_name.to_uppercase());
+    let candidate_version = candidate_home_path.file_name().unwrap().to_str().unwrap();
+    let shell = Shell::current();
+    let mut changes = EnvChanges::default();
+    candidate_env(&mut changes, candidate_name, candidate_home_path);
+    for statement in changes.render(shell) {
+        println!("{}", statement);
+    }
+    println!("# Run this command to configure your shell:");
+    println!("# {}", shell.eval_hint(&format!("use {} {}", candidate_name, candidate_version)));
+}
+
+/// candidate home and path, `JENV_VERSION` is unset for Java default version from `current`
+pub fn candidate_env(changes: &mut EnvChanges, candidate_name: &str, candidate_home_path: &Path) {
     let candidate_home = candidate_home_path.to_str().unwrap();
     let candidate_version = candidate_home_path.file_name().unwrap().to_str().unwrap();
-    println!("export {}=\"{}\"", candidate_home_name, candidate_home);
+    changes.set(&format!("{}_HOME", candidate_name.to_uppercase()), candidate_home);
     if candidate_name == "java" {
-        println!("export JENV_VERSION={}", candidate_version);
+        if candidate_version == "current" {
+            changes.unset("JENV_VERSION");
+        } else {
+            changes.set("JENV_VERSION", candidate_version);
+        }
         if candidate_home.contains("graal") {
-            println!("export GRAALVM_HOME=\"{}\"", candidate_home);
+            changes.set("GRAALVM_HOME", candidate_home);
         }
     }
-    if candidate_home_path.join("bin").exists() {
-        println!("export PATH=\"{}:$PATH\"", candidate_home_path.join("bin").to_str().unwrap());
+    let candidate_bin_path = candidate_home_path.join("bin");
+    if candidate_bin_path.exists() {
+        changes.prepend_path(candidate_name, &candidate_bin_path);
     } else {
-        println!("export PATH=\"{}/bin:$PATH\"", candidate_home);
+        changes.prepend_path(candidate_name, candidate_home_path);
     }
-    println!("# Run this command to configure your shell:");
-    println!("# eval $(sdk use {} {})", candidate_name, candidate_version);
 }
 
 #[cfg(test)]
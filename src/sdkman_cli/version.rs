@@ -0,0 +1,33 @@
+use crate::sdkman_cli::clap_app::VERSION;
+use crate::sdkman_cli::{sdkman_home, SDKMAN_CANDIDATES_API};
+use clap::Command;
+
+pub fn manage_version(_version_matches: &clap::ArgMatches) {
+    println!("SDKMAN-rs (wukong): {}", VERSION);
+    println!("SDKMAN API: {} ({})", sdkman_api_version(SDKMAN_CANDIDATES_API), SDKMAN_CANDIDATES_API);
+    // version file of SDKMAN bash scripts, if installed by SDKMAN
+    if let Ok(script_version) = std::fs::read_to_string(sdkman_home().join("var").join("version")) {
+        println!("SDKMAN script: {}", script_version.trim());
+    }
+}
+
+/// API version from the last path segment of the API URL: `https://api.sdkman.io/2` to `2`
+fn sdkman_api_version(api_url: &str) -> &str {
+    api_url.trim_end_matches('/').rsplit('/').next().unwrap_or_default()
+}
+
+pub fn build_version_command() -> Command {
+    Command::new("version")
+        .about("display the versions of wukong and the SDKMAN API.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sdkman_api_version() {
+        assert_eq!(sdkman_api_version(SDKMAN_CANDIDATES_API), "2");
+        assert_eq!(sdkman_api_version("https://sdkman.example.com/api/3/"), "3");
+    }
+}
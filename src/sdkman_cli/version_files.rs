@@ -0,0 +1,254 @@
+//! Project version files, merged by precedence for each candidate:
+//!
+//! 1. `.sdkmanrc`: `java=21.0.2-tem`
+//! 2. `.tool-versions` of asdf/mise: `java temurin-21.0.2`
+//! 3. `.mise.toml`: Java entry of `[tools]`, such as `java = "temurin-21"`
+//! 4. `.java-version`: `21`, `21.0.2-tem` or `temurin-21.0.2`
+//! 5. `.mvn/jvm.config`: Java release hints, such as `-Dmaven.compiler.release=21`
+//!
+//! Java versions with vendor prefix, such as `temurin-21.0.2+13.0.LTS`, are converted to SDKMAN version queries: `21.0.2-tem`.
+
+use std::path::Path;
+use wukong::common::resolve_java_version_file;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionFile {
+    Sdkmanrc,
+    ToolVersions,
+    MiseToml,
+    JavaVersion,
+    MavenJvmConfig,
+}
+
+/// version files by precedence
+pub const VERSION_FILES: [VersionFile; 5] = [
+    VersionFile::Sdkmanrc,
+    VersionFile::ToolVersions,
+    VersionFile::MiseToml,
+    VersionFile::JavaVersion,
+    VersionFile::MavenJvmConfig,
+];
+
+/// asdf/mise Java vendor prefix and SDKMAN dist, longer prefixes first
+const JAVA_VENDORS: [(&str, &str); 13] = [
+    ("graalvm-community", "graalce"),
+    ("oracle-graalvm", "graal"),
+    ("temurin", "tem"),
+    ("zulu", "zulu"),
+    ("corretto", "amzn"),
+    ("liberica", "librca"),
+    ("microsoft", "ms"),
+    ("sapmachine", "sapmchn"),
+    ("semeru", "sem"),
+    ("dragonwell", "albba"),
+    ("mandrel", "mandrel"),
+    ("openjdk", "open"),
+    ("oracle", "oracle"),
+];
+
+/// Maven properties for Java release in `.mvn/jvm.config`
+const JVM_CONFIG_HINTS: [&str; 3] = ["-Dmaven.compiler.release=", "-Dmaven.compiler.target=", "-Djava.version="];
+
+impl VersionFile {
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            VersionFile::Sdkmanrc => ".sdkmanrc",
+            VersionFile::ToolVersions => ".tool-versions",
+            VersionFile::MiseToml => ".mise.toml",
+            VersionFile::JavaVersion => ".java-version",
+            VersionFile::MavenJvmConfig => ".mvn/jvm.config",
+        }
+    }
+
+    /// candidate name and version pairs in file order
+    pub fn parse(&self, text: &str) -> Vec<(String, String)> {
+        match self {
+            VersionFile::Sdkmanrc => text.lines().filter_map(parse_sdkmanrc_line).collect(),
+            VersionFile::ToolVersions => parse_tool_versions(text),
+            VersionFile::MiseToml => parse_mise_toml(text),
+            VersionFile::JavaVersion => text.lines()
+                .map(|line| line.trim())
+                .find(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(|version| vec![("java".to_owned(), to_sdkman_version("java", version))])
+                .unwrap_or_default(),
+            VersionFile::MavenJvmConfig => parse_jvm_config(text),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectVersion {
+    pub candidate: String,
+    /// version query, such as `21`, `21.0.2-tem` or `3.9`
+    pub version: String,
+    pub source: VersionFile,
+}
+
+/// versions of project directory, the first version file by precedence wins for each candidate
+pub fn read_project_versions(project_dir: &Path) -> Vec<ProjectVersion> {
+    let mut versions: Vec<ProjectVersion> = vec![];
+    for version_file in VERSION_FILES {
+        let Ok(text) = std::fs::read_to_string(project_dir.join(version_file.file_name())) else {
+            continue;
+        };
+        for (candidate, version) in version_file.parse(&text) {
+            if !versions.iter().any(|project_version| project_version.candidate == candidate) {
+                versions.push(ProjectVersion {
+                    candidate,
+                    version,
+                    source: version_file,
+                });
+            }
+        }
+    }
+    versions
+}
+
+/// versions of project directory, and Java from `.java-version` of parent directories if no version file of
+/// the directory has Java. `.java-version` is resolved the same way as jenv.
+pub fn read_project_versions_with_parents(project_dir: &Path) -> Vec<ProjectVersion> {
+    let mut versions = read_project_versions(project_dir);
+    if !versions.iter().any(|project_version| project_version.candidate == "java")
+        && let Some(version_file) = resolve_java_version_file(project_dir) {
+        versions.extend(VersionFile::JavaVersion.parse(&version_file.version).into_iter()
+            .map(|(candidate, version)| ProjectVersion { candidate, version, source: VersionFile::JavaVersion }));
+    }
+    versions
+}
+
+pub fn has_version_files(project_dir: &Path) -> bool {
+    VERSION_FILES.iter().any(|version_file| project_dir.join(version_file.file_name()).is_file())
+}
+
+/// `java=21.0.2-tem`, comments and blank lines are skipped
+pub fn parse_sdkmanrc_line(line: &str) -> Option<(String, String)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let (candidate_name, candidate_version) = line.split_once('=')?;
+    Some((candidate_name.trim().to_string(), candidate_version.trim().to_string()))
+}
+
+/// `java temurin-21.0.2 17.0.2`, the first version is used, and `system` is skipped
+fn parse_tool_versions(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.split('#').next().unwrap_or_default();
+            let mut parts = line.split_whitespace();
+            let tool = parts.next()?;
+            let version = parts.next().filter(|version| *version != "system")?;
+            Some((tool.to_string(), to_sdkman_version(tool, version)))
+        })
+        .collect()
+}
+
+/// `java = "21"`, `java = ["temurin-21", "17"]` or `java = { version = "21" }` in `[tools]`
+fn parse_mise_toml(text: &str) -> Vec<(String, String)> {
+    let Ok(table) = text.parse::<toml::Table>() else {
+        return vec![];
+    };
+    let version = match table.get("tools").and_then(|tools| tools.get("java")) {
+        Some(toml::Value::String(version)) => Some(version.as_str()),
+        Some(toml::Value::Array(versions)) => versions.first().and_then(|version| version.as_str()),
+        Some(toml::Value::Table(options)) => options.get("version").and_then(|version| version.as_str()),
+        _ => None,
+    };
+    version.map(|version| vec![("java".to_owned(), to_sdkman_version("java", version))])
+        .unwrap_or_default()
+}
+
+/// Java release from Maven properties, `1.8` is converted to `8`
+fn parse_jvm_config(text: &str) -> Vec<(String, String)> {
+    text.split_whitespace()
+        .find_map(|option| JVM_CONFIG_HINTS.iter().find_map(|hint| option.strip_prefix(hint)))
+        .map(|release| release.trim_start_matches("1.").to_string())
+        .filter(|release| release.parse::<u32>().is_ok())
+        .map(|release| vec![("java".to_owned(), release)])
+        .unwrap_or_default()
+}
+
+/// Java version with vendor prefix to SDKMAN version query: `temurin-21.0.2+13.0.LTS` to `21.0.2-tem`,
+/// `corretto-21.0.2.13.1` to `21.0.2-amzn`, and `zulu-21.32.17` to `21-zulu` because Zulu versions are not Java versions
+pub fn to_sdkman_version(candidate_name: &str, version: &str) -> String {
+    if candidate_name != "java" || version.starts_with(|c: char| c.is_ascii_digit()) {
+        return version.to_string();
+    }
+    for (prefix, dist) in JAVA_VENDORS {
+        // jenv style names have arch suffix: `temurin64-21.0.2`
+        let Some(rest) = version.strip_prefix(prefix).map(|rest| rest.trim_start_matches("64")) else {
+            continue;
+        };
+        let Some(rest) = rest.strip_prefix('-') else {
+            continue;
+        };
+        let numbers: Vec<&str> = rest.split(|c: char| !c.is_ascii_digit() && c != '.')
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .filter(|number| !number.is_empty())
+            .collect();
+        if numbers.is_empty() {
+            continue;
+        }
+        let java_version = if dist == "zulu" { numbers[0].to_string() } else { numbers.iter().take(3).cloned().collect::<Vec<&str>>().join(".") };
+        return format!("{}-{}", java_version, dist);
+    }
+    version.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_sdkman_version() {
+        assert_eq!(to_sdkman_version("java", "temurin-21.0.2+13.0.LTS"), "21.0.2-tem");
+        assert_eq!(to_sdkman_version("java", "temurin-21"), "21-tem");
+        assert_eq!(to_sdkman_version("java", "corretto-21.0.2.13.1"), "21.0.2-amzn");
+        assert_eq!(to_sdkman_version("java", "zulu-21.32.17"), "21-zulu");
+        assert_eq!(to_sdkman_version("java", "graalvm-community-21.0.2"), "21.0.2-graalce");
+        assert_eq!(to_sdkman_version("java", "temurin64-21.0.2"), "21.0.2-tem");
+        assert_eq!(to_sdkman_version("java", "21.0.2-tem"), "21.0.2-tem");
+        assert_eq!(to_sdkman_version("maven", "3.9.6"), "3.9.6");
+    }
+
+    #[test]
+    fn test_parse_version_files() {
+        assert_eq!(VersionFile::ToolVersions.parse("# tools\njava temurin-21.0.2 17\nmaven 3.9.6 # build\nnodejs system\n"),
+                   vec![("java".to_owned(), "21.0.2-tem".to_owned()), ("maven".to_owned(), "3.9.6".to_owned())]);
+        assert_eq!(VersionFile::MiseToml.parse("[tools]\njava = [\"corretto-21\", \"17\"]\nnode = \"20\"\n"),
+                   vec![("java".to_owned(), "21-amzn".to_owned())]);
+        assert_eq!(VersionFile::MiseToml.parse("[tools]\njava = { version = \"17\" }\n"),
+                   vec![("java".to_owned(), "17".to_owned())]);
+        assert_eq!(VersionFile::JavaVersion.parse("\n21\n"), vec![("java".to_owned(), "21".to_owned())]);
+        assert_eq!(VersionFile::MavenJvmConfig.parse("-Xmx2g -Dmaven.compiler.release=17\n"), vec![("java".to_owned(), "17".to_owned())]);
+        assert_eq!(VersionFile::MavenJvmConfig.parse("-Djava.version=1.8"), vec![("java".to_owned(), "8".to_owned())]);
+        assert!(VersionFile::MavenJvmConfig.parse("-Xmx2g").is_empty());
+    }
+
+    #[test]
+    fn test_precedence() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let project_dir = temp_dir.path();
+        std::fs::create_dir_all(project_dir.join(".mvn")).unwrap();
+        std::fs::write(project_dir.join(".mvn").join("jvm.config"), "-Dmaven.compiler.release=11").unwrap();
+        std::fs::write(project_dir.join(".java-version"), "17").unwrap();
+        std::fs::write(project_dir.join(".tool-versions"), "java temurin-21.0.2\ngradle 8.5\n").unwrap();
+        std::fs::write(project_dir.join(".sdkmanrc"), "gradle=8.7\n").unwrap();
+        let versions = read_project_versions(project_dir);
+        assert_eq!(versions, vec![
+            ProjectVersion { candidate: "gradle".to_owned(), version: "8.7".to_owned(), source: VersionFile::Sdkmanrc },
+            ProjectVersion { candidate: "java".to_owned(), version: "21.0.2-tem".to_owned(), source: VersionFile::ToolVersions },
+        ]);
+        std::fs::remove_file(project_dir.join(".tool-versions")).unwrap();
+        assert_eq!(read_project_versions(project_dir)[1].source, VersionFile::JavaVersion);
+        let module_dir = project_dir.join("module");
+        std::fs::create_dir_all(&module_dir).unwrap();
+        assert!(read_project_versions(&module_dir).is_empty());
+        assert_eq!(read_project_versions_with_parents(&module_dir)[0].version, "17");
+        std::fs::remove_file(project_dir.join(".java-version")).unwrap();
+        assert_eq!(read_project_versions(project_dir)[1].version, "11");
+        assert!(has_version_files(project_dir));
+    }
+}
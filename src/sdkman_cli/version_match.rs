@@ -0,0 +1,129 @@
+//! Version matching over SDKMAN version lists.
+//!
+//! Supported queries:
+//!
+//! - exact identifier: `21.0.2-zulu`, `23.ea.5-open` or `3.9.6`
+//! - version prefix by segments: `21`, `21.0` or `3.9`, the latest version is selected
+//! - prefix with dist: `21-zulu`
+//! - `latest`: the latest version
+//!
+//! Pre-release versions, such as `23.ea.5` or `4.0.0-rc-1`, are only selected if the query is a pre-release
+//! or there is no other match.
+
+use crate::sdkman_cli::models::CandidateVersion;
+use wukong::common::{compare_versions, version_segments};
+
+const PRE_RELEASE_MARKERS: [&str; 7] = ["ea", "rc", "alpha", "beta", "snapshot", "preview", "milestone"];
+
+pub fn is_pre_release(version: &str) -> bool {
+    version_segments(version).iter().any(|segment| {
+        let segment = segment.to_lowercase();
+        let is_milestone = segment.len() > 1 && segment.starts_with('m') && segment[1..].chars().all(|c| c.is_ascii_digit());
+        is_milestone || PRE_RELEASE_MARKERS.iter().any(|marker| {
+            segment.strip_prefix(marker).is_some_and(|rest| rest.chars().all(|c| c.is_ascii_digit()))
+        })
+    })
+}
+
+/// `21` matches `21`, `21.0.2` and `21-ea`, but not `210.1`
+fn matches_prefix(version: &str, prefix: &str) -> bool {
+    match version.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with(['.', '-', '_', '+']),
+        None => false,
+    }
+}
+
+/// best matched version for query. `vendor` is from `--vendor`, and `default_vendor` is used if there is
+/// no vendor in both query and `vendor`, such as `tem` for Java
+pub fn find_best_match<'a>(versions: &'a [CandidateVersion], query: &str, vendor: Option<&str>,
+                           default_vendor: Option<&str>) -> Option<&'a CandidateVersion> {
+    let query = query.trim();
+    if let Some(version) = versions.iter().find(|version| version.identifier == query)
+        && vendor.is_none_or(|vendor| version.matches_vendor(vendor)) {
+        return Some(version);
+    }
+    // `21-zulu`, only if `zulu` is a known dist
+    let (prefix, query_vendor) = match query.rsplit_once('-') {
+        Some((prefix, dist)) if versions.iter().any(|version| version.dist.as_deref() == Some(dist)) => (prefix, Some(dist)),
+        _ => (query, None),
+    };
+    let vendor = query_vendor.or(vendor);
+    let candidates = |vendor: Option<&str>| -> Vec<&'a CandidateVersion> {
+        versions.iter()
+            .filter(|version| vendor.is_none_or(|vendor| version.matches_vendor(vendor)))
+            .filter(|version| prefix == "latest" || matches_prefix(&version.version, prefix))
+            .collect()
+    };
+    let mut matched = candidates(vendor.or(default_vendor));
+    if matched.is_empty() && vendor.is_none() && default_vendor.is_some() {
+        matched = candidates(None);
+    }
+    let allow_pre_release = is_pre_release(prefix) || matched.iter().all(|version| is_pre_release(&version.version));
+    matched.into_iter()
+        .filter(|version| allow_pre_release || !is_pre_release(&version.version))
+        .max_by(|a, b| compare_versions(&a.version, &b.version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Ordering;
+
+    fn java_versions() -> Vec<CandidateVersion> {
+        ["23.ea.5-open", "22.0.1-open", "21.0.3-zulu", "21.0.2-zulu", "21.0.3-tem", "21.0.2-graalce", "17.0.11-tem", "8.0.412-tem"]
+            .iter()
+            .map(|identifier| CandidateVersion::from_identifier(true, identifier))
+            .collect()
+    }
+
+    fn maven_versions() -> Vec<CandidateVersion> {
+        ["4.0.0-rc-1", "3.9.10", "3.9.9", "3.8.8", "3.10.0"]
+            .iter()
+            .map(|identifier| CandidateVersion::from_identifier(false, identifier))
+            .collect()
+    }
+
+    fn best(versions: &[CandidateVersion], query: &str, vendor: Option<&str>, default_vendor: Option<&str>) -> Option<String> {
+        find_best_match(versions, query, vendor, default_vendor).map(|version| version.identifier.clone())
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("3.10.0", "3.9.10"), Ordering::Greater);
+        assert_eq!(compare_versions("23.0.1", "23.ea.5"), Ordering::Greater);
+        assert_eq!(compare_versions("4.0.0", "4.0.0-rc-1"), Ordering::Greater);
+        assert_eq!(compare_versions("3.9.0", "3.9.0.1"), Ordering::Less);
+        assert!(is_pre_release("23.ea.5"));
+        assert!(is_pre_release("4.0.0-M1"));
+        assert!(is_pre_release("4.0.0-rc-1"));
+        assert!(!is_pre_release("22.1.0.1.r17"));
+    }
+
+    #[test]
+    fn test_java_match() {
+        let versions = java_versions();
+        let tem = Some("tem");
+        assert_eq!(best(&versions, "21.0.2-zulu", None, tem).as_deref(), Some("21.0.2-zulu"));
+        assert_eq!(best(&versions, "23.ea.5-open", None, tem).as_deref(), Some("23.ea.5-open"));
+        assert_eq!(best(&versions, "21", None, tem).as_deref(), Some("21.0.3-tem"));
+        assert_eq!(best(&versions, "21", Some("graalce"), tem).as_deref(), Some("21.0.2-graalce"));
+        assert_eq!(best(&versions, "21-zulu", None, tem).as_deref(), Some("21.0.3-zulu"));
+        assert_eq!(best(&versions, "8", None, tem).as_deref(), Some("8.0.412-tem"));
+        // no Temurin build, fallback to other vendors
+        assert_eq!(best(&versions, "22", None, tem).as_deref(), Some("22.0.1-open"));
+        assert_eq!(best(&versions, "23", None, tem).as_deref(), Some("23.ea.5-open"));
+        assert_eq!(best(&versions, "latest", None, tem).as_deref(), Some("21.0.3-tem"));
+        assert_eq!(best(&versions, "2", None, tem), None);
+        assert_eq!(best(&versions, "11", None, tem), None);
+    }
+
+    #[test]
+    fn test_candidate_match() {
+        let versions = maven_versions();
+        assert_eq!(best(&versions, "3.9", None, None).as_deref(), Some("3.9.10"));
+        assert_eq!(best(&versions, "3", None, None).as_deref(), Some("3.10.0"));
+        assert_eq!(best(&versions, "latest", None, None).as_deref(), Some("3.10.0"));
+        assert_eq!(best(&versions, "4", None, None).as_deref(), Some("4.0.0-rc-1"));
+        assert_eq!(best(&versions, "3.9.9", None, None).as_deref(), Some("3.9.9"));
+    }
+}